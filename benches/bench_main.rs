@@ -0,0 +1,118 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use snowflake::SnowflakeIdGenerator;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::time::UNIX_EPOCH;
+use tagid::snowflake::GenerationStrategy;
+use tagid::{Id, Label, MachineNode, SnowflakeGenerator};
+
+const STRATEGIES: [GenerationStrategy; 3] = [
+    GenerationStrategy::RealTime,
+    GenerationStrategy::Generate,
+    GenerationStrategy::Lazy,
+];
+
+const IDS_PER_THREAD: u64 = 10_000;
+
+/// The previous mutex-guarded generator, kept as a baseline for the lock-free implementation.
+struct MutexGenerator {
+    strategy: GenerationStrategy,
+    gen: Mutex<SnowflakeIdGenerator>,
+}
+
+impl MutexGenerator {
+    fn new(strategy: GenerationStrategy) -> Self {
+        let machine_node = MachineNode::default();
+        Self {
+            strategy,
+            gen: Mutex::new(SnowflakeIdGenerator::with_epoch(
+                machine_node.machine_id,
+                machine_node.node_id,
+                UNIX_EPOCH,
+            )),
+        }
+    }
+
+    fn next_id(&self) -> i64 {
+        let mut gen = self.gen.lock().unwrap();
+        match self.strategy {
+            GenerationStrategy::RealTime => gen.real_time_generate(),
+            GenerationStrategy::Generate => gen.generate(),
+            GenerationStrategy::Lazy => gen.lazy_generate(),
+        }
+    }
+}
+
+fn generate_concurrently(threads: u64, next_id: impl Fn() -> i64 + Sync) {
+    std::thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| {
+                for _ in 0..IDS_PER_THREAD {
+                    criterion::black_box(next_id());
+                }
+            });
+        }
+    });
+}
+
+fn bench_single_thread(c: &mut Criterion) {
+    let mut group = c.benchmark_group("snowflake_single_thread");
+    for strategy in STRATEGIES {
+        let lock_free = SnowflakeGenerator::new(MachineNode::default(), strategy);
+        group.bench_function(BenchmarkId::new("lock_free", strategy), |b| {
+            b.iter(|| lock_free.next_id())
+        });
+
+        let mutex = MutexGenerator::new(strategy);
+        group.bench_function(BenchmarkId::new("mutex", strategy), |b| {
+            b.iter(|| mutex.next_id())
+        });
+    }
+    group.finish();
+}
+
+fn bench_contended(c: &mut Criterion) {
+    let threads = std::thread::available_parallelism().map_or(4, |n| n.get().max(4) as u64);
+    let mut group = c.benchmark_group(format!("snowflake_contended_{threads}_threads"));
+    group.throughput(Throughput::Elements(threads * IDS_PER_THREAD));
+    for strategy in STRATEGIES {
+        let lock_free = SnowflakeGenerator::new(MachineNode::default(), strategy);
+        group.bench_function(BenchmarkId::new("lock_free", strategy), |b| {
+            b.iter(|| generate_concurrently(threads, || lock_free.next_id()))
+        });
+
+        let mutex = MutexGenerator::new(strategy);
+        group.bench_function(BenchmarkId::new("mutex", strategy), |b| {
+            b.iter(|| generate_concurrently(threads, || mutex.next_id()))
+        });
+    }
+    group.finish();
+}
+
+#[derive(Label)]
+struct Order;
+
+fn bench_display(c: &mut Criterion) {
+    let id: Id<Order, i64> = Id::for_labeled(7_014_829_537_245_790_208);
+    let mut group = c.benchmark_group("id_display");
+    group.bench_function("format", |b| {
+        b.iter(|| format!("{}", criterion::black_box(&id)))
+    });
+    let mut buffer = String::with_capacity(64);
+    group.bench_function("write_to", |b| {
+        b.iter(|| {
+            buffer.clear();
+            criterion::black_box(&id).write_to(&mut buffer).unwrap();
+        })
+    });
+    group.bench_function("display_compact", |b| {
+        b.iter(|| {
+            buffer.clear();
+            write!(buffer, "{}", criterion::black_box(&id).display_compact()).unwrap();
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_single_thread, bench_contended, bench_display);
+criterion_main!(benches);
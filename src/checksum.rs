@@ -0,0 +1,137 @@
+//! Check digits that catch typos in ids read or typed in by people, e.g., support staff entering
+//! an id from a customer call.
+//!
+//! Letters are checked as their two-digit base-36 values (`a` and `A` are both `10`), so
+//! alphanumeric ids such as CUIDs are protected case-insensitively. Other characters, such as
+//! delimiters, are ignored.
+//!
+#![cfg_attr(all(feature = "derive", feature = "cuid"), doc = "```rust")]
+#![cfg_attr(not(all(feature = "derive", feature = "cuid")), doc = "```ignore")]
+//! use tagid::checksum::{damm, ChecksummedId};
+//! use tagid::{CuidGenerator, Entity, Label};
+//!
+//! #[derive(Label)]
+//! struct Ticket;
+//!
+//! impl Entity for Ticket {
+//!     type IdGen = ChecksummedId<CuidGenerator>;
+//! }
+//!
+//! let id = Ticket::next_id();
+//! assert!(damm::is_valid(&id.id));
+//! assert!(ChecksummedId::<CuidGenerator>::strip(&id.id).is_some());
+//! ```
+
+pub mod damm;
+pub mod luhn;
+
+use crate::id::{IdGenError, IdGenerator, TryIdGenerator};
+use smol_str::SmolStr;
+use std::fmt;
+use std::marker::PhantomData;
+
+/// A check digit algorithm.
+pub trait Checksum {
+    /// Appends the check digit to `rep`.
+    fn encode(&self, rep: &str) -> String;
+
+    /// Whether `rep` ends with a valid check digit.
+    fn is_valid(&self, rep: &str) -> bool;
+
+    /// Strips the check digit from `rep`, if it is valid.
+    fn decode<'a>(&self, rep: &'a str) -> Option<&'a str> {
+        if self.is_valid(rep) {
+            rep.get(..(rep.len() - 1))
+        } else {
+            None
+        }
+    }
+}
+
+/// The [`damm`] check digit algorithm, which detects all single-digit errors and adjacent
+/// transpositions.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Damm;
+
+impl Checksum for Damm {
+    fn encode(&self, rep: &str) -> String {
+        damm::encode(rep)
+    }
+
+    fn is_valid(&self, rep: &str) -> bool {
+        damm::is_valid(rep)
+    }
+}
+
+/// The [`luhn`] check digit algorithm, for compatibility with systems that require it.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Luhn;
+
+impl Checksum for Luhn {
+    fn encode(&self, rep: &str) -> String {
+        luhn::encode(rep)
+    }
+
+    fn is_valid(&self, rep: &str) -> bool {
+        luhn::is_valid(rep)
+    }
+}
+
+/// The decimal digits checked for `rep`, expanding letters into their two-digit base-36 values.
+fn digits(rep: &str) -> impl DoubleEndedIterator<Item = usize> + '_ {
+    rep.chars().flat_map(|c| {
+        let (tens, ones) = match c.to_digit(36) {
+            Some(value) if c.is_ascii_digit() => (None, Some(value)),
+            Some(value) => (Some(value / 10), Some(value % 10)),
+            None => (None, None),
+        };
+        tens.into_iter().chain(ones).map(|digit| digit as usize)
+    })
+}
+
+/// Generates ids via `G`, appending a check digit, [`Damm`] by default, to their display form.
+pub struct ChecksummedId<G, K = Damm> {
+    marker: PhantomData<fn() -> (G, K)>,
+}
+
+impl<G, K: Checksum + Default> ChecksummedId<G, K> {
+    /// Whether `rep` ends with a valid check digit.
+    pub fn is_valid(rep: &str) -> bool {
+        K::default().is_valid(rep)
+    }
+
+    /// Strips the check digit from `rep`, returning the id generated by `G` if the check digit is
+    /// valid.
+    pub fn strip(rep: &str) -> Option<&str> {
+        K::default().decode(rep)
+    }
+}
+
+impl<G, K> IdGenerator for ChecksummedId<G, K>
+where
+    G: IdGenerator,
+    G::IdType: fmt::Display,
+    K: Checksum + Default,
+{
+    type IdType = SmolStr;
+
+    fn next_id_rep() -> Self::IdType {
+        K::default().encode(&G::next_id_rep().to_string()).into()
+    }
+
+    fn is_available() -> bool {
+        G::is_available()
+    }
+}
+
+impl<G, K> TryIdGenerator for ChecksummedId<G, K>
+where
+    G: TryIdGenerator,
+    G::IdType: fmt::Display,
+    K: Checksum + Default,
+{
+    fn try_next_id_rep() -> Result<Self::IdType, IdGenError> {
+        let id = G::try_next_id_rep()?;
+        Ok(K::default().encode(&id.to_string()).into())
+    }
+}
@@ -0,0 +1,70 @@
+//! The [Damm algorithm](https://en.wikipedia.org/wiki/Damm_algorithm), whose single check digit
+//! detects all single-digit errors and adjacent transpositions.
+//!
+//! Letters are checked as their two-digit base-36 values, as described in [`crate::checksum`].
+
+/// Appends the check digit to `rep`.
+pub fn encode(rep: &str) -> String {
+    let mut base = rep.to_string();
+    base.push_str(checksum(rep).to_string().as_str());
+    base
+}
+
+/// Strips the check digit from `rep`, if it is valid.
+pub fn decode(rep: &str) -> Option<&str> {
+    super::Checksum::decode(&super::Damm, rep)
+}
+
+/// Whether `rep` ends with a valid check digit.
+pub fn is_valid(rep: &str) -> bool {
+    rep.ends_with(|c: char| c.is_ascii_digit()) && checksum(rep) == 0
+}
+
+const MATRIX: [[usize; 10]; 10] = [
+    [0, 3, 1, 7, 5, 9, 8, 6, 4, 2],
+    [7, 0, 9, 2, 1, 5, 4, 8, 6, 3],
+    [4, 2, 0, 6, 8, 7, 1, 3, 5, 9],
+    [1, 7, 5, 0, 9, 8, 3, 4, 2, 6],
+    [6, 1, 2, 3, 0, 4, 5, 9, 7, 8],
+    [3, 6, 7, 4, 2, 0, 9, 5, 8, 1],
+    [5, 8, 6, 9, 7, 2, 0, 1, 3, 4],
+    [8, 9, 4, 5, 3, 6, 2, 0, 1, 7],
+    [9, 4, 3, 8, 6, 1, 7, 2, 0, 5],
+    [2, 5, 8, 1, 4, 3, 6, 7, 9, 0],
+];
+
+fn checksum(rep: &str) -> usize {
+    super::digits(rep).fold(0, |interim, digit| MATRIX[interim][digit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use claim::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_damm_numeric() {
+        assert_eq!(encode("572"), "5724");
+        assert_eq!(encode("100"), "1007");
+        assert!(is_valid("5724"));
+        assert!(!is_valid("5734"));
+        assert!(!is_valid("7524"));
+        assert_eq!(assert_some!(decode("5724")), "572");
+        assert_none!(decode("5725"));
+        assert_none!(decode(""));
+    }
+
+    #[test]
+    fn test_damm_alphanumeric() {
+        let cuid = "clh3am8ks0000s6qwu1ja8bq3";
+        let checked = encode(cuid);
+        assert!(is_valid(&checked));
+        assert!(is_valid(&checked.to_uppercase()));
+        assert_eq!(assert_some!(decode(&checked)), cuid);
+
+        assert!(!is_valid(&checked.replacen('h', "k", 1)));
+        assert!(!is_valid(&checked.replacen("am", "ma", 1)));
+        assert!(!is_valid("-"));
+    }
+}
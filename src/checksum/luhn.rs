@@ -0,0 +1,65 @@
+//! The [Luhn algorithm](https://en.wikipedia.org/wiki/Luhn_algorithm), as used for credit card
+//! numbers. It detects all single-digit errors and most adjacent transpositions.
+//!
+//! Letters are checked as their two-digit base-36 values, as described in [`crate::checksum`].
+
+/// Appends the check digit to `rep`.
+pub fn encode(rep: &str) -> String {
+    let mut base = rep.to_string();
+    base.push_str(((10 - sum(rep, false) % 10) % 10).to_string().as_str());
+    base
+}
+
+/// Strips the check digit from `rep`, if it is valid.
+pub fn decode(rep: &str) -> Option<&str> {
+    super::Checksum::decode(&super::Luhn, rep)
+}
+
+/// Whether `rep` ends with a valid check digit.
+pub fn is_valid(rep: &str) -> bool {
+    rep.ends_with(|c: char| c.is_ascii_digit()) && sum(rep, true).is_multiple_of(10)
+}
+
+/// Sums the digits of `rep`, doubling every second digit from the right. The rightmost digit is
+/// doubled unless `rep` already ends with its check digit.
+fn sum(rep: &str, has_check_digit: bool) -> usize {
+    super::digits(rep)
+        .rev()
+        .enumerate()
+        .map(|(position, digit)| {
+            let doubled = position.is_multiple_of(2) != has_check_digit;
+            match digit * (1 + usize::from(doubled)) {
+                d if 9 < d => d - 9,
+                d => d,
+            }
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use claim::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_luhn_numeric() {
+        assert_eq!(encode("7992739871"), "79927398713");
+        assert_eq!(encode("0"), "00");
+        assert!(is_valid("79927398713"));
+        assert!(is_valid("4539 1488 0343 6467"));
+        assert!(!is_valid("79927398714"));
+        assert!(!is_valid("79927389713"));
+        assert_eq!(assert_some!(decode("79927398713")), "7992739871");
+        assert_none!(decode(""));
+    }
+
+    #[test]
+    fn test_luhn_alphanumeric() {
+        let cuid = "clh3am8ks0000s6qwu1ja8bq3";
+        let checked = encode(cuid);
+        assert!(is_valid(&checked));
+        assert!(is_valid(&checked.to_uppercase()));
+        assert!(!is_valid(&checked.replacen('h', "k", 1)));
+    }
+}
@@ -0,0 +1,199 @@
+//! The source of the current time for envelope timestamps and time-ordered ids.
+//!
+//! Everything in the crate that reads the time does so via [`now()`], which consults the clock
+//! installed for the current thread, then the global clock, before falling back to the system
+//! clock. Tests install a deterministic clock, such as `testing::FixedClock` or
+//! `testing::StepClock` (with the `testing` feature), so received timestamps and time-ordered ids
+//! are reproducible:
+//!
+//! ```rust
+//! use std::time::{Duration, SystemTime};
+//! use tagid::clock::{self, Clock};
+//!
+//! struct Epoch;
+//!
+//! impl Clock for Epoch {
+//!     fn now(&self) -> SystemTime {
+//!         SystemTime::UNIX_EPOCH
+//!     }
+//! }
+//!
+//! {
+//!     let _guard = clock::install(Epoch);
+//!     assert_eq!(clock::now(), SystemTime::UNIX_EPOCH);
+//! }
+//! assert!(SystemTime::UNIX_EPOCH + Duration::from_secs(1) < clock::now());
+//! ```
+//!
+//! **Clocks installed via [`install()`] only apply to the installing thread.** As with
+//! `testing::MockSequence`s, this keeps tests running in parallel from interfering, but the clock
+//! does not reach spawned threads or tasks on a multi-threaded runtime. Use [`install_global()`]
+//! to override the time for every thread:
+//!
+//! ```rust
+//! use std::time::SystemTime;
+//! use tagid::clock::{self, Clock};
+//!
+//! struct Epoch;
+//!
+//! impl Clock for Epoch {
+//!     fn now(&self) -> SystemTime {
+//!         SystemTime::UNIX_EPOCH
+//!     }
+//! }
+//!
+//! let _guard = clock::install_global(Epoch);
+//! let spawned = std::thread::spawn(clock::now).join().unwrap();
+//! assert_eq!(spawned, SystemTime::UNIX_EPOCH);
+//! ```
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::{Arc, RwLock};
+use std::time::SystemTime;
+
+static GLOBAL_CLOCK: RwLock<Option<Arc<dyn Clock>>> = RwLock::new(None);
+
+thread_local! {
+    static CLOCK: RefCell<Option<Rc<dyn Clock>>> = RefCell::new(None);
+}
+
+pub trait Clock: Send + Sync {
+    fn now(&self) -> SystemTime;
+}
+
+/// Reads the system time.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+impl<C: Clock + ?Sized> Clock for Arc<C> {
+    fn now(&self) -> SystemTime {
+        (**self).now()
+    }
+}
+
+/// The current time according to the clock installed for the current thread, else the global
+/// clock, else the system time.
+pub fn now() -> SystemTime {
+    let clock = CLOCK
+        .try_with(|clock| clock.borrow().clone())
+        .ok()
+        .flatten();
+    if let Some(clock) = clock {
+        return clock.now();
+    }
+
+    let global = GLOBAL_CLOCK.read().map_or_else(
+        |poisoned| poisoned.into_inner().clone(),
+        |clock| clock.clone(),
+    );
+    global.map_or_else(SystemTime::now, |clock| clock.now())
+}
+
+#[cfg(feature = "envelope")]
+pub(crate) fn now_timestamp() -> iso8601_timestamp::Timestamp {
+    now().into()
+}
+
+/// Installs the clock for the current thread until the returned guard is dropped, at which point
+/// any previously installed clock is restored.
+#[must_use = "the clock is uninstalled when the guard is dropped"]
+pub fn install(clock: impl Clock + 'static) -> ClockGuard {
+    let clock: Rc<dyn Clock> = Rc::new(clock);
+    let previous = CLOCK.with(|current| current.borrow_mut().replace(clock));
+    ClockGuard { previous }
+}
+
+/// Installs the clock for every thread until the returned guard is dropped.
+///
+/// Dropping the guard restores any previously installed global clock. Clocks installed per thread
+/// via [`install()`] take precedence.
+#[must_use = "the clock is uninstalled when the guard is dropped"]
+pub fn install_global(clock: impl Clock + 'static) -> GlobalClockGuard {
+    let clock: Arc<dyn Clock> = Arc::new(clock);
+    let previous = swap_global(Some(clock));
+    GlobalClockGuard { previous }
+}
+
+fn swap_global(clock: Option<Arc<dyn Clock>>) -> Option<Arc<dyn Clock>> {
+    let mut global = GLOBAL_CLOCK
+        .write()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    std::mem::replace(&mut *global, clock)
+}
+
+/// Uninstalls a global [`Clock`] when dropped, restoring the global clock it replaced.
+pub struct GlobalClockGuard {
+    previous: Option<Arc<dyn Clock>>,
+}
+
+impl std::fmt::Debug for GlobalClockGuard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GlobalClockGuard").finish_non_exhaustive()
+    }
+}
+
+impl Drop for GlobalClockGuard {
+    fn drop(&mut self) {
+        swap_global(self.previous.take());
+    }
+}
+
+/// Uninstalls a [`Clock`] when dropped, restoring the clock it replaced.
+pub struct ClockGuard {
+    previous: Option<Rc<dyn Clock>>,
+}
+
+impl std::fmt::Debug for ClockGuard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ClockGuard").finish_non_exhaustive()
+    }
+}
+
+impl Drop for ClockGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        // ignore drops during thread teardown, when the thread-local may already be destroyed
+        let _ = CLOCK.try_with(|current| *current.borrow_mut() = previous);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::time::Duration;
+
+    struct At(u64);
+
+    impl Clock for At {
+        fn now(&self) -> SystemTime {
+            SystemTime::UNIX_EPOCH + Duration::from_secs(self.0)
+        }
+    }
+
+    #[test]
+    fn test_installed_clock() {
+        let at = |secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs);
+
+        let outer = install(At(1));
+        assert_eq!(now(), at(1));
+        {
+            let _inner = install(Arc::new(At(2)));
+            assert_eq!(now(), at(2));
+            std::thread::spawn(move || assert!(at(2) < now()))
+                .join()
+                .unwrap();
+        }
+        assert_eq!(now(), at(1));
+
+        drop(outer);
+        assert!(at(2) < now());
+    }
+}
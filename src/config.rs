@@ -0,0 +1,371 @@
+//! Configuration of the crate's global id generation state from application settings.
+//!
+//! [`TagIdConfig`] deserializes from any serde format (YAML, TOML, JSON, ...), so services can
+//! configure id generation alongside the rest of their settings and [`apply()`] it at startup:
+//!
+#![cfg_attr(feature = "uuid", doc = "```rust")]
+#![cfg_attr(not(feature = "uuid"), doc = "```ignore")]
+//! use tagid::config::{self, GeneratorKind, TagIdConfig};
+//!
+//! let config: TagIdConfig = serde_json::from_str(r#"{ "default_generator": "uuid_v7" }"#).unwrap();
+//! config::apply(&config).unwrap();
+//! assert_eq!(config::default_generator(), Some(GeneratorKind::UuidV7));
+//! ```
+
+#[cfg(feature = "snowflake")]
+use crate::snowflake::pretty::{AlphabetCodec, IdPrettifier, BASE_23};
+#[cfg(feature = "snowflake")]
+use crate::snowflake::{ClockDriftPolicy, GenerationStrategy};
+#[cfg(all(feature = "ulid", feature = "sqlx"))]
+use crate::UlidStorage;
+#[cfg(feature = "snowflake")]
+use crate::{MachineNode, SnowflakeGenerator};
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "snowflake")]
+use std::time::{Duration, SystemTime};
+use thiserror::Error;
+#[cfg(feature = "snowflake")]
+use validator::{Validate, ValidationError};
+
+pub use self::generator_kind::GeneratorKind;
+
+static DEFAULT_GENERATOR: OnceCell<GeneratorKind> = OnceCell::new();
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("{0} is already initialized with a different configuration")]
+    AlreadyInitialized(&'static str),
+
+    #[error("invalid configuration: {0}")]
+    Invalid(#[from] validator::ValidationErrors),
+}
+
+mod generator_kind {
+    // Without the uuid feature the enum is empty, so its derived parsing is unreachable.
+    #![allow(unreachable_code)]
+
+    use serde::{Deserialize, Serialize};
+    use strum_macros::{Display, EnumString, IntoStaticStr, VariantNames};
+
+    /// The generators [`DefaultIdGenerator`](crate::DefaultIdGenerator) can be configured to use.
+    ///
+    /// Only the alternatives for id types with more than one generator are listed, so every
+    /// configurable kind changes which ids are generated.
+    #[derive(
+        Debug,
+        Copy,
+        Clone,
+        PartialEq,
+        Eq,
+        Hash,
+        Display,
+        IntoStaticStr,
+        EnumString,
+        VariantNames,
+        Serialize,
+        Deserialize,
+    )]
+    #[serde(rename_all = "snake_case")]
+    #[strum(serialize_all = "snake_case")]
+    pub enum GeneratorKind {
+        /// Random (v4) UUIDs.
+        #[cfg(feature = "uuid")]
+        Uuid,
+
+        /// Time-ordered (v7) UUIDs.
+        #[cfg(feature = "uuid")]
+        UuidV7,
+    }
+}
+
+/// Settings for the crate's global id generation state.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TagIdConfig {
+    #[cfg(feature = "snowflake")]
+    pub snowflake: Option<SnowflakeConfig>,
+
+    #[cfg(feature = "snowflake")]
+    pub prettifier: Option<PrettifierConfig>,
+
+    /// How ULIDs are stored via sqlx, applied via [`UlidStorage::try_init()`].
+    #[cfg(all(feature = "ulid", feature = "sqlx"))]
+    pub ulid_storage: Option<UlidStorage>,
+
+    /// The generator [`DefaultIdGenerator`](crate::DefaultIdGenerator) uses, available via
+    /// [`default_generator()`] once applied.
+    pub default_generator: Option<GeneratorKind>,
+}
+
+/// Settings for the global [`SnowflakeGenerator`], validated and applied via
+/// [`SnowflakeGenerator::try_init()`].
+#[cfg(feature = "snowflake")]
+#[derive(Debug, Clone, PartialEq, Eq, Validate, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SnowflakeConfig {
+    #[validate(range(min = 0, max = 31))]
+    pub machine_id: i32,
+
+    #[validate(range(min = 0, max = 31))]
+    pub node_id: i32,
+
+    pub strategy: GenerationStrategy,
+
+    /// Milliseconds since the UNIX epoch from which snowflake timestamps are measured.
+    pub epoch_millis: u64,
+
+    pub drift_policy: ClockDriftPolicy,
+}
+
+#[cfg(feature = "snowflake")]
+impl Default for SnowflakeConfig {
+    fn default() -> Self {
+        let machine_node = MachineNode::default();
+        Self {
+            machine_id: machine_node.machine_id,
+            node_id: machine_node.node_id,
+            strategy: GenerationStrategy::RealTime,
+            epoch_millis: 0,
+            drift_policy: ClockDriftPolicy::default(),
+        }
+    }
+}
+
+#[cfg(feature = "snowflake")]
+impl SnowflakeConfig {
+    pub const fn machine_node(&self) -> MachineNode {
+        MachineNode {
+            machine_id: self.machine_id,
+            node_id: self.node_id,
+        }
+    }
+
+    pub fn epoch(&self) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_millis(self.epoch_millis)
+    }
+}
+
+/// Settings for the global pretty snowflake [`IdPrettifier`], validated and applied via
+/// [`IdPrettifier::try_init()`].
+#[cfg(feature = "snowflake")]
+#[derive(Debug, Clone, PartialEq, Eq, Validate, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PrettifierConfig {
+    /// Characters used to encode parts of the id; must be at least two distinct characters.
+    #[validate(custom(function = "validate_alphabet"))]
+    pub alphabet: String,
+
+    /// The number of digits in each part of the id.
+    #[validate(range(min = 1, max = 18))]
+    pub parts_size: usize,
+
+    /// Separator placed between parts of the id.
+    #[validate(length(min = 1))]
+    pub delimiter: String,
+
+    /// Whether ids are padded to a constant length.
+    pub leading_zeros: bool,
+}
+
+#[cfg(feature = "snowflake")]
+impl Default for PrettifierConfig {
+    fn default() -> Self {
+        Self {
+            alphabet: BASE_23.elements.clone(),
+            parts_size: 5,
+            delimiter: '-'.to_string(),
+            leading_zeros: true,
+        }
+    }
+}
+
+#[cfg(feature = "snowflake")]
+fn validate_alphabet(alphabet: &str) -> Result<(), ValidationError> {
+    let mut unique: Vec<char> = alphabet.chars().collect();
+    unique.sort_unstable();
+    unique.dedup();
+    if 2 <= unique.len() && unique.len() == alphabet.chars().count() {
+        Ok(())
+    } else {
+        Err(ValidationError::new("alphabet")
+            .with_message("alphabet must contain at least two distinct characters".into()))
+    }
+}
+
+/// Initializes the crate's global id generation state from the configuration.
+///
+/// Sections left unset are not initialized. Applying the same configuration more than once is
+/// harmless, but a section that conflicts with previously initialized state results in
+/// [`ConfigError::AlreadyInitialized`]. Every section is checked before any is initialized, so
+/// an invalid or conflicting configuration leaves the global state untouched.
+pub fn apply(config: &TagIdConfig) -> Result<(), ConfigError> {
+    check(config)?;
+
+    #[cfg(feature = "snowflake")]
+    if let Some(snowflake) = &config.snowflake {
+        SnowflakeGenerator::try_init(snowflake)?;
+    }
+
+    #[cfg(feature = "snowflake")]
+    if let Some(prettifier) = &config.prettifier {
+        IdPrettifier::<AlphabetCodec>::try_init(prettifier)?;
+    }
+
+    #[cfg(all(feature = "ulid", feature = "sqlx"))]
+    if let Some(storage) = config.ulid_storage {
+        UlidStorage::try_init(storage)?;
+    }
+
+    if let Some(kind) = config.default_generator {
+        set_default_generator(kind)?;
+    }
+
+    Ok(())
+}
+
+/// Validates each section and checks it against previously initialized state.
+fn check(config: &TagIdConfig) -> Result<(), ConfigError> {
+    #[cfg(feature = "snowflake")]
+    if let Some(snowflake) = &config.snowflake {
+        SnowflakeGenerator::check_init(snowflake)?;
+    }
+
+    #[cfg(feature = "snowflake")]
+    if let Some(prettifier) = &config.prettifier {
+        IdPrettifier::<AlphabetCodec>::check_init(prettifier)?;
+    }
+
+    #[cfg(all(feature = "ulid", feature = "sqlx"))]
+    if let Some(storage) = config.ulid_storage {
+        UlidStorage::check_init(storage)?;
+    }
+
+    if let Some(kind) = config.default_generator {
+        if default_generator().is_some_and(|current| current != kind) {
+            return Err(ConfigError::AlreadyInitialized("default generator"));
+        }
+    }
+
+    Ok(())
+}
+
+/// Selects the generator behind [`DefaultIdGenerator`](crate::DefaultIdGenerator), returning
+/// [`ConfigError::AlreadyInitialized`] if a different one was already selected.
+pub fn set_default_generator(kind: GeneratorKind) -> Result<(), ConfigError> {
+    if *DEFAULT_GENERATOR.get_or_init(|| kind) != kind {
+        return Err(ConfigError::AlreadyInitialized("default generator"));
+    }
+    Ok(())
+}
+
+/// The default generator selected via [`apply()`] or [`set_default_generator()`], if any.
+pub fn default_generator() -> Option<GeneratorKind> {
+    DEFAULT_GENERATOR.get().copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use claim::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_config_deserialize_defaults() {
+        let config: TagIdConfig = assert_ok!(serde_json::from_str("{}"));
+        assert_eq!(config, TagIdConfig::default());
+        assert_ok!(apply(&config));
+    }
+
+    #[cfg(feature = "snowflake")]
+    #[test]
+    fn test_config_deserialize_sections() {
+        let config: TagIdConfig = assert_ok!(serde_json::from_str(
+            r#"{
+                "snowflake": { "machine_id": 3, "node_id": 7, "strategy": "Lazy", "drift_policy": "WaitForClock" },
+                "prettifier": { "parts_size": 4 }
+            }"#
+        ));
+
+        let snowflake = assert_some!(config.snowflake);
+        assert_eq!(snowflake.machine_id, 3);
+        assert_eq!(snowflake.node_id, 7);
+        assert_eq!(snowflake.strategy, GenerationStrategy::Lazy);
+        assert_eq!(snowflake.epoch(), SystemTime::UNIX_EPOCH);
+        assert_eq!(snowflake.drift_policy, ClockDriftPolicy::WaitForClock);
+
+        let prettifier = assert_some!(config.prettifier);
+        assert_eq!(prettifier.alphabet, BASE_23.elements);
+        assert_eq!(prettifier.parts_size, 4);
+    }
+
+    #[cfg(feature = "snowflake")]
+    #[test]
+    fn test_apply_rejects_invalid_sections() {
+        let config = TagIdConfig {
+            snowflake: Some(SnowflakeConfig {
+                machine_id: 32,
+                ..SnowflakeConfig::default()
+            }),
+            ..TagIdConfig::default()
+        };
+        assert_matches!(apply(&config), Err(ConfigError::Invalid(_)));
+
+        let config = TagIdConfig {
+            prettifier: Some(PrettifierConfig {
+                alphabet: "AAB".to_string(),
+                ..PrettifierConfig::default()
+            }),
+            ..TagIdConfig::default()
+        };
+        assert_matches!(apply(&config), Err(ConfigError::Invalid(_)));
+
+        let config = TagIdConfig {
+            prettifier: Some(PrettifierConfig {
+                parts_size: 0,
+                ..PrettifierConfig::default()
+            }),
+            #[cfg(feature = "uuid")]
+            default_generator: Some(GeneratorKind::Uuid),
+            ..TagIdConfig::default()
+        };
+        assert_matches!(apply(&config), Err(ConfigError::Invalid(_)));
+        #[cfg(feature = "uuid")]
+        assert_ne!(default_generator(), Some(GeneratorKind::Uuid));
+
+        let config = PrettifierConfig {
+            parts_size: 19,
+            delimiter: String::new(),
+            ..PrettifierConfig::default()
+        };
+        let errors = assert_err!(config.validate());
+        let fields = errors.field_errors();
+        assert!(fields.contains_key("parts_size"));
+        assert!(fields.contains_key("delimiter"));
+        assert!(!fields.contains_key("alphabet"));
+    }
+
+    #[test]
+    fn test_config_rejects_kinds_default_generator_cannot_use() {
+        for kind in ["cuid", "ksuid", "ulid", "snowflake", "pretty_snowflake"] {
+            let json = format!(r#"{{ "default_generator": "{kind}" }}"#);
+            assert_err!(serde_json::from_str::<TagIdConfig>(&json));
+            assert_err!(kind.parse::<GeneratorKind>());
+        }
+
+        #[cfg(feature = "uuid")]
+        assert_eq!(
+            assert_ok!("uuid_v7".parse::<GeneratorKind>()),
+            GeneratorKind::UuidV7
+        );
+    }
+
+    #[cfg(all(feature = "ulid", feature = "sqlx"))]
+    #[test]
+    fn test_config_ulid_storage() {
+        let config: TagIdConfig = assert_ok!(serde_json::from_str(r#"{ "ulid_storage": "text" }"#));
+        assert_eq!(config.ulid_storage, Some(UlidStorage::Text));
+        assert_ok!(apply(&config));
+        assert_eq!(UlidStorage::current(), UlidStorage::Text);
+    }
+}
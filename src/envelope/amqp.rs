@@ -0,0 +1,246 @@
+//! Conversions between envelope metadata and AMQP basic properties, so [`lapin`] publishers and
+//! consumers can round-trip envelopes through RabbitMQ.
+//!
+//! The correlation id travels in the `correlation-id` property, the message id in the
+//! `message-id` property and the received timestamp in the `timestamp` property. AMQP timestamps have second precision, so the exact received
+//! timestamp and the causation id travel in the `recv_timestamp` and `causation_id` entries of
+//! the headers table, along with the custom metadata entries, including projected
+//! [`Extensions`](crate::envelope::Extensions).
+//!
+//! ```rust
+//! use tagid::envelope::{Correlation, MetaData};
+//! use tagid::{CuidGenerator, Entity, Label};
+//!
+//! #[derive(Label)]
+//! struct Order;
+//! impl Entity for Order { type IdGen = CuidGenerator; }
+//!
+//! let metadata = MetaData::<Order, _>::default();
+//! let properties = metadata.to_amqp_properties();
+//! assert_eq!(
+//!     properties.correlation_id().as_ref().map(|id| id.as_str()),
+//!     Some(metadata.correlation().id.as_str())
+//! );
+//!
+//! let extracted = MetaData::<Order, String>::from_amqp_properties(&properties).unwrap();
+//! assert_eq!(extracted, metadata);
+//! ```
+
+use crate::clock;
+use crate::envelope::metadata::{
+    CAUSATION_ID_KEY, CORRELATION_ID_KEY, MESSAGE_ID_KEY, RECV_TIMESTAMP_KEY,
+};
+use crate::envelope::{Correlation, MetaData, MetaHeaderError, ReceivedAt};
+use crate::{Id, Label};
+use iso8601_timestamp::{Duration, Timestamp};
+use lapin::types::{AMQPValue, LongString};
+use lapin::BasicProperties;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+impl<T, ID> MetaData<T, ID>
+where
+    ID: fmt::Display,
+{
+    pub fn to_amqp_properties(&self) -> BasicProperties {
+        self.apply_to_amqp_properties(BasicProperties::default())
+    }
+
+    /// Sets the metadata on the `properties`, e.g., along with the message content type, merging
+    /// into any existing headers table.
+    pub fn apply_to_amqp_properties(&self, properties: BasicProperties) -> BasicProperties {
+        let mut headers = properties.headers().clone().unwrap_or_default();
+        let mut insert = |key: &str, value: String| {
+            headers.insert(key.into(), AMQPValue::LongString(LongString::from(value)));
+        };
+
+        if let Some(causation_id) = self.causation_id() {
+            insert(CAUSATION_ID_KEY, causation_id.id.to_string());
+        }
+        insert(RECV_TIMESTAMP_KEY, self.recv_timestamp().to_string());
+
+        let mut custom = self.custom().clone();
+        custom.extend(self.extensions().projection());
+        for (key, value) in custom {
+            insert(&key, value);
+        }
+
+        let seconds = self
+            .recv_timestamp()
+            .duration_since(Timestamp::UNIX_EPOCH)
+            .whole_seconds();
+        properties
+            .with_correlation_id(self.correlation().id.to_string().into())
+            .with_message_id(self.message_id().id.to_string().into())
+            .with_timestamp(u64::try_from(seconds).unwrap_or_default())
+            .with_headers(headers)
+    }
+}
+
+impl<T, ID> MetaData<T, ID>
+where
+    T: Label,
+    ID: FromStr,
+    <ID as FromStr>::Err: fmt::Display,
+{
+    /// Extracts the metadata from AMQP basic properties, which must include the correlation id.
+    /// The received timestamp falls back to the `timestamp` property, then to now, and only
+    /// string entries of the headers table are taken as custom metadata.
+    pub fn from_amqp_properties(properties: &BasicProperties) -> Result<Self, MetaHeaderError> {
+        let mut custom = HashMap::new();
+        if let Some(headers) = properties.headers() {
+            for (key, value) in headers.inner() {
+                let value = match value {
+                    AMQPValue::LongString(value) => std::str::from_utf8(value.as_bytes())
+                        .map_err(|err| MetaHeaderError::invalid(key.as_str(), err))?,
+                    AMQPValue::ShortString(value) => value.as_str(),
+                    _ => continue,
+                };
+                custom.insert(key.to_string(), value.to_string());
+            }
+        }
+
+        let id = |key: &'static str, rep: &str| {
+            ID::from_str(rep)
+                .map(Id::for_labeled)
+                .map_err(|err| MetaHeaderError::invalid(key, err))
+        };
+        let correlation_id = properties
+            .correlation_id()
+            .as_ref()
+            .ok_or(MetaHeaderError::Missing(CORRELATION_ID_KEY))
+            .and_then(|rep| id(CORRELATION_ID_KEY, rep.as_str()))?;
+        let message_id = properties
+            .message_id()
+            .as_ref()
+            .map(|rep| id(MESSAGE_ID_KEY, rep.as_str()))
+            .transpose()?;
+        let causation_id = custom
+            .remove(CAUSATION_ID_KEY)
+            .map(|rep| id(CAUSATION_ID_KEY, &rep))
+            .transpose()?;
+        let recv_timestamp = match custom.remove(RECV_TIMESTAMP_KEY) {
+            Some(rep) => Timestamp::parse(&rep).ok_or_else(|| {
+                MetaHeaderError::invalid(RECV_TIMESTAMP_KEY, "not an ISO 8601 timestamp")
+            })?,
+            None => properties
+                .timestamp()
+                .and_then(|seconds| i64::try_from(seconds).ok())
+                .and_then(|seconds| Timestamp::UNIX_EPOCH.checked_add(Duration::seconds(seconds)))
+                .unwrap_or_else(clock::now_timestamp),
+        };
+
+        let custom = if custom.is_empty() {
+            None
+        } else {
+            Some(custom)
+        };
+        let mut metadata = Self::from_parts(correlation_id, recv_timestamp, custom);
+        if let Some(message_id) = message_id {
+            metadata = metadata.with_message_id(message_id);
+        }
+        Ok(match causation_id {
+            Some(causation_id) => metadata.with_causation_id(causation_id),
+            None => metadata,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::envelope::Envelope;
+    use crate::id::IdGenerator;
+    use crate::CustomLabeling;
+    use claim::*;
+    use lapin::types::FieldTable;
+    use pretty_assertions::assert_eq;
+
+    struct Order;
+
+    impl Label for Order {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("Order")
+        }
+    }
+
+    struct ReplyGenerator;
+
+    impl IdGenerator for ReplyGenerator {
+        type IdType = u64;
+
+        fn next_id_rep() -> Self::IdType {
+            18
+        }
+    }
+
+    #[test]
+    fn test_metadata_amqp_roundtrip() {
+        let ts = Timestamp::parse("2022-11-30T03:43:18.068Z").unwrap();
+        let mut custom = HashMap::new();
+        custom.insert("tenant".to_string(), "acme".to_string());
+        let command = Envelope::from_parts(
+            MetaData::from_parts(Id::<Order, u64>::for_labeled(17), ts, Some(custom)),
+            Order,
+        );
+        let metadata = Envelope::caused_by::<ReplyGenerator, _>(Order, &command)
+            .metadata()
+            .clone()
+            .with_recv_timestamp(ts);
+
+        let mut headers = FieldTable::default();
+        headers.insert("x-death".into(), AMQPValue::LongLongInt(1));
+        let properties = BasicProperties::default()
+            .with_content_type("application/json".into())
+            .with_headers(headers);
+        let properties = metadata.apply_to_amqp_properties(properties);
+        assert_eq!(
+            properties.content_type().as_ref().map(|t| t.as_str()),
+            Some("application/json")
+        );
+        assert_eq!(properties.correlation_id().as_ref().unwrap().as_str(), "17");
+        assert_eq!(properties.message_id().as_ref().unwrap().as_str(), "18");
+        assert_eq!(*properties.timestamp(), Some(1_669_779_798));
+
+        let actual = assert_ok!(MetaData::<Order, u64>::from_amqp_properties(&properties));
+        assert_eq!(actual, metadata);
+        assert_eq!(actual.recv_timestamp(), ts);
+        assert_eq!(actual.message_id().id, 18);
+        assert_eq!(actual.causation_id().map(|id| id.id), Some(17));
+        assert!(actual.custom().is_empty());
+
+        let properties = command.metadata().to_amqp_properties();
+        let actual = assert_ok!(MetaData::<Order, u64>::from_amqp_properties(&properties));
+        assert_eq!(actual.message_id(), actual.correlation());
+        assert_eq!(actual.causation_id(), None);
+        assert_eq!(actual.custom()["tenant"], "acme");
+    }
+
+    #[test]
+    fn test_metadata_amqp_properties_fallbacks() {
+        assert_eq!(
+            assert_err!(MetaData::<Order, u64>::from_amqp_properties(
+                &BasicProperties::default()
+            )),
+            MetaHeaderError::Missing(CORRELATION_ID_KEY)
+        );
+
+        let properties = BasicProperties::default()
+            .with_correlation_id("17".into())
+            .with_timestamp(1_669_779_798);
+        let actual = assert_ok!(MetaData::<Order, u64>::from_amqp_properties(&properties));
+        assert_eq!(
+            actual.recv_timestamp(),
+            Timestamp::parse("2022-11-30T03:43:18Z").unwrap()
+        );
+
+        let properties = properties.with_correlation_id("seventeen".into());
+        assert_matches!(
+            MetaData::<Order, u64>::from_amqp_properties(&properties),
+            Err(MetaHeaderError::Invalid { name, .. }) if name == CORRELATION_ID_KEY
+        );
+    }
+}
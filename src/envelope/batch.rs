@@ -0,0 +1,231 @@
+use crate::envelope::{Correlation, Envelope, MetaData, ReceivedAt};
+use crate::id::IdGenerator;
+use crate::{Entity, Id, Label, Labeling};
+use iso8601_timestamp::Timestamp;
+use serde::{de, Deserialize, Serialize};
+use std::fmt;
+
+/// A batch of items sharing one metadata block, so bulk consumers need not carry a copy of the
+/// metadata per item.
+///
+/// ```rust
+/// use tagid::envelope::{Correlation, Envelope, EnvelopeBatch};
+/// use tagid::{CuidGenerator, Entity, Label};
+///
+/// #[derive(Debug, Label)]
+/// struct Order(u32);
+/// impl Entity for Order { type IdGen = CuidGenerator; }
+///
+/// let envelopes = vec![Envelope::from_entity(Order(1)), Envelope::from_entity(Order(2))];
+/// let batch = EnvelopeBatch::from_envelopes(envelopes).unwrap();
+/// assert_eq!(batch.len(), 2);
+///
+/// let batch_id = batch.metadata().correlation().clone();
+/// let envelopes = batch.split();
+/// for envelope in &envelopes {
+///     assert_eq!(envelope.metadata().correlation(), &batch_id);
+/// }
+/// assert_ne!(envelopes[0].metadata().message_id(), envelopes[1].metadata().message_id());
+/// ```
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "T: Serialize, ID: Serialize",
+    deserialize = "T: Label + de::DeserializeOwned, ID: de::DeserializeOwned"
+))]
+pub struct EnvelopeBatch<T, ID> {
+    metadata: MetaData<T, ID>,
+    items: Vec<T>,
+}
+
+impl<T, ID> EnvelopeBatch<T, ID> {
+    pub const fn from_parts(metadata: MetaData<T, ID>, items: Vec<T>) -> Self {
+        Self { metadata, items }
+    }
+
+    /// Merges the envelopes into one batch, combining their metadata as the envelope semigroup
+    /// does, i.e., the metadata received last wins. Returns `None` if there are no envelopes.
+    pub fn from_envelopes(envelopes: impl IntoIterator<Item = Envelope<T, ID>>) -> Option<Self>
+    where
+        ID: PartialOrd,
+    {
+        let mut envelopes = envelopes.into_iter();
+        let (metadata, item) = envelopes.next()?.into_parts();
+        let mut items = Vec::with_capacity(envelopes.size_hint().0 + 1);
+        items.push(item);
+
+        let metadata = envelopes.fold(metadata, |acc, envelope| {
+            let (metadata, item) = envelope.into_parts();
+            items.push(item);
+            acc + metadata
+        });
+
+        Some(Self { metadata, items })
+    }
+
+    pub const fn metadata(&self) -> &MetaData<T, ID> {
+        &self.metadata
+    }
+
+    pub const fn items(&self) -> &[T] {
+        self.items.as_slice()
+    }
+
+    pub const fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn push(&mut self, item: T) {
+        self.items.push(item);
+    }
+
+    #[allow(clippy::missing_const_for_fn)]
+    pub fn into_parts(self) -> (MetaData<T, ID>, Vec<T>) {
+        (self.metadata, self.items)
+    }
+}
+
+impl<T: Label, ID: Clone> EnvelopeBatch<T, ID> {
+    /// Splits the batch into an envelope per item. Each envelope gets a clone of the batch
+    /// metadata, keeping the batch correlation id, under a fresh message id generated via `G`.
+    pub fn split_with<G>(self) -> Vec<Envelope<T, ID>>
+    where
+        G: IdGenerator<IdType = ID>,
+    {
+        let labeler = T::labeler();
+        let metadata = self.metadata;
+
+        self.items
+            .into_iter()
+            .map(|item| {
+                let message_id = Id::direct_labeled(labeler.label(), G::next_id_rep());
+                Envelope::from_parts(metadata.clone().with_message_id(message_id), item)
+            })
+            .collect()
+    }
+}
+
+impl<E> EnvelopeBatch<E, <<E as Entity>::IdGen as IdGenerator>::IdType>
+where
+    E: Entity,
+    <<E as Entity>::IdGen as IdGenerator>::IdType: Clone,
+{
+    /// Splits the batch into an envelope per item, generating fresh message ids for the entity.
+    pub fn split(self) -> Vec<Envelope<E, <<E as Entity>::IdGen as IdGenerator>::IdType>> {
+        self.split_with::<E::IdGen>()
+    }
+}
+
+impl<T, ID> Correlation for EnvelopeBatch<T, ID> {
+    type Correlated = T;
+    type IdType = ID;
+
+    fn correlation(&self) -> &Id<Self::Correlated, Self::IdType> {
+        self.metadata.correlation()
+    }
+}
+
+impl<T, ID> ReceivedAt for EnvelopeBatch<T, ID> {
+    fn recv_timestamp(&self) -> Timestamp {
+        self.metadata.recv_timestamp()
+    }
+}
+
+impl<T, ID> fmt::Debug for EnvelopeBatch<T, ID>
+where
+    T: fmt::Debug,
+    ID: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EnvelopeBatch")
+            .field("metadata", &self.metadata)
+            .field("items", &self.items)
+            .finish()
+    }
+}
+
+impl<T, ID> IntoIterator for EnvelopeBatch<T, ID> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CustomLabeling;
+    use claim::*;
+    use pretty_assertions::assert_eq;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Order(u32);
+
+    impl Label for Order {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("Order")
+        }
+    }
+
+    static NEXT_ID: AtomicU64 = AtomicU64::new(100);
+
+    struct CountingGenerator;
+
+    impl IdGenerator for CountingGenerator {
+        type IdType = u64;
+
+        fn next_id_rep() -> Self::IdType {
+            NEXT_ID.fetch_add(1, Ordering::Relaxed)
+        }
+    }
+
+    #[test]
+    fn test_envelope_batch() {
+        assert_none!(EnvelopeBatch::<Order, u64>::from_envelopes(Vec::new()));
+
+        let ts = |rep: &str| Timestamp::parse(rep).unwrap();
+        let envelope = |id: u64, recv: &str, item: u32| {
+            let metadata = MetaData::from_parts(Id::for_labeled(id), ts(recv), None);
+            Envelope::from_parts(metadata, Order(item))
+        };
+        let batch = assert_some!(EnvelopeBatch::from_envelopes(vec![
+            envelope(1, "2022-11-30T03:43:18Z", 1),
+            envelope(2, "2022-11-30T03:45:18Z", 2),
+            envelope(3, "2022-11-30T03:44:18Z", 3),
+        ]));
+        assert_eq!(batch.items(), &[Order(1), Order(2), Order(3)]);
+        assert_eq!(batch.correlation().id, 2);
+        assert_eq!(batch.recv_timestamp(), ts("2022-11-30T03:45:18Z"));
+
+        let json = assert_ok!(serde_json::to_string(&batch));
+        let actual: EnvelopeBatch<Order, u64> = assert_ok!(serde_json::from_str(&json));
+        assert_eq!(actual, batch);
+
+        let envelopes = batch.split_with::<CountingGenerator>();
+        assert_eq!(envelopes.len(), 3);
+        let mut ids: Vec<_> = envelopes
+            .iter()
+            .map(|e| e.metadata().message_id().id)
+            .collect();
+        ids.sort_unstable();
+        ids.dedup();
+        assert_eq!(ids.len(), 3);
+        assert!(!ids.contains(&2));
+        for (envelope, item) in envelopes.iter().zip(1..) {
+            assert_eq!(envelope.as_ref(), &Order(item));
+            assert_eq!(envelope.metadata().correlation().id, 2);
+            assert_eq!(envelope.metadata().correlation().label, "Order");
+            assert_eq!(envelope.metadata().message_id().label, "Order");
+            assert_eq!(envelope.metadata().causation_id(), None);
+            assert_eq!(envelope.recv_timestamp(), ts("2022-11-30T03:45:18Z"));
+        }
+    }
+}
@@ -0,0 +1,205 @@
+use crate::clock;
+use crate::envelope::{Envelope, MetaData};
+use crate::id::IdGenerator;
+use crate::{Entity, Id, Label, Labeling};
+use iso8601_timestamp::Timestamp;
+use std::collections::HashMap;
+
+/// Builds an [`Envelope`] with explicit metadata, e.g., for tests or adapters. Metadata not set
+/// defaults as in [`Envelope::new()`]: a fresh correlation id and the current time.
+///
+/// ```rust
+/// use iso8601_timestamp::Timestamp;
+/// use tagid::envelope::{Correlation, Envelope, ReceivedAt};
+/// use tagid::{CuidGenerator, Entity, Label};
+///
+/// #[derive(Label)]
+/// struct Order;
+/// impl Entity for Order { type IdGen = CuidGenerator; }
+///
+/// let received_at = Timestamp::parse("2022-11-30T03:43:18.068Z").unwrap();
+/// let envelope = Envelope::builder(Order)
+///     .correlation("order-17".to_string())
+///     .received_at(received_at)
+///     .custom("tenant", "acme")
+///     .build();
+/// assert_eq!(envelope.metadata().correlation().id, "order-17");
+/// assert_eq!(envelope.recv_timestamp(), received_at);
+/// assert_eq!(envelope.metadata().custom()["tenant"], "acme");
+/// ```
+#[derive(Debug, Clone)]
+pub struct EnvelopeBuilder<T, ID> {
+    content: T,
+    correlation_id: Option<ID>,
+    causation_id: Option<ID>,
+    actor_id: Option<ID>,
+    tenant_id: Option<ID>,
+    recv_timestamp: Option<Timestamp>,
+    custom: HashMap<String, String>,
+}
+
+impl<T, ID> EnvelopeBuilder<T, ID> {
+    pub fn new(content: T) -> Self {
+        Self {
+            content,
+            correlation_id: None,
+            causation_id: None,
+            actor_id: None,
+            tenant_id: None,
+            recv_timestamp: None,
+            custom: HashMap::new(),
+        }
+    }
+
+    pub fn correlation(self, correlation_id: ID) -> Self {
+        Self {
+            correlation_id: Some(correlation_id),
+            ..self
+        }
+    }
+
+    pub fn causation(self, causation_id: ID) -> Self {
+        Self {
+            causation_id: Some(causation_id),
+            ..self
+        }
+    }
+
+    pub fn actor<A>(self, actor_id: Id<A, ID>) -> Self {
+        Self {
+            actor_id: Some(actor_id.id),
+            ..self
+        }
+    }
+
+    pub fn tenant<A>(self, tenant_id: Id<A, ID>) -> Self {
+        Self {
+            tenant_id: Some(tenant_id.id),
+            ..self
+        }
+    }
+
+    pub fn received_at(self, recv_timestamp: Timestamp) -> Self {
+        Self {
+            recv_timestamp: Some(recv_timestamp),
+            ..self
+        }
+    }
+
+    pub fn custom(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.custom.insert(key.into(), value.into());
+        self
+    }
+}
+
+impl<T: Label, ID> EnvelopeBuilder<T, ID> {
+    /// Builds the envelope, generating the correlation id via `G` if not set.
+    pub fn build_with<G>(self) -> Envelope<T, ID>
+    where
+        G: IdGenerator<IdType = ID>,
+    {
+        let labeler = T::labeler();
+        let correlation_id = self.correlation_id.unwrap_or_else(G::next_id_rep);
+        let custom = if self.custom.is_empty() {
+            None
+        } else {
+            Some(self.custom)
+        };
+
+        let metadata = MetaData::from_parts(
+            Id::direct_labeled(labeler.label(), correlation_id),
+            self.recv_timestamp.unwrap_or_else(clock::now_timestamp),
+            custom,
+        );
+        let metadata = match self.causation_id {
+            Some(causation_id) => {
+                metadata.with_causation_id(Id::direct_labeled(labeler.label(), causation_id))
+            }
+            None => metadata,
+        };
+        let metadata = match self.actor_id {
+            Some(actor_id) => metadata.with_actor_id(Id::<T, ID>::for_labeled(actor_id)),
+            None => metadata,
+        };
+        let metadata = match self.tenant_id {
+            Some(tenant_id) => metadata.with_tenant_id(Id::<T, ID>::for_labeled(tenant_id)),
+            None => metadata,
+        };
+
+        Envelope::from_parts(metadata, self.content)
+    }
+}
+
+impl<E: Entity> EnvelopeBuilder<E, <<E as Entity>::IdGen as IdGenerator>::IdType> {
+    /// Builds the envelope, generating the correlation id for the entity if not set.
+    pub fn build(self) -> Envelope<E, <<E as Entity>::IdGen as IdGenerator>::IdType> {
+        self.build_with::<E::IdGen>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::envelope::{Correlation, ReceivedAt};
+    use crate::CustomLabeling;
+    use pretty_assertions::assert_eq;
+
+    struct Order;
+
+    impl Label for Order {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("Order")
+        }
+    }
+
+    struct User;
+
+    impl Label for User {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("User")
+        }
+    }
+
+    struct CountingGenerator;
+
+    impl IdGenerator for CountingGenerator {
+        type IdType = u64;
+
+        fn next_id_rep() -> Self::IdType {
+            42
+        }
+    }
+
+    #[test]
+    fn test_envelope_builder() {
+        let received_at = Timestamp::parse("2022-11-30T03:43:18.068Z").unwrap();
+        let envelope = Envelope::builder(Order)
+            .correlation(17)
+            .causation(13)
+            .actor(Id::<User, _>::for_labeled(7))
+            .received_at(received_at)
+            .custom("tenant", "acme")
+            .custom("region", "us-east-1")
+            .build_with::<CountingGenerator>();
+
+        let metadata = envelope.metadata();
+        assert_eq!(metadata.correlation().id, 17);
+        assert_eq!(metadata.correlation().label, "Order");
+        assert_eq!(metadata.causation_id().map(|id| id.id), Some(13));
+        let actor_id = metadata.actor_id::<User>().unwrap();
+        assert_eq!((actor_id.label.as_str(), actor_id.id), ("User", 7));
+        assert_eq!(metadata.tenant_id::<()>(), None);
+        assert_eq!(envelope.recv_timestamp(), received_at);
+        assert_eq!(metadata.custom().len(), 2);
+        assert_eq!(metadata.custom()["region"], "us-east-1");
+
+        let envelope = Envelope::builder(Order).build_with::<CountingGenerator>();
+        assert_eq!(envelope.metadata().correlation().id, 42);
+        assert_eq!(envelope.metadata().causation_id(), None);
+        assert!(envelope.metadata().custom().is_empty());
+    }
+}
@@ -0,0 +1,173 @@
+use crate::clock;
+use crate::envelope::{Envelope, ReceivedAt};
+use crate::Label;
+use iso8601_timestamp::Timestamp;
+use serde::{de, Deserialize, Serialize};
+use std::fmt;
+
+/// A failed envelope parked as a poison message, with the failure reason, when it failed and
+/// after how many delivery attempts.
+///
+/// ```rust
+/// use tagid::envelope::{DeadLetter, Envelope};
+/// use tagid::{CuidGenerator, Entity, Label};
+///
+/// #[derive(Debug, Label)]
+/// struct Order(u32);
+/// impl Entity for Order { type IdGen = CuidGenerator; }
+///
+/// let envelope = Envelope::from_entity(Order(17));
+/// let dead_letter = envelope.dead_letter("insufficient stock");
+/// assert_eq!(dead_letter.reason(), "insufficient stock");
+/// assert_eq!(dead_letter.attempts(), 1);
+/// assert_eq!(dead_letter.envelope().0, 17);
+/// ```
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "T: Serialize, ID: Serialize",
+    deserialize = "T: Label + de::DeserializeOwned, ID: de::DeserializeOwned"
+))]
+pub struct DeadLetter<T, ID> {
+    envelope: Envelope<T, ID>,
+    reason: String,
+    failed_at: Timestamp,
+    attempts: u32,
+}
+
+impl<T, ID> DeadLetter<T, ID> {
+    /// Parks the envelope as failed now, after the delivery attempts recorded in its metadata.
+    pub fn new(envelope: Envelope<T, ID>, reason: impl Into<String>) -> Self {
+        let attempts = envelope.metadata().delivery_attempt();
+        Self {
+            envelope,
+            reason: reason.into(),
+            failed_at: clock::now_timestamp(),
+            attempts,
+        }
+    }
+
+    pub fn from_error<E: std::error::Error>(envelope: Envelope<T, ID>, error: &E) -> Self {
+        Self::new(envelope, error.to_string())
+    }
+
+    #[allow(clippy::missing_const_for_fn)]
+    pub fn with_failed_at(self, failed_at: Timestamp) -> Self {
+        Self { failed_at, ..self }
+    }
+
+    #[allow(clippy::missing_const_for_fn)]
+    pub fn with_attempts(self, attempts: u32) -> Self {
+        Self { attempts, ..self }
+    }
+
+    pub const fn envelope(&self) -> &Envelope<T, ID> {
+        &self.envelope
+    }
+
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+
+    pub const fn failed_at(&self) -> Timestamp {
+        self.failed_at
+    }
+
+    pub const fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    /// How long the message was in flight, from when it was first received until it failed.
+    pub fn time_to_failure(&self) -> iso8601_timestamp::Duration {
+        self.failed_at
+            .duration_since(self.envelope.metadata().first_received_at())
+    }
+
+    #[allow(clippy::missing_const_for_fn)]
+    pub fn into_envelope(self) -> Envelope<T, ID> {
+        self.envelope
+    }
+
+    /// Releases the envelope for another delivery, recording the redelivery in its metadata.
+    pub fn redeliver(self) -> Envelope<T, ID> {
+        let (metadata, content) = self.envelope.into_parts();
+        Envelope::from_parts(metadata.redelivered(), content)
+    }
+}
+
+impl<T, ID> ReceivedAt for DeadLetter<T, ID> {
+    fn recv_timestamp(&self) -> Timestamp {
+        self.envelope.recv_timestamp()
+    }
+}
+
+impl<T, ID> fmt::Debug for DeadLetter<T, ID>
+where
+    Envelope<T, ID>: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DeadLetter")
+            .field("envelope", &self.envelope)
+            .field("reason", &self.reason)
+            .field("failed_at", &self.failed_at.to_string())
+            .field("attempts", &self.attempts)
+            .finish()
+    }
+}
+
+impl<T, ID> Envelope<T, ID> {
+    /// Parks the envelope as a dead letter that failed for the `reason`.
+    pub fn dead_letter(self, reason: impl Into<String>) -> DeadLetter<T, ID> {
+        DeadLetter::new(self, reason)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::envelope::{Correlation, MetaData};
+    use crate::{Id, MakeLabeling};
+    use claim::*;
+    use pretty_assertions::assert_eq;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Order(u32);
+
+    impl Label for Order {
+        type Labeler = MakeLabeling<Self>;
+
+        fn labeler() -> Self::Labeler {
+            MakeLabeling::default()
+        }
+    }
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("order {0} is out of stock")]
+    struct OutOfStock(u32);
+
+    #[test]
+    fn test_dead_letter() {
+        let received_at = Timestamp::parse("2022-11-30T03:43:18.068Z").unwrap();
+        let failed_at = Timestamp::parse("2022-11-30T03:45:18.068Z").unwrap();
+        let metadata = MetaData::from_parts(Id::for_labeled(17_u64), received_at, None)
+            .redelivered_at(received_at);
+        let envelope = Envelope::from_parts(metadata, Order(17));
+
+        let dead_letter =
+            DeadLetter::from_error(envelope, &OutOfStock(17)).with_failed_at(failed_at);
+        assert_eq!(dead_letter.reason(), "order 17 is out of stock");
+        assert_eq!(dead_letter.attempts(), 2);
+        assert_eq!(dead_letter.time_to_failure().whole_seconds(), 120);
+
+        let json = assert_ok!(serde_json::to_string(&dead_letter));
+        let actual: DeadLetter<Order, u64> = assert_ok!(serde_json::from_str(&json));
+        assert_eq!(actual.envelope().as_ref(), &Order(17));
+        assert_eq!(actual.envelope().metadata().correlation().id, 17);
+        assert_eq!(actual.reason(), dead_letter.reason());
+        assert_eq!(actual.failed_at(), failed_at);
+        assert_eq!(actual.attempts(), 2);
+
+        let envelope = actual.with_attempts(5).redeliver();
+        assert_eq!(envelope.metadata().delivery_attempt(), 3);
+        assert_eq!(envelope.metadata().first_received_at(), received_at);
+    }
+}
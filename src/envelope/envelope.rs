@@ -1,5 +1,6 @@
+use crate::clock;
 use crate::envelope::metadata::MetaData;
-use crate::envelope::{Correlation, ReceivedAt};
+use crate::envelope::{Correlation, EnvelopeBatch, EnvelopeBuilder, ReceivedAt};
 use crate::id::IdGenerator;
 use crate::{Entity, Id, Label, Labeling};
 #[cfg(feature = "functional")]
@@ -10,6 +11,7 @@ use serde::{de, ser::SerializeStruct, Deserialize, Serialize, Serializer};
 use std::fmt;
 use std::future::Future;
 use std::marker::PhantomData;
+use thiserror::Error;
 
 pub trait IntoEnvelope {
     type Content: Label;
@@ -70,13 +72,18 @@ where
     where
         G: IdGenerator<IdType = ID>,
     {
-        let correlation_id = Id::direct(T::labeler().label(), G::next_id_rep());
+        let correlation_id = Id::direct_labeled(T::labeler().label(), G::next_id_rep());
 
         Self {
-            metadata: MetaData::from_parts(correlation_id, Timestamp::now_utc(), None),
+            metadata: MetaData::from_parts(correlation_id, clock::now_timestamp(), None),
             content,
         }
     }
+
+    /// Builds enveloped data with explicit metadata.
+    pub fn builder(content: T) -> EnvelopeBuilder<T, ID> {
+        EnvelopeBuilder::new(content)
+    }
 }
 
 impl<T, ID> Envelope<T, ID> {
@@ -107,6 +114,70 @@ impl<T, ID> Envelope<T, ID> {
     pub const fn from_parts(metadata: MetaData<T, ID>, content: T) -> Self {
         Self { metadata, content }
     }
+
+    /// How long ago the envelope was received.
+    pub fn age(&self) -> std::time::Duration {
+        self.metadata.elapsed_since_recv()
+    }
+
+    /// Whether the envelope is past its processing deadline, if it has one.
+    pub fn is_expired(&self) -> bool {
+        self.metadata.is_expired()
+    }
+
+    /// Records the schema version of the content, which travels with the envelope so consumers
+    /// can upcast content written by older producers.
+    ///
+    /// ```rust
+    /// use serde::{Deserialize, Serialize};
+    /// use tagid::envelope::Envelope;
+    /// use tagid::{CuidGenerator, Entity, Label};
+    ///
+    /// #[derive(Debug, Label, Serialize, Deserialize)]
+    /// struct Order { quantity: u32 }
+    /// impl Entity for Order { type IdGen = CuidGenerator; }
+    ///
+    /// let envelope = Envelope::from_entity(Order { quantity: 17 }).with_schema_version(2);
+    /// let json = serde_json::to_string(&envelope).unwrap();
+    /// let envelope: Envelope<Order, String> = serde_json::from_str(&json).unwrap();
+    /// match envelope.schema_version() {
+    ///     Some(2) => assert_eq!(envelope.quantity, 17),
+    ///     version => panic!("no upcaster for order schema {version:?}"),
+    /// }
+    /// ```
+    pub fn with_schema_version(self, schema_version: u32) -> Self {
+        Self {
+            metadata: self.metadata.with_schema_version(schema_version),
+            ..self
+        }
+    }
+
+    pub const fn schema_version(&self) -> Option<u32> {
+        self.metadata.schema_version()
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl<T, ID> Envelope<T, ID>
+where
+    ID: fmt::Display,
+{
+    /// Creates an info span recording the envelope's `correlation_id`, `label` and
+    /// `recv_timestamp`, along with its `causation_id` if any.
+    pub fn instrument_span(&self) -> tracing::Span {
+        let correlation_id = self.metadata.correlation();
+        let span = tracing::info_span!(
+            "envelope",
+            correlation_id = %correlation_id.id,
+            label = %correlation_id.label,
+            recv_timestamp = %self.metadata.recv_timestamp(),
+            causation_id = tracing::field::Empty,
+        );
+        if let Some(causation_id) = self.metadata.causation_id() {
+            span.record("causation_id", tracing::field::display(&causation_id.id));
+        }
+        span
+    }
 }
 
 impl<T, ID> Envelope<T, ID>
@@ -114,6 +185,24 @@ where
     T: Label,
     ID: Clone,
 {
+    /// Envelopes content caused by the `cause` message, carrying forward its correlation id and
+    /// recording its message id as the causation id. The new message gets a fresh message id
+    /// generated via `G`.
+    pub fn caused_by<G, U>(content: T, cause: &Envelope<U, ID>) -> Self
+    where
+        G: IdGenerator<IdType = ID>,
+    {
+        let message_id = Id::direct_labeled(T::labeler().label(), G::next_id_rep());
+        let metadata = MetaData::from_parts(
+            cause.metadata.correlation().relabel(),
+            clock::now_timestamp(),
+            None,
+        )
+        .with_message_id(message_id)
+        .with_causation_id(cause.metadata.message_id().relabel());
+        Self { metadata, content }
+    }
+
     pub fn adopt_metadata<U>(&mut self, new_metadata: MetaData<U, ID>) -> MetaData<T, ID>
     where
         U: Label,
@@ -148,6 +237,43 @@ where
     }
 }
 
+impl<E> Envelope<E, <<E as Entity>::IdGen as IdGenerator>::IdType>
+where
+    E: Entity,
+    <<E as Entity>::IdGen as IdGenerator>::IdType: Clone,
+{
+    /// Envelopes the reply to this message, carrying forward its correlation id with a fresh
+    /// received timestamp and recording this message as the cause. The reply's message id is
+    /// generated via the entity's [`IdGen`](Entity::IdGen).
+    ///
+    /// ```rust
+    /// use tagid::{CuidGenerator, Entity, Label};
+    /// use tagid::envelope::{Correlation, Envelope};
+    ///
+    /// #[derive(Label)]
+    /// struct GetQuote;
+    /// impl Entity for GetQuote { type IdGen = CuidGenerator; }
+    ///
+    /// #[derive(Label)]
+    /// struct Quote(u32);
+    ///
+    /// let request = Envelope::from_entity(GetQuote);
+    /// let reply = request.reply_with(Quote(17));
+    /// assert_eq!(reply.metadata().correlation().id, request.metadata().correlation().id);
+    /// assert_eq!(
+    ///     reply.metadata().causation_id().map(|id| &id.id),
+    ///     Some(&request.metadata().message_id().id)
+    /// );
+    /// assert_ne!(reply.metadata().message_id().id, request.metadata().message_id().id);
+    /// ```
+    pub fn reply_with<U: Label>(
+        &self,
+        content: U,
+    ) -> Envelope<U, <<E as Entity>::IdGen as IdGenerator>::IdType> {
+        Envelope::caused_by::<E::IdGen, _>(content, self)
+    }
+}
+
 impl<T, ID> Envelope<T, ID>
 where
     T: Label + Send,
@@ -165,6 +291,38 @@ where
             content: f(self.content).await,
         }
     }
+
+    /// Asynchronously rewrites the metadata along with the content, e.g., so an enrichment stage
+    /// can record processing annotations as custom metadata entries.
+    ///
+    /// ```rust
+    /// use tagid::{CuidGenerator, Entity, Label};
+    /// use tagid::envelope::Envelope;
+    ///
+    /// #[derive(Label)]
+    /// struct Order(u32);
+    /// impl Entity for Order { type IdGen = CuidGenerator; }
+    ///
+    /// #[derive(Label)]
+    /// struct PricedOrder(u32, u64);
+    ///
+    /// let order = Envelope::from_entity(Order(17));
+    /// let priced = futures::executor::block_on(order.then(|metadata, order| async move {
+    ///     let metadata = metadata.with_custom("priced_by", "catalog-v2").relabel();
+    ///     (metadata, PricedOrder(order.0, 4_200))
+    /// }));
+    /// assert_eq!(priced.metadata().custom()["priced_by"], "catalog-v2");
+    /// assert_eq!(priced.1, 4_200);
+    /// ```
+    pub async fn then<Op, Fut, U>(self, f: Op) -> Envelope<U, ID>
+    where
+        U: Label + Send,
+        Fut: Future<Output = (MetaData<U, ID>, U)> + Send,
+        Op: FnOnce(MetaData<T, ID>, T) -> Fut + Send,
+    {
+        let (metadata, content) = f(self.metadata, self.content).await;
+        Envelope { metadata, content }
+    }
 }
 
 impl<E> Correlation for Envelope<E, <<E as Entity>::IdGen as IdGenerator>::IdType>
@@ -190,6 +348,8 @@ where
     T: Label,
 {
     type Labeler = <T as Label>::Labeler;
+    const DELIMITER: &'static str = <T as Label>::DELIMITER;
+    const LABEL: Option<&'static str> = <T as Label>::LABEL;
 
     fn labeler() -> Self::Labeler {
         <T as Label>::labeler()
@@ -369,6 +529,144 @@ where
     }
 }
 
+impl<T, ID> Envelope<Vec<T>, ID>
+where
+    T: Label,
+    ID: Clone,
+{
+    /// Transposes an `Envelope` of a [`Vec`] into a [`Vec`] of `Envelope`, each sharing the
+    /// metadata.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tagid::{CuidGenerator, Entity, Label};
+    /// use tagid::envelope::Envelope;
+    ///
+    /// #[derive(Debug, Label, PartialEq)]
+    /// struct Foo(pub i32);
+    /// impl Entity for Foo { type IdGen = CuidGenerator; }
+    ///
+    /// let envelopes = vec![Envelope::from_entity(Foo(1)), Envelope::from_entity(Foo(2))];
+    /// let collected = Envelope::collect_enveloped(envelopes).unwrap();
+    /// assert_eq!(collected.as_ref(), &vec![Foo(1), Foo(2)]);
+    ///
+    /// let envelopes = collected.transpose_vec();
+    /// assert_eq!(envelopes.len(), 2);
+    /// assert_eq!(envelopes[1], Foo(2));
+    /// ```
+    pub fn transpose_vec(self) -> Vec<Envelope<T, ID>> {
+        let metadata: MetaData<T, ID> = self.metadata.relabel();
+        self.content
+            .into_iter()
+            .map(|content| Envelope {
+                metadata: metadata.clone(),
+                content,
+            })
+            .collect()
+    }
+
+    /// Collects the envelopes into an `Envelope` of a [`Vec`], combining their metadata as the
+    /// envelope semigroup does, i.e., the metadata received last wins. Returns `None` if there
+    /// are no envelopes.
+    pub fn collect_enveloped<I>(envelopes: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = Envelope<T, ID>>,
+        ID: PartialOrd,
+    {
+        EnvelopeBatch::from_envelopes(envelopes).map(|batch| {
+            let (metadata, content) = batch.into_parts();
+            Self {
+                metadata: metadata.relabel(),
+                content,
+            }
+        })
+    }
+}
+
+/// Envelopes expected to share a correlation id, e.g., when zipped, are correlated differently.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("envelope correlation mismatch: {left} vs {right}")]
+pub struct CorrelationMismatch {
+    pub left: String,
+    pub right: String,
+}
+
+impl<A, ID> Envelope<A, ID>
+where
+    A: Label,
+    ID: Clone + PartialEq + fmt::Display,
+{
+    /// Zips this envelope with a correlated one, keeping this envelope's metadata with the later
+    /// of the two received timestamps.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tagid::{CuidGenerator, Entity, Label};
+    /// use tagid::envelope::{Correlation, Envelope};
+    ///
+    /// #[derive(Debug, Label, PartialEq)]
+    /// struct Order(u32);
+    /// impl Entity for Order { type IdGen = CuidGenerator; }
+    ///
+    /// #[derive(Debug, Label, PartialEq)]
+    /// struct Payment(u32);
+    ///
+    /// let order = Envelope::from_entity(Order(17));
+    /// let payment = order.reply_with(Payment(42));
+    /// let zipped = order.zip(payment).unwrap();
+    /// assert_eq!(zipped.as_ref(), &(Order(17), Payment(42)));
+    ///
+    /// let (order, payment) = zipped.unzip();
+    /// assert_eq!(order.metadata().correlation().id, payment.metadata().correlation().id);
+    /// ```
+    pub fn zip<B>(
+        self,
+        other: Envelope<B, ID>,
+    ) -> Result<Envelope<(A, B), ID>, CorrelationMismatch> {
+        let left = &self.metadata.correlation().id;
+        let right = &other.metadata.correlation().id;
+        if left != right {
+            return Err(CorrelationMismatch {
+                left: left.to_string(),
+                right: right.to_string(),
+            });
+        }
+
+        let recv_timestamp = self
+            .metadata
+            .recv_timestamp()
+            .max(other.metadata.recv_timestamp());
+        Ok(Envelope {
+            metadata: self.metadata.relabel().with_recv_timestamp(recv_timestamp),
+            content: (self.content, other.content),
+        })
+    }
+}
+
+impl<A, B, ID> Envelope<(A, B), ID>
+where
+    A: Label,
+    B: Label,
+    ID: Clone,
+{
+    /// Splits an `Envelope` of a pair into an envelope for each, sharing the metadata.
+    pub fn unzip(self) -> (Envelope<A, ID>, Envelope<B, ID>) {
+        let (a, b) = self.content;
+        (
+            Envelope {
+                metadata: self.metadata.clone().relabel(),
+                content: a,
+            },
+            Envelope {
+                metadata: self.metadata.relabel(),
+                content: b,
+            },
+        )
+    }
+}
+
 const ENV_METADATA: &str = "metadata";
 const ENV_CONTENT: &str = "content";
 const FIELDS: [&str; 2] = [ENV_METADATA, ENV_CONTENT];
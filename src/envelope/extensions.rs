@@ -0,0 +1,260 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::fmt;
+
+/// A typed extension with a string form, so it is carried in the serializable projection of
+/// [`Extensions`] and can be restored from it.
+pub trait MetaExtension: Clone + Send + Sync + 'static {
+    /// The key of the extension in the string map projection.
+    const KEY: &'static str;
+
+    fn to_meta_value(&self) -> String;
+
+    fn from_meta_value(value: &str) -> Option<Self>;
+}
+
+/// A map of typed values keyed by their type, so middleware can attach structured context, such
+/// as tenant, auth claims or retry info, to metadata without serialization round-trips.
+///
+/// Only extensions inserted via [`insert_projected()`](Self::insert_projected) appear in the
+/// string map [`projection()`](Self::projection) that metadata serializes.
+///
+/// ```rust
+/// use tagid::envelope::{Extensions, MetaExtension};
+///
+/// #[derive(Debug, Clone, PartialEq)]
+/// struct Tenant(String);
+///
+/// impl MetaExtension for Tenant {
+///     const KEY: &'static str = "tenant";
+///
+///     fn to_meta_value(&self) -> String {
+///         self.0.clone()
+///     }
+///
+///     fn from_meta_value(value: &str) -> Option<Self> {
+///         Some(Self(value.to_string()))
+///     }
+/// }
+///
+/// #[derive(Debug, Clone, PartialEq)]
+/// struct RetryCount(u32);
+///
+/// let mut extensions = Extensions::new();
+/// extensions.insert_projected(Tenant("acme".to_string()));
+/// extensions.insert(RetryCount(2));
+/// assert_eq!(extensions.get::<RetryCount>(), Some(&RetryCount(2)));
+/// assert_eq!(extensions.projection().get("tenant").map(String::as_str), Some("acme"));
+/// assert_eq!(extensions.projection().len(), 1);
+/// ```
+#[derive(Clone, Default)]
+pub struct Extensions {
+    map: HashMap<TypeId, Box<dyn AnyExtension>>,
+}
+
+impl Extensions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts the extension, returning the previous extension of its type.
+    pub fn insert<E: Clone + Send + Sync + 'static>(&mut self, extension: E) -> Option<E> {
+        self.insert_entry(Entry {
+            value: extension,
+            project: None,
+        })
+    }
+
+    /// Inserts the extension, including it in the string map projection.
+    pub fn insert_projected<E: MetaExtension>(&mut self, extension: E) -> Option<E> {
+        self.insert_entry(Entry {
+            value: extension,
+            project: Some(|e: &E| (E::KEY, e.to_meta_value())),
+        })
+    }
+
+    fn insert_entry<E: Clone + Send + Sync + 'static>(&mut self, entry: Entry<E>) -> Option<E> {
+        self.map
+            .insert(TypeId::of::<E>(), Box::new(entry))
+            .and_then(|previous| previous.into_any().downcast::<Entry<E>>().ok())
+            .map(|previous| previous.value)
+    }
+
+    pub fn get<E: 'static>(&self) -> Option<&E> {
+        self.map
+            .get(&TypeId::of::<E>())
+            .and_then(|entry| entry.as_any().downcast_ref::<Entry<E>>())
+            .map(|entry| &entry.value)
+    }
+
+    pub fn get_mut<E: 'static>(&mut self) -> Option<&mut E> {
+        self.map
+            .get_mut(&TypeId::of::<E>())
+            .and_then(|entry| entry.as_any_mut().downcast_mut::<Entry<E>>())
+            .map(|entry| &mut entry.value)
+    }
+
+    pub fn remove<E: 'static>(&mut self) -> Option<E> {
+        self.map
+            .remove(&TypeId::of::<E>())
+            .and_then(|entry| entry.into_any().downcast::<Entry<E>>().ok())
+            .map(|entry| entry.value)
+    }
+
+    pub fn contains<E: 'static>(&self) -> bool {
+        self.map.contains_key(&TypeId::of::<E>())
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Restores the extension from its string form in the `projection`, as it was serialized.
+    pub fn restore<E: MetaExtension>(
+        &mut self,
+        projection: &HashMap<String, String>,
+    ) -> Option<&E> {
+        let extension = projection
+            .get(E::KEY)
+            .and_then(|value| E::from_meta_value(value))?;
+        self.insert_projected(extension);
+        self.get()
+    }
+
+    /// The string forms of the extensions inserted via
+    /// [`insert_projected()`](Self::insert_projected), keyed by [`MetaExtension::KEY`].
+    pub fn projection(&self) -> HashMap<String, String> {
+        self.map
+            .values()
+            .filter_map(|entry| entry.project())
+            .map(|(key, value)| (key.to_string(), value))
+            .collect()
+    }
+}
+
+impl fmt::Debug for Extensions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Extensions")
+            .field("len", &self.map.len())
+            .field("projection", &self.projection())
+            .finish()
+    }
+}
+
+/// Renders an extension as its key and string form.
+type Projection<E> = fn(&E) -> (&'static str, String);
+
+#[derive(Clone)]
+struct Entry<E> {
+    value: E,
+    project: Option<Projection<E>>,
+}
+
+trait AnyExtension: Any + Send + Sync {
+    fn clone_box(&self) -> Box<dyn AnyExtension>;
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+    fn project(&self) -> Option<(&'static str, String)>;
+}
+
+impl<E: Clone + Send + Sync + 'static> AnyExtension for Entry<E> {
+    fn clone_box(&self) -> Box<dyn AnyExtension> {
+        Box::new(self.clone())
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+
+    fn project(&self) -> Option<(&'static str, String)> {
+        self.project.map(|project| project(&self.value))
+    }
+}
+
+impl Clone for Box<dyn AnyExtension> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use claim::*;
+    use pretty_assertions::assert_eq;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Tenant(String);
+
+    impl MetaExtension for Tenant {
+        const KEY: &'static str = "tenant";
+
+        fn to_meta_value(&self) -> String {
+            self.0.clone()
+        }
+
+        fn from_meta_value(value: &str) -> Option<Self> {
+            Some(Self(value.to_string()))
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Claims {
+        subject: String,
+        scopes: Vec<String>,
+    }
+
+    #[test]
+    fn test_extensions() {
+        let mut extensions = Extensions::new();
+        assert!(extensions.is_empty());
+        assert_none!(extensions.insert_projected(Tenant("acme".to_string())));
+        let claims = Claims {
+            subject: "otis".to_string(),
+            scopes: vec!["orders:read".to_string()],
+        };
+        assert_none!(extensions.insert(claims.clone()));
+        assert_eq!(extensions.len(), 2);
+
+        assert_eq!(assert_some!(extensions.get::<Claims>()), &claims);
+        assert_some!(extensions.get_mut::<Claims>()).scopes.clear();
+        assert!(assert_some!(extensions.get::<Claims>()).scopes.is_empty());
+        assert!(!extensions.contains::<u32>());
+
+        let cloned = extensions.clone();
+        assert_eq!(
+            assert_some!(extensions.insert_projected(Tenant("globex".to_string()))),
+            Tenant("acme".to_string())
+        );
+        assert_eq!(
+            assert_some!(cloned.get::<Tenant>()),
+            &Tenant("acme".to_string())
+        );
+
+        let projection = extensions.projection();
+        assert_eq!(projection.len(), 1);
+        assert_eq!(projection["tenant"], "globex");
+
+        let mut restored = Extensions::new();
+        assert_eq!(
+            assert_some!(restored.restore::<Tenant>(&projection)),
+            &Tenant("globex".to_string())
+        );
+
+        assert_eq!(assert_some!(extensions.remove::<Claims>()).subject, "otis");
+        assert_none!(extensions.remove::<Claims>());
+    }
+}
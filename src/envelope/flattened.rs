@@ -0,0 +1,149 @@
+//! Serde representation of envelopes with the content's fields at the top level alongside the
+//! `metadata` key, rather than nested under `content`.
+//!
+//! Some external systems only accept this shape. It relies on `#[serde(flatten)]`, so the content
+//! must (de)serialize as a map or struct without a `metadata` field of its own, and the format
+//! must be self-describing (e.g., JSON rather than bincode).
+//!
+//! ```rust
+//! use serde::{Deserialize, Serialize};
+//! use tagid::envelope::{Envelope, MetaData};
+//! use tagid::{Id, Label};
+//!
+//! #[derive(Debug, Label, Serialize, Deserialize)]
+//! struct Order {
+//!     quantity: u32,
+//! }
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Message {
+//!     #[serde(with = "tagid::envelope::flattened")]
+//!     order: Envelope<Order, u64>,
+//! }
+//!
+//! let recv_timestamp = iso8601_timestamp::Timestamp::now_utc();
+//! let metadata = MetaData::from_parts(Id::for_labeled(17), recv_timestamp, None);
+//! let message = Message { order: Envelope::from_parts(metadata, Order { quantity: 3 }) };
+//! let json = serde_json::to_value(&message).unwrap();
+//! assert_eq!(json["order"]["quantity"], 3);
+//! assert_eq!(json["order"]["metadata"]["correlation_id"], 17);
+//!
+//! let message: Message = serde_json::from_value(json).unwrap();
+//! assert_eq!(message.order.quantity, 3);
+//! ```
+
+use crate::envelope::{Envelope, MetaData};
+use crate::Label;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Serialize)]
+struct FlattenedRef<'a, T, ID> {
+    metadata: &'a MetaData<T, ID>,
+    #[serde(flatten)]
+    content: &'a T,
+}
+
+#[derive(Deserialize)]
+#[serde(bound(deserialize = "T: Label + de::DeserializeOwned, ID: de::DeserializeOwned"))]
+struct Flattened<T, ID> {
+    metadata: MetaData<T, ID>,
+    #[serde(flatten)]
+    content: T,
+}
+
+pub fn serialize<T, ID, S>(envelope: &Envelope<T, ID>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Serialize,
+    ID: Serialize,
+    S: Serializer,
+{
+    envelope.serialize_flattened(serializer)
+}
+
+pub fn deserialize<'de, T, ID, D>(deserializer: D) -> Result<Envelope<T, ID>, D::Error>
+where
+    T: Label + de::DeserializeOwned,
+    ID: de::DeserializeOwned,
+    D: Deserializer<'de>,
+{
+    Envelope::deserialize_flattened(deserializer)
+}
+
+impl<T: Serialize, ID: Serialize> Envelope<T, ID> {
+    /// Serializes the envelope with the content's fields flattened beside the `metadata` key.
+    pub fn serialize_flattened<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        FlattenedRef {
+            metadata: self.metadata(),
+            content: &**self,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<T: Label + de::DeserializeOwned, ID: de::DeserializeOwned> Envelope<T, ID> {
+    /// Deserializes an envelope whose content's fields sit beside the `metadata` key.
+    pub fn deserialize_flattened<'de, D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let Flattened { metadata, content } = Flattened::deserialize(deserializer)?;
+        Ok(Self::from_parts(metadata, content))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CustomLabeling, Id};
+    use claim::*;
+    use iso8601_timestamp::Timestamp;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Shipment {
+        carrier: String,
+        parcels: u32,
+    }
+
+    impl Label for Shipment {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("Shipment")
+        }
+    }
+
+    #[test]
+    fn test_flattened_envelope_serde() {
+        let metadata = MetaData::from_parts(
+            Id::for_labeled("ship-17".to_string()),
+            Timestamp::now_utc(),
+            None,
+        );
+        let content = Shipment {
+            carrier: "acme".to_string(),
+            parcels: 2,
+        };
+        let envelope = Envelope::from_parts(metadata.clone(), content);
+
+        let mut json = Vec::new();
+        let mut serializer = serde_json::Serializer::new(&mut json);
+        assert_ok!(envelope.serialize_flattened(&mut serializer));
+        let json: serde_json::Value = assert_ok!(serde_json::from_slice(&json));
+        assert_eq!(json["carrier"], json!("acme"));
+        assert_eq!(json["parcels"], json!(2));
+        assert_eq!(
+            json["metadata"],
+            assert_ok!(serde_json::to_value(&metadata))
+        );
+        assert_none!(json.get("content"));
+
+        let actual: Envelope<Shipment, String> = assert_ok!(Envelope::deserialize_flattened(json));
+        assert_eq!(actual.metadata(), &metadata);
+        assert_eq!(actual.into_inner(), envelope.into_inner());
+    }
+}
@@ -0,0 +1,328 @@
+//! Propagation of envelope metadata across HTTP boundaries, so axum or reqwest middleware can
+//! carry correlation across services.
+//!
+//! The correlation id travels in the `X-Correlation-ID` header, the message id in
+//! `X-Message-ID`, the causation id in `X-Causation-ID` and the received timestamp in
+//! `X-Received-At`. Custom metadata entries,
+//! including projected [`Extensions`](crate::envelope::Extensions), travel in `X-Meta-<key>`
+//! headers. HTTP header names are case-insensitive, so custom keys are extracted in lowercase.
+//!
+//! ```rust
+//! use http::HeaderMap;
+//! use tagid::envelope::MetaData;
+//! use tagid::{CuidGenerator, Entity, Label};
+//!
+//! #[derive(Label)]
+//! struct Order;
+//! impl Entity for Order { type IdGen = CuidGenerator; }
+//!
+//! let metadata = MetaData::<Order, _>::default();
+//! let mut headers = HeaderMap::new();
+//! metadata.to_headers(&mut headers).unwrap();
+//!
+//! let extracted = MetaData::<Order, String>::from_headers(&headers).unwrap();
+//! assert_eq!(extracted, metadata);
+//! ```
+
+use crate::clock;
+use crate::envelope::metadata::{
+    CAUSATION_ID_KEY, CORRELATION_ID_KEY, MESSAGE_ID_KEY, RECV_TIMESTAMP_KEY,
+};
+use crate::envelope::{Correlation, IntoMetaData, MetaData, MetaHeaderError, ReceivedAt};
+use crate::id::IdGenerator;
+use crate::{Id, Label};
+use ::http::header::{HeaderMap, HeaderName, HeaderValue};
+use iso8601_timestamp::Timestamp;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+pub const CORRELATION_ID_HEADER: &str = "x-correlation-id";
+pub const MESSAGE_ID_HEADER: &str = "x-message-id";
+pub const CAUSATION_ID_HEADER: &str = "x-causation-id";
+pub const RECV_TIMESTAMP_HEADER: &str = "x-received-at";
+pub const CUSTOM_HEADER_PREFIX: &str = "x-meta-";
+
+impl<T, ID> MetaData<T, ID>
+where
+    ID: fmt::Display,
+{
+    /// Inserts the metadata into the `headers`, overwriting existing headers of the same name.
+    /// The received timestamp is rendered in millisecond precision.
+    pub fn to_headers(&self, headers: &mut HeaderMap) -> Result<(), MetaHeaderError> {
+        let mut insert = |name: String, value: String| {
+            let header_name = HeaderName::try_from(name.as_str())
+                .map_err(|err| MetaHeaderError::invalid(name.as_str(), err))?;
+            let header_value =
+                HeaderValue::try_from(value).map_err(|err| MetaHeaderError::invalid(name, err))?;
+            headers.insert(header_name, header_value);
+            Ok(())
+        };
+
+        insert(
+            CORRELATION_ID_HEADER.to_string(),
+            self.correlation().id.to_string(),
+        )?;
+        insert(
+            MESSAGE_ID_HEADER.to_string(),
+            self.message_id().id.to_string(),
+        )?;
+        if let Some(causation_id) = self.causation_id() {
+            insert(CAUSATION_ID_HEADER.to_string(), causation_id.id.to_string())?;
+        }
+        insert(
+            RECV_TIMESTAMP_HEADER.to_string(),
+            self.recv_timestamp().to_string(),
+        )?;
+
+        let mut custom = self.custom().clone();
+        custom.extend(self.extensions().projection());
+        for (key, value) in custom {
+            insert(
+                format!("{CUSTOM_HEADER_PREFIX}{}", key.to_lowercase()),
+                value,
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<T, ID> MetaData<T, ID>
+where
+    T: Label,
+    ID: FromStr,
+    <ID as FromStr>::Err: fmt::Display,
+{
+    /// Extracts the metadata from the `headers`, which must include the correlation id. The
+    /// received timestamp defaults to now if absent.
+    pub fn from_headers(headers: &HeaderMap) -> Result<Self, MetaHeaderError> {
+        let header = |name: &'static str| {
+            headers
+                .get(name)
+                .map(|value| {
+                    value
+                        .to_str()
+                        .map_err(|err| MetaHeaderError::invalid(name, err))
+                })
+                .transpose()
+        };
+        let id = |name: &'static str, rep: &str| {
+            ID::from_str(rep)
+                .map(Id::for_labeled)
+                .map_err(|err| MetaHeaderError::invalid(name, err))
+        };
+
+        let correlation_id = header(CORRELATION_ID_HEADER)?
+            .ok_or(MetaHeaderError::Missing(CORRELATION_ID_HEADER))
+            .and_then(|rep| id(CORRELATION_ID_HEADER, rep))?;
+        let message_id = header(MESSAGE_ID_HEADER)?
+            .map(|rep| id(MESSAGE_ID_HEADER, rep))
+            .transpose()?;
+        let causation_id = header(CAUSATION_ID_HEADER)?
+            .map(|rep| id(CAUSATION_ID_HEADER, rep))
+            .transpose()?;
+        let recv_timestamp = header(RECV_TIMESTAMP_HEADER)?
+            .map(|rep| {
+                Timestamp::parse(rep).ok_or_else(|| {
+                    MetaHeaderError::invalid(RECV_TIMESTAMP_HEADER, "not an ISO 8601 timestamp")
+                })
+            })
+            .transpose()?
+            .unwrap_or_else(clock::now_timestamp);
+
+        let mut custom = HashMap::new();
+        for (name, value) in headers {
+            if let Some(key) = name.as_str().strip_prefix(CUSTOM_HEADER_PREFIX) {
+                let value = value
+                    .to_str()
+                    .map_err(|err| MetaHeaderError::invalid(name.as_str(), err))?;
+                custom.insert(key.to_string(), value.to_string());
+            }
+        }
+
+        let custom = if custom.is_empty() {
+            None
+        } else {
+            Some(custom)
+        };
+        let mut metadata = Self::from_parts(correlation_id, recv_timestamp, custom);
+        if let Some(message_id) = message_id {
+            metadata = metadata.with_message_id(message_id);
+        }
+        Ok(match causation_id {
+            Some(causation_id) => metadata.with_causation_id(causation_id),
+            None => metadata,
+        })
+    }
+}
+
+impl IntoMetaData for &HeaderMap {
+    type CorrelatedType = ();
+
+    /// Leniently extracts the metadata from the headers named as for
+    /// [`MetaData::from_headers()`], skipping header values that are not visible ASCII.
+    fn into_metadata_for<T, G>(self) -> MetaData<T, G::IdType>
+    where
+        T: Label,
+        G: IdGenerator,
+        G::IdType: FromStr,
+    {
+        let entries = self.iter().filter_map(|(name, value)| {
+            let key = match name.as_str() {
+                CORRELATION_ID_HEADER => CORRELATION_ID_KEY,
+                MESSAGE_ID_HEADER => MESSAGE_ID_KEY,
+                CAUSATION_ID_HEADER => CAUSATION_ID_KEY,
+                RECV_TIMESTAMP_HEADER => RECV_TIMESTAMP_KEY,
+                name => name.strip_prefix(CUSTOM_HEADER_PREFIX)?,
+            };
+            let value = value.to_str().ok()?;
+            Some((key.to_string(), value.to_string()))
+        });
+
+        entries
+            .collect::<HashMap<_, _>>()
+            .into_metadata_for::<T, G>()
+    }
+}
+
+impl IntoMetaData for HeaderMap {
+    type CorrelatedType = ();
+
+    fn into_metadata_for<T, G>(self) -> MetaData<T, G::IdType>
+    where
+        T: Label,
+        G: IdGenerator,
+        G::IdType: FromStr,
+    {
+        (&self).into_metadata_for::<T, G>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::envelope::Envelope;
+    use crate::CustomLabeling;
+    use claim::*;
+    use pretty_assertions::assert_eq;
+
+    struct Order;
+
+    impl Label for Order {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("Order")
+        }
+    }
+
+    #[test]
+    fn test_metadata_headers_roundtrip() {
+        let ts = Timestamp::parse("2022-11-30T03:43:18.068Z").unwrap();
+        let mut custom = HashMap::new();
+        custom.insert("Tenant".to_string(), "acme".to_string());
+        let command = Envelope::from_parts(
+            MetaData::from_parts(Id::<Order, u64>::for_labeled(17), ts, Some(custom)),
+            Order,
+        );
+        let metadata = Envelope::caused_by::<SequenceGenerator, _>(Order, &command)
+            .metadata()
+            .clone()
+            .with_recv_timestamp(ts);
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-meta-stale", HeaderValue::from_static("kept"));
+        assert_ok!(command.metadata().to_headers(&mut headers));
+        assert_ok!(metadata.to_headers(&mut headers));
+        assert_eq!(headers[CORRELATION_ID_HEADER], "17");
+        assert_eq!(headers[MESSAGE_ID_HEADER], "42");
+        assert_eq!(headers[CAUSATION_ID_HEADER], "17");
+        assert_eq!(headers["X-Meta-Tenant"], "acme");
+        assert_eq!(headers.len(), 6);
+
+        let actual = assert_ok!(MetaData::<Order, u64>::from_headers(&headers));
+        assert_eq!(actual, metadata);
+        assert_eq!(actual.recv_timestamp(), metadata.recv_timestamp());
+        assert_eq!(actual.message_id().id, 42);
+        assert_eq!(
+            actual.causation_id().map(|id| id.id),
+            metadata.causation_id().map(|id| id.id)
+        );
+        assert_eq!(actual.custom()["tenant"], "acme");
+        assert_eq!(actual.custom()["stale"], "kept");
+        assert_eq!(actual.custom().len(), 2);
+    }
+
+    struct SequenceGenerator;
+
+    impl IdGenerator for SequenceGenerator {
+        type IdType = u64;
+
+        fn next_id_rep() -> Self::IdType {
+            42
+        }
+    }
+
+    #[test]
+    fn test_headers_into_metadata() {
+        let mut headers = HeaderMap::new();
+        headers.insert(CORRELATION_ID_HEADER, HeaderValue::from_static("17"));
+        headers.insert(
+            RECV_TIMESTAMP_HEADER,
+            HeaderValue::from_static("2022-11-30T03:43:18.068Z"),
+        );
+        headers.insert("x-meta-tenant", HeaderValue::from_static("acme"));
+        headers.insert("content-type", HeaderValue::from_static("application/json"));
+        headers.insert("x-meta-opaque", HeaderValue::from_bytes(b"\xff").unwrap());
+
+        let metadata = (&headers).into_metadata_for::<Order, SequenceGenerator>();
+        assert_eq!(metadata.correlation().id, 17);
+        assert_eq!(metadata.correlation().label, "Order");
+        assert_eq!(
+            metadata.recv_timestamp(),
+            Timestamp::parse("2022-11-30T03:43:18.068Z").unwrap()
+        );
+        assert_eq!(metadata.custom().len(), 1);
+        assert_eq!(metadata.custom()["tenant"], "acme");
+
+        headers.insert(CORRELATION_ID_HEADER, HeaderValue::from_static("seventeen"));
+        let metadata = headers.into_metadata::<SequenceGenerator>();
+        assert_eq!(metadata.correlation().id, 42);
+        assert_eq!(metadata.correlation().label, "");
+    }
+
+    #[test]
+    fn test_metadata_headers_errors() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(
+            assert_err!(MetaData::<Order, u64>::from_headers(&headers)),
+            MetaHeaderError::Missing(CORRELATION_ID_HEADER)
+        );
+
+        headers.insert(CORRELATION_ID_HEADER, HeaderValue::from_static("seventeen"));
+        assert_matches!(
+            MetaData::<Order, u64>::from_headers(&headers),
+            Err(MetaHeaderError::Invalid { name, .. }) if name == CORRELATION_ID_HEADER
+        );
+
+        headers.insert(CORRELATION_ID_HEADER, HeaderValue::from_static("17"));
+        headers.insert(RECV_TIMESTAMP_HEADER, HeaderValue::from_static("yesterday"));
+        assert_eq!(
+            assert_err!(MetaData::<Order, u64>::from_headers(&headers)).to_string(),
+            "invalid x-received-at header: not an ISO 8601 timestamp"
+        );
+
+        let mut custom = HashMap::new();
+        custom.insert("bad key".to_string(), "value".to_string());
+        let metadata = MetaData::from_parts(
+            Id::<Order, u64>::for_labeled(17),
+            Timestamp::now_utc(),
+            Some(custom),
+        );
+        assert_matches!(
+            metadata.to_headers(&mut HeaderMap::new()),
+            Err(MetaHeaderError::Invalid { name, .. }) if name == "x-meta-bad key"
+        );
+    }
+}
@@ -0,0 +1,305 @@
+//! Conversions between envelope metadata and Kafka record headers, for [`rdkafka`] producers
+//! and consumers.
+//!
+//! Metadata travels in the `correlation_id`, `message_id`, `causation_id` and `recv_timestamp`
+//! headers, with custom metadata entries, including projected
+//! [`Extensions`](crate::envelope::Extensions), in headers named by their keys.
+//!
+//! ```rust
+//! use rdkafka::producer::FutureRecord;
+//! use tagid::envelope::{Correlation, Envelope, MetaData};
+//! use tagid::{CuidGenerator, Entity, Label};
+//!
+//! #[derive(Label)]
+//! struct Order(&'static str);
+//! impl Entity for Order { type IdGen = CuidGenerator; }
+//!
+//! let envelope = Envelope::from_entity(Order("17 widgets"));
+//! let record = envelope.into_record("orders").map_payload(|order| order.0);
+//! let future_record: FutureRecord<'_, str, str> = record.as_future_record();
+//! assert_eq!(future_record.payload, Some("17 widgets"));
+//!
+//! let metadata = MetaData::<Order, String>::from_kafka_headers(&record.headers).unwrap();
+//! assert_eq!(&metadata.correlation().id, &record.key);
+//! ```
+
+use crate::clock;
+use crate::envelope::metadata::{
+    CAUSATION_ID_KEY, CORRELATION_ID_KEY, MESSAGE_ID_KEY, RECV_TIMESTAMP_KEY,
+};
+use crate::envelope::{Correlation, Envelope, MetaData, MetaHeaderError, ReceivedAt};
+use crate::{Id, Label};
+use iso8601_timestamp::Timestamp;
+use rdkafka::message::{Header, Headers, OwnedHeaders, ToBytes};
+use rdkafka::producer::{BaseRecord, FutureRecord};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+impl<T, ID> MetaData<T, ID>
+where
+    ID: fmt::Display,
+{
+    /// Renders the metadata as Kafka record headers with UTF-8 values.
+    pub fn to_kafka_headers(&self) -> OwnedHeaders {
+        let insert = |headers: OwnedHeaders, key: &str, value: &str| {
+            headers.insert(Header {
+                key,
+                value: Some(value),
+            })
+        };
+
+        let mut headers = insert(
+            OwnedHeaders::new(),
+            CORRELATION_ID_KEY,
+            &self.correlation().id.to_string(),
+        );
+        headers = insert(headers, MESSAGE_ID_KEY, &self.message_id().id.to_string());
+        if let Some(causation_id) = self.causation_id() {
+            headers = insert(headers, CAUSATION_ID_KEY, &causation_id.id.to_string());
+        }
+        headers = insert(
+            headers,
+            RECV_TIMESTAMP_KEY,
+            &self.recv_timestamp().to_string(),
+        );
+
+        let mut custom = self.custom().clone();
+        custom.extend(self.extensions().projection());
+        custom
+            .iter()
+            .fold(headers, |headers, (key, value)| insert(headers, key, value))
+    }
+}
+
+impl<T, ID> MetaData<T, ID>
+where
+    T: Label,
+    ID: FromStr,
+    <ID as FromStr>::Err: fmt::Display,
+{
+    /// Extracts the metadata from Kafka record headers, which must include the correlation id.
+    /// The received timestamp defaults to now if absent, and headers without values are ignored.
+    pub fn from_kafka_headers<H: Headers>(headers: &H) -> Result<Self, MetaHeaderError> {
+        let mut custom = HashMap::with_capacity(headers.count());
+        for header in headers.iter() {
+            if let Some(value) = header.value {
+                let value = std::str::from_utf8(value)
+                    .map_err(|err| MetaHeaderError::invalid(header.key, err))?;
+                custom.insert(header.key.to_string(), value.to_string());
+            }
+        }
+
+        let mut id = |key: &'static str| {
+            custom
+                .remove(key)
+                .map(|rep| {
+                    ID::from_str(&rep)
+                        .map(Id::for_labeled)
+                        .map_err(|err| MetaHeaderError::invalid(key, err))
+                })
+                .transpose()
+        };
+        let correlation_id =
+            id(CORRELATION_ID_KEY)?.ok_or(MetaHeaderError::Missing(CORRELATION_ID_KEY))?;
+        let message_id = id(MESSAGE_ID_KEY)?;
+        let causation_id = id(CAUSATION_ID_KEY)?;
+        let recv_timestamp = custom
+            .remove(RECV_TIMESTAMP_KEY)
+            .map(|rep| {
+                Timestamp::parse(&rep).ok_or_else(|| {
+                    MetaHeaderError::invalid(RECV_TIMESTAMP_KEY, "not an ISO 8601 timestamp")
+                })
+            })
+            .transpose()?
+            .unwrap_or_else(clock::now_timestamp);
+
+        let custom = if custom.is_empty() {
+            None
+        } else {
+            Some(custom)
+        };
+        let mut metadata = Self::from_parts(correlation_id, recv_timestamp, custom);
+        if let Some(message_id) = message_id {
+            metadata = metadata.with_message_id(message_id);
+        }
+        Ok(match causation_id {
+            Some(causation_id) => metadata.with_causation_id(causation_id),
+            None => metadata,
+        })
+    }
+}
+
+/// An owned Kafka record of an envelope, keyed for partitioning by the correlation id.
+///
+/// Rekey the record, e.g., by the entity id, via [`with_key()`](Self::with_key). Producers send
+/// it via [`as_future_record()`](Self::as_future_record) or
+/// [`as_base_record()`](Self::as_base_record).
+#[derive(Debug, Clone)]
+pub struct KafkaRecord<P> {
+    pub topic: String,
+    pub key: String,
+    pub headers: OwnedHeaders,
+    pub payload: P,
+}
+
+impl<P> KafkaRecord<P> {
+    pub fn with_key(self, key: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            ..self
+        }
+    }
+
+    /// Maps the payload, e.g., serializing the envelope content into bytes.
+    pub fn map_payload<Q>(self, f: impl FnOnce(P) -> Q) -> KafkaRecord<Q> {
+        KafkaRecord {
+            topic: self.topic,
+            key: self.key,
+            headers: self.headers,
+            payload: f(self.payload),
+        }
+    }
+}
+
+impl<P, B> KafkaRecord<P>
+where
+    P: std::ops::Deref<Target = B>,
+    B: ToBytes + ?Sized,
+{
+    pub fn as_future_record(&self) -> FutureRecord<'_, str, B> {
+        FutureRecord::to(&self.topic)
+            .key(self.key.as_str())
+            .payload(&*self.payload)
+            .headers(self.headers.clone())
+    }
+
+    pub fn as_base_record(&self) -> BaseRecord<'_, str, B> {
+        BaseRecord::to(&self.topic)
+            .key(self.key.as_str())
+            .payload(&*self.payload)
+            .headers(self.headers.clone())
+    }
+}
+
+impl<T, ID> Envelope<T, ID>
+where
+    ID: fmt::Display,
+{
+    /// Converts the envelope into a Kafka record for the `topic`, with the metadata in the record
+    /// headers and the content as payload.
+    pub fn into_record(self, topic: impl Into<String>) -> KafkaRecord<T> {
+        let headers = self.metadata().to_kafka_headers();
+        let key = self.metadata().correlation().id.to_string();
+        KafkaRecord {
+            topic: topic.into(),
+            key,
+            headers,
+            payload: self.into_inner(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::id::IdGenerator;
+    use crate::CustomLabeling;
+    use claim::*;
+    use pretty_assertions::assert_eq;
+    use rdkafka::message::Message;
+    use rdkafka::message::{OwnedMessage, Timestamp as KafkaTimestamp};
+
+    #[derive(Debug, PartialEq)]
+    struct Order(u32);
+
+    impl Label for Order {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("Order")
+        }
+    }
+
+    struct ReplyGenerator;
+
+    impl IdGenerator for ReplyGenerator {
+        type IdType = u64;
+
+        fn next_id_rep() -> Self::IdType {
+            18
+        }
+    }
+
+    #[test]
+    fn test_envelope_kafka_roundtrip() {
+        let ts = Timestamp::parse("2022-11-30T03:43:18.068Z").unwrap();
+        let mut custom = HashMap::new();
+        custom.insert("Tenant".to_string(), "acme".to_string());
+        let command = Envelope::from_parts(
+            MetaData::from_parts(Id::<Order, u64>::for_labeled(17), ts, Some(custom)),
+            Order(1),
+        );
+        let mut reply = Envelope::caused_by::<ReplyGenerator, _>(Order(2), &command);
+        let metadata = reply.metadata().clone().with_recv_timestamp(ts);
+        reply.adopt_metadata(metadata.clone());
+
+        let record = reply
+            .into_record("orders")
+            .with_key("order-2")
+            .map_payload(|order| order.0.to_be_bytes().to_vec());
+        assert_eq!(record.key, "order-2");
+        let future_record = record.as_future_record();
+        assert_eq!(future_record.topic, "orders");
+        assert_eq!(future_record.payload, Some(&[0, 0, 0, 2][..]));
+        assert_eq!(future_record.headers.map(|h| h.count()), Some(4));
+
+        let message = OwnedMessage::new(
+            Some(record.payload.clone()),
+            Some(record.key.clone().into_bytes()),
+            record.topic.clone(),
+            KafkaTimestamp::NotAvailable,
+            0,
+            0,
+            Some(command.metadata().to_kafka_headers()),
+        );
+        let headers = assert_some!(message.headers());
+        let actual = assert_ok!(MetaData::<Order, u64>::from_kafka_headers(headers));
+        assert_eq!(actual.correlation().id, 17);
+        assert_eq!(actual.recv_timestamp(), ts);
+        assert_eq!(actual.custom()["Tenant"], "acme");
+
+        let actual = assert_ok!(MetaData::<Order, u64>::from_kafka_headers(&record.headers));
+        assert_eq!(actual, metadata);
+        assert_eq!(actual.message_id().id, 18);
+        assert_eq!(actual.causation_id().map(|id| id.id), Some(17));
+        assert!(actual.custom().is_empty());
+    }
+
+    #[test]
+    fn test_kafka_headers_errors() {
+        let headers = OwnedHeaders::new();
+        assert_eq!(
+            assert_err!(MetaData::<Order, u64>::from_kafka_headers(&headers)),
+            MetaHeaderError::Missing(CORRELATION_ID_KEY)
+        );
+
+        let headers = headers.insert(Header {
+            key: CORRELATION_ID_KEY,
+            value: Some("seventeen"),
+        });
+        assert_matches!(
+            MetaData::<Order, u64>::from_kafka_headers(&headers),
+            Err(MetaHeaderError::Invalid { name, .. }) if name == CORRELATION_ID_KEY
+        );
+
+        let headers = OwnedHeaders::new().insert(Header {
+            key: CORRELATION_ID_KEY,
+            value: Some(&[0xff_u8][..]),
+        });
+        assert_matches!(
+            MetaData::<Order, u64>::from_kafka_headers(&headers),
+            Err(MetaHeaderError::Invalid { .. })
+        );
+    }
+}
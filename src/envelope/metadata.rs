@@ -1,26 +1,72 @@
-use crate::envelope::{Correlation, ReceivedAt};
+use crate::clock;
+use crate::envelope::{Correlation, Extensions, MetaExtension, ReceivedAt};
 use crate::id::IdGenerator;
 use crate::{Entity, Id, Label, Labeling};
 use iso8601_timestamp::Timestamp;
 use pretty_type_name::pretty_type_name;
-use serde::{de, Deserialize, Deserializer, Serialize};
+use serde::ser::SerializeStruct;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use std::cmp::Ordering;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt;
 use std::marker::PhantomData;
 use std::str::FromStr;
+use thiserror::Error;
 
 #[cfg(feature = "functional")]
 use frunk::{Monoid, Semigroup};
 
 pub const CORRELATION_ID_KEY: &str = "correlation_id";
+pub const CAUSATION_ID_KEY: &str = "causation_id";
+pub const MESSAGE_ID_KEY: &str = "message_id";
 pub const RECV_TIMESTAMP_KEY: &str = "recv_timestamp";
+pub const DELIVERY_ATTEMPT_KEY: &str = "delivery_attempt";
+pub const FIRST_RECEIVED_AT_KEY: &str = "first_received_at";
+pub const NOT_BEFORE_KEY: &str = "not_before";
+pub const PROCESSING_DEADLINE_KEY: &str = "processing_deadline";
+pub const ACTOR_ID_KEY: &str = "actor_id";
+pub const TENANT_ID_KEY: &str = "tenant_id";
+pub const SCHEMA_VERSION_KEY: &str = "schema_version";
+
+/// Error converting transport headers, such as HTTP or Kafka record headers, into metadata.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum MetaHeaderError {
+    #[error("missing {0} header")]
+    Missing(&'static str),
+
+    #[error("invalid {name} header: {reason}")]
+    Invalid { name: String, reason: String },
+}
+
+#[cfg(any(feature = "amqp", feature = "http", feature = "kafka"))]
+impl MetaHeaderError {
+    pub(crate) fn invalid(name: impl Into<String>, reason: impl fmt::Display) -> Self {
+        Self::Invalid {
+            name: name.into(),
+            reason: reason.to_string(),
+        }
+    }
+}
 
-pub trait IntoMetaData {
+/// Lenient conversion of transport metadata into [`MetaData`]: a missing or unparsable
+/// correlation id is generated via `G` and a missing or unparsable received timestamp defaults
+/// to now.
+pub trait IntoMetaData: Sized {
     type CorrelatedType: Label;
 
     fn into_metadata<G>(self) -> MetaData<Self::CorrelatedType, G::IdType>
     where
+        G: IdGenerator,
+        G::IdType: FromStr,
+    {
+        self.into_metadata_for::<Self::CorrelatedType, G>()
+    }
+
+    /// Converts into metadata correlated to `T` rather than the
+    /// [`CorrelatedType`](IntoMetaData::CorrelatedType).
+    fn into_metadata_for<T, G>(self) -> MetaData<T, G::IdType>
+    where
+        T: Label,
         G: IdGenerator,
         G::IdType: FromStr;
 }
@@ -28,31 +74,91 @@ pub trait IntoMetaData {
 impl IntoMetaData for HashMap<String, String> {
     type CorrelatedType = ();
 
-    fn into_metadata<G>(mut self) -> MetaData<Self::CorrelatedType, G::IdType>
+    fn into_metadata_for<T, G>(mut self) -> MetaData<T, G::IdType>
     where
+        T: Label,
         G: IdGenerator,
         G::IdType: FromStr,
     {
+        let label = T::labeler();
         let id_rep = self
             .remove(CORRELATION_ID_KEY)
             .and_then(|rep| G::IdType::from_str(&rep).ok())
             .unwrap_or_else(|| G::next_id_rep());
-        let correlation_id = Id::direct(<() as Label>::labeler().label(), id_rep);
+        let correlation_id = Id::direct_labeled(label.label(), id_rep);
+        let mut labeled_id = |key: &str| {
+            self.remove(key)
+                .and_then(|rep| G::IdType::from_str(&rep).ok())
+                .map(|id_rep| Id::direct_labeled(label.label(), id_rep))
+        };
+        let causation_id = labeled_id(CAUSATION_ID_KEY);
+        let message_id = labeled_id(MESSAGE_ID_KEY);
+        let mut bare_id = |key: &str| {
+            self.remove(key)
+                .and_then(|rep| G::IdType::from_str(&rep).ok())
+        };
+        let actor_id = bare_id(ACTOR_ID_KEY);
+        let tenant_id = bare_id(TENANT_ID_KEY);
 
         let recv_timestamp = self
             .remove(RECV_TIMESTAMP_KEY)
-            .map_or_else(Timestamp::now_utc, |ts| {
-                Timestamp::parse(ts.as_str()).unwrap_or_else(Timestamp::now_utc)
+            .map_or_else(clock::now_timestamp, |ts| {
+                Timestamp::parse(ts.as_str()).unwrap_or_else(clock::now_timestamp)
             });
 
+        let delivery_attempt = self
+            .remove(DELIVERY_ATTEMPT_KEY)
+            .and_then(|rep| rep.parse().ok());
+        let first_received_at = self
+            .remove(FIRST_RECEIVED_AT_KEY)
+            .and_then(|ts| Timestamp::parse(ts.as_str()));
+        let not_before = self
+            .remove(NOT_BEFORE_KEY)
+            .and_then(|ts| Timestamp::parse(ts.as_str()));
+        let processing_deadline = self
+            .remove(PROCESSING_DEADLINE_KEY)
+            .and_then(|ts| Timestamp::parse(ts.as_str()));
+        let schema_version = self
+            .remove(SCHEMA_VERSION_KEY)
+            .and_then(|rep| rep.parse().ok());
+
         let custom = if !self.is_empty() { Some(self) } else { None };
 
-        MetaData::from_parts(correlation_id, recv_timestamp, custom)
+        let metadata = MetaData::from_parts(correlation_id, recv_timestamp, custom);
+        MetaData {
+            causation_id,
+            delivery_attempt: delivery_attempt.unwrap_or(FIRST_DELIVERY_ATTEMPT),
+            first_received_at,
+            not_before,
+            processing_deadline,
+            actor_id,
+            tenant_id,
+            schema_version,
+            message_id,
+            ..metadata
+        }
+    }
+}
+
+impl IntoMetaData for BTreeMap<String, String> {
+    type CorrelatedType = ();
+
+    fn into_metadata_for<T, G>(self) -> MetaData<T, G::IdType>
+    where
+        T: Label,
+        G: IdGenerator,
+        G::IdType: FromStr,
+    {
+        self.into_iter()
+            .collect::<HashMap<_, _>>()
+            .into_metadata_for::<T, G>()
     }
 }
 
 /// A set of metdata regarding the envelope contents.
-#[derive(Serialize)]
+///
+/// Typed [`Extensions`] are carried alongside the `custom` string map and serialized through
+/// their projection into it.
 pub struct MetaData<T, ID>
 where
     T: ?Sized,
@@ -60,6 +166,110 @@ where
     correlation_id: Id<T, ID>,
     recv_timestamp: Timestamp,
     custom: HashMap<String, String>,
+
+    /// The message id of the message that caused this one, if any.
+    causation_id: Option<Id<T, ID>>,
+
+    delivery_attempt: u32,
+
+    /// When the message was first received, if redelivered.
+    first_received_at: Option<Timestamp>,
+
+    not_before: Option<Timestamp>,
+
+    /// When the message must be processed by, after which it is expired.
+    processing_deadline: Option<Timestamp>,
+
+    /// The id of who or what initiated the message, for auditing. Stored bare, since it is not
+    /// an id of the correlated type; accessors label it as requested.
+    actor_id: Option<ID>,
+
+    /// The id of the tenant the message belongs to, stored bare as `actor_id` is.
+    tenant_id: Option<ID>,
+
+    /// The schema version of the content, by which consumers upcast older content.
+    schema_version: Option<u32>,
+
+    /// The id of this message, unless it is the first message of its correlation, which is
+    /// identified by the correlation id.
+    message_id: Option<Id<T, ID>>,
+
+    extensions: Extensions,
+}
+
+/// The delivery attempt of a message on its first delivery.
+const FIRST_DELIVERY_ATTEMPT: u32 = 1;
+
+impl<T, ID> Serialize for MetaData<T, ID>
+where
+    T: ?Sized,
+    ID: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // Optional fields are serialized up to the last one set, so sequence formats keep their
+        // positions.
+        let optional_fields = if self.message_id.is_some() {
+            9
+        } else if self.schema_version.is_some() {
+            8
+        } else if self.tenant_id.is_some() {
+            7
+        } else if self.actor_id.is_some() {
+            6
+        } else if self.processing_deadline.is_some() {
+            5
+        } else if self.not_before.is_some() {
+            4
+        } else if self.first_received_at.is_some() {
+            3
+        } else if self.delivery_attempt != FIRST_DELIVERY_ATTEMPT {
+            2
+        } else {
+            usize::from(self.causation_id.is_some())
+        };
+
+        let mut state = serializer.serialize_struct("MetaData", 3 + optional_fields)?;
+        state.serialize_field(META_CORRELATION_ID, &self.correlation_id)?;
+        state.serialize_field(META_RECV_TIMESTAMP, &self.recv_timestamp)?;
+        if self.extensions.is_empty() {
+            state.serialize_field(META_CUSTOM, &self.custom)?;
+        } else {
+            let mut custom = self.custom.clone();
+            custom.extend(self.extensions.projection());
+            state.serialize_field(META_CUSTOM, &custom)?;
+        }
+        if 0 < optional_fields {
+            state.serialize_field(META_CAUSATION_ID, &self.causation_id)?;
+        }
+        if 1 < optional_fields {
+            state.serialize_field(META_DELIVERY_ATTEMPT, &self.delivery_attempt)?;
+        }
+        if 2 < optional_fields {
+            state.serialize_field(META_FIRST_RECEIVED_AT, &self.first_received_at)?;
+        }
+        if 3 < optional_fields {
+            state.serialize_field(META_NOT_BEFORE, &self.not_before)?;
+        }
+        if 4 < optional_fields {
+            state.serialize_field(META_PROCESSING_DEADLINE, &self.processing_deadline)?;
+        }
+        if 5 < optional_fields {
+            state.serialize_field(META_ACTOR_ID, &self.actor_id)?;
+        }
+        if 6 < optional_fields {
+            state.serialize_field(META_TENANT_ID, &self.tenant_id)?;
+        }
+        if 7 < optional_fields {
+            state.serialize_field(META_SCHEMA_VERSION, &self.schema_version)?;
+        }
+        if 8 < optional_fields {
+            state.serialize_field(META_MESSAGE_ID, &self.message_id)?;
+        }
+        state.end()
+    }
 }
 
 impl<T, ID> fmt::Debug for MetaData<T, ID>
@@ -70,12 +280,43 @@ where
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let mut debug = f.debug_struct("MetaData");
         debug.field("correlation", &self.correlation_id);
+        if let Some(message_id) = &self.message_id {
+            debug.field("message", message_id);
+        }
+        if let Some(causation_id) = &self.causation_id {
+            debug.field("causation", causation_id);
+        }
         debug.field("recv_timestamp", &self.recv_timestamp.to_string());
+        if self.delivery_attempt != FIRST_DELIVERY_ATTEMPT {
+            debug.field("delivery_attempt", &self.delivery_attempt);
+        }
+        if let Some(first_received_at) = self.first_received_at {
+            debug.field("first_received_at", &first_received_at.to_string());
+        }
+        if let Some(not_before) = self.not_before {
+            debug.field("not_before", &not_before.to_string());
+        }
+        if let Some(processing_deadline) = self.processing_deadline {
+            debug.field("processing_deadline", &processing_deadline.to_string());
+        }
+        if let Some(actor_id) = &self.actor_id {
+            debug.field("actor_id", actor_id);
+        }
+        if let Some(tenant_id) = &self.tenant_id {
+            debug.field("tenant_id", tenant_id);
+        }
+        if let Some(schema_version) = self.schema_version {
+            debug.field("schema_version", &schema_version);
+        }
 
         if !self.custom.is_empty() {
             debug.field("custom", &self.custom);
         }
 
+        if !self.extensions.is_empty() {
+            debug.field("extensions", &self.extensions);
+        }
+
         debug.finish()
     }
 }
@@ -99,7 +340,7 @@ where
     E: Entity + Label,
 {
     fn default() -> Self {
-        Self::from_parts(<E as Entity>::next_id(), Timestamp::now_utc(), None)
+        Self::from_parts(<E as Entity>::next_id(), clock::now_timestamp(), None)
     }
 }
 
@@ -113,6 +354,135 @@ impl<T, ID> MetaData<T, ID> {
             correlation_id,
             recv_timestamp,
             custom: custom.unwrap_or_default(),
+            causation_id: None,
+            delivery_attempt: FIRST_DELIVERY_ATTEMPT,
+            first_received_at: None,
+            not_before: None,
+            processing_deadline: None,
+            actor_id: None,
+            tenant_id: None,
+            schema_version: None,
+            message_id: None,
+            extensions: Extensions::default(),
+        }
+    }
+
+    pub const fn custom(&self) -> &HashMap<String, String> {
+        &self.custom
+    }
+
+    /// Adds a custom entry, e.g., to annotate the message as it passes a processing stage.
+    pub fn with_custom(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.custom.insert(key.into(), value.into());
+        self
+    }
+
+    pub const fn extensions(&self) -> &Extensions {
+        &self.extensions
+    }
+
+    pub const fn extensions_mut(&mut self) -> &mut Extensions {
+        &mut self.extensions
+    }
+
+    pub fn with_extension<E: Clone + Send + Sync + 'static>(mut self, extension: E) -> Self {
+        self.extensions.insert(extension);
+        self
+    }
+
+    pub fn with_projected_extension<E: MetaExtension>(mut self, extension: E) -> Self {
+        self.extensions.insert_projected(extension);
+        self
+    }
+
+    /// Restores the typed extension from its string form in the `custom` map, e.g., after
+    /// deserialization, moving it out of `custom`.
+    pub fn restore_extension<E: MetaExtension>(&mut self) -> Option<&E> {
+        if self.extensions.restore::<E>(&self.custom).is_some() {
+            self.custom.remove(E::KEY);
+        }
+        self.extensions.get()
+    }
+
+    #[allow(clippy::missing_const_for_fn)]
+    pub fn with_correlation_id(self, correlation_id: Id<T, ID>) -> Self {
+        Self {
+            correlation_id,
+            ..self
+        }
+    }
+
+    /// The id of this message, which messages it causes record as their causation id. The first
+    /// message of a correlation is identified by the correlation id, while messages caused by
+    /// another are assigned their own, e.g., via [`Envelope::caused_by()`].
+    ///
+    /// [`Envelope::caused_by()`]: crate::envelope::Envelope::caused_by
+    pub fn message_id(&self) -> &Id<T, ID> {
+        self.message_id.as_ref().unwrap_or(&self.correlation_id)
+    }
+
+    #[allow(clippy::missing_const_for_fn)]
+    pub fn with_message_id(self, message_id: Id<T, ID>) -> Self {
+        Self {
+            message_id: Some(message_id),
+            ..self
+        }
+    }
+
+    /// The message id of the message that caused this one, if any.
+    pub const fn causation_id(&self) -> Option<&Id<T, ID>> {
+        self.causation_id.as_ref()
+    }
+
+    #[allow(clippy::missing_const_for_fn)]
+    pub fn with_causation_id(self, causation_id: Id<T, ID>) -> Self {
+        Self {
+            causation_id: Some(causation_id),
+            ..self
+        }
+    }
+
+    /// The id of who or what initiated the message, e.g., a user or service, labeled as `A`.
+    pub fn actor_id<A: Label>(&self) -> Option<Id<A, ID>>
+    where
+        ID: Clone,
+    {
+        self.actor_id.clone().map(Id::for_labeled)
+    }
+
+    pub fn with_actor_id<A>(self, actor_id: Id<A, ID>) -> Self {
+        Self {
+            actor_id: Some(actor_id.id),
+            ..self
+        }
+    }
+
+    /// The id of the tenant the message belongs to, labeled as `A`.
+    pub fn tenant_id<A: Label>(&self) -> Option<Id<A, ID>>
+    where
+        ID: Clone,
+    {
+        self.tenant_id.clone().map(Id::for_labeled)
+    }
+
+    pub fn with_tenant_id<A>(self, tenant_id: Id<A, ID>) -> Self {
+        Self {
+            tenant_id: Some(tenant_id.id),
+            ..self
+        }
+    }
+
+    /// The schema version of the content, if recorded, so consumers can dispatch older content
+    /// to the right upcaster.
+    pub const fn schema_version(&self) -> Option<u32> {
+        self.schema_version
+    }
+
+    #[allow(clippy::missing_const_for_fn)]
+    pub fn with_schema_version(self, schema_version: u32) -> Self {
+        Self {
+            schema_version: Some(schema_version),
+            ..self
         }
     }
 
@@ -124,6 +494,99 @@ impl<T, ID> MetaData<T, ID> {
         }
     }
 
+    /// The delivery attempt of the message, starting at 1 on its first delivery.
+    pub const fn delivery_attempt(&self) -> u32 {
+        self.delivery_attempt
+    }
+
+    pub const fn is_redelivery(&self) -> bool {
+        FIRST_DELIVERY_ATTEMPT < self.delivery_attempt
+    }
+
+    /// When the message was first received, which is the received timestamp on its first
+    /// delivery.
+    pub fn first_received_at(&self) -> Timestamp {
+        self.first_received_at.unwrap_or(self.recv_timestamp)
+    }
+
+    /// The time before which the message should not be processed, e.g., to back off a retry.
+    pub const fn not_before(&self) -> Option<Timestamp> {
+        self.not_before
+    }
+
+    #[allow(clippy::missing_const_for_fn)]
+    pub fn with_not_before(self, not_before: Timestamp) -> Self {
+        Self {
+            not_before: Some(not_before),
+            ..self
+        }
+    }
+
+    /// Sets the delivery attempt, e.g., from a delivery count tracked by the broker.
+    #[allow(clippy::missing_const_for_fn)]
+    pub fn with_delivery_attempt(self, delivery_attempt: u32) -> Self {
+        Self {
+            delivery_attempt,
+            ..self
+        }
+    }
+
+    /// Whether the message is due for processing at `now`, i.e., it is not held back by
+    /// [`not_before()`](Self::not_before).
+    pub fn is_due_at(&self, now: Timestamp) -> bool {
+        self.not_before.is_none_or(|not_before| not_before <= now)
+    }
+
+    /// How long ago the message was received, according to the [`clock`](crate::clock); zero if
+    /// its received timestamp is in the future.
+    pub fn elapsed_since_recv(&self) -> std::time::Duration {
+        clock::now_timestamp()
+            .duration_since(self.recv_timestamp)
+            .try_into()
+            .unwrap_or_default()
+    }
+
+    /// The time by which the message must be processed, if any.
+    pub const fn processing_deadline(&self) -> Option<Timestamp> {
+        self.processing_deadline
+    }
+
+    #[allow(clippy::missing_const_for_fn)]
+    pub fn with_processing_deadline(self, processing_deadline: Timestamp) -> Self {
+        Self {
+            processing_deadline: Some(processing_deadline),
+            ..self
+        }
+    }
+
+    /// Whether the message is past its [`processing_deadline()`](Self::processing_deadline) at
+    /// `now`.
+    pub fn is_expired_at(&self, now: Timestamp) -> bool {
+        self.processing_deadline
+            .is_some_and(|processing_deadline| processing_deadline < now)
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.is_expired_at(clock::now_timestamp())
+    }
+
+    /// Records the redelivery of the message received at `recv_timestamp`, incrementing the
+    /// delivery attempt, keeping the first received timestamp and clearing `not_before`.
+    #[allow(clippy::missing_const_for_fn)]
+    pub fn redelivered_at(self, recv_timestamp: Timestamp) -> Self {
+        Self {
+            delivery_attempt: self.delivery_attempt.saturating_add(1),
+            first_received_at: Some(self.first_received_at()),
+            recv_timestamp,
+            not_before: None,
+            ..self
+        }
+    }
+
+    pub fn redelivered(self) -> Self {
+        self.redelivered_at(clock::now_timestamp())
+    }
+
     #[allow(clippy::missing_const_for_fn)]
     pub fn into_parts(self) -> (Id<T, ID>, Timestamp, HashMap<String, String>) {
         (self.correlation_id, self.recv_timestamp, self.custom)
@@ -139,6 +602,16 @@ where
             correlation_id: self.correlation_id.relabel(),
             recv_timestamp: self.recv_timestamp,
             custom: self.custom,
+            causation_id: self.causation_id.map(|id| id.relabel()),
+            delivery_attempt: self.delivery_attempt,
+            first_received_at: self.first_received_at,
+            not_before: self.not_before,
+            processing_deadline: self.processing_deadline,
+            actor_id: self.actor_id,
+            tenant_id: self.tenant_id,
+            schema_version: self.schema_version,
+            message_id: self.message_id.map(|id| id.relabel()),
+            extensions: self.extensions,
         }
     }
 }
@@ -167,6 +640,16 @@ where
             correlation_id: self.correlation_id.clone(),
             recv_timestamp: self.recv_timestamp,
             custom: self.custom.clone(),
+            causation_id: self.causation_id.clone(),
+            delivery_attempt: self.delivery_attempt,
+            first_received_at: self.first_received_at,
+            not_before: self.not_before,
+            processing_deadline: self.processing_deadline,
+            actor_id: self.actor_id.clone(),
+            tenant_id: self.tenant_id.clone(),
+            schema_version: self.schema_version,
+            message_id: self.message_id.clone(),
+            extensions: self.extensions.clone(),
         }
     }
 }
@@ -255,17 +738,54 @@ where
     ID: fmt::Display,
 {
     fn from(meta: MetaData<T, ID>) -> Self {
-        let mut core = Self::with_capacity(2);
+        let mut core = Self::with_capacity(3);
         core.insert(
             CORRELATION_ID_KEY.to_string(),
             meta.correlation_id.id.to_string(),
         );
+        if let Some(message_id) = meta.message_id {
+            core.insert(MESSAGE_ID_KEY.to_string(), message_id.id.to_string());
+        }
+        if let Some(causation_id) = meta.causation_id {
+            core.insert(CAUSATION_ID_KEY.to_string(), causation_id.id.to_string());
+        }
         core.insert(
             RECV_TIMESTAMP_KEY.to_string(),
             meta.recv_timestamp.to_string(),
         );
+        if meta.delivery_attempt != FIRST_DELIVERY_ATTEMPT {
+            core.insert(
+                DELIVERY_ATTEMPT_KEY.to_string(),
+                meta.delivery_attempt.to_string(),
+            );
+        }
+        if let Some(first_received_at) = meta.first_received_at {
+            core.insert(
+                FIRST_RECEIVED_AT_KEY.to_string(),
+                first_received_at.to_string(),
+            );
+        }
+        if let Some(not_before) = meta.not_before {
+            core.insert(NOT_BEFORE_KEY.to_string(), not_before.to_string());
+        }
+        if let Some(processing_deadline) = meta.processing_deadline {
+            core.insert(
+                PROCESSING_DEADLINE_KEY.to_string(),
+                processing_deadline.to_string(),
+            );
+        }
+        if let Some(actor_id) = meta.actor_id {
+            core.insert(ACTOR_ID_KEY.to_string(), actor_id.to_string());
+        }
+        if let Some(tenant_id) = meta.tenant_id {
+            core.insert(TENANT_ID_KEY.to_string(), tenant_id.to_string());
+        }
+        if let Some(schema_version) = meta.schema_version {
+            core.insert(SCHEMA_VERSION_KEY.to_string(), schema_version.to_string());
+        }
 
         let mut result = meta.custom;
+        result.extend(meta.extensions.projection());
         result.extend(core);
 
         result
@@ -275,7 +795,29 @@ where
 const META_CORRELATION_ID: &str = "correlation_id";
 const META_RECV_TIMESTAMP: &str = "recv_timestamp";
 const META_CUSTOM: &str = "custom";
-const FIELDS: [&str; 3] = [META_CORRELATION_ID, META_RECV_TIMESTAMP, META_CUSTOM];
+const META_CAUSATION_ID: &str = "causation_id";
+const META_DELIVERY_ATTEMPT: &str = "delivery_attempt";
+const META_FIRST_RECEIVED_AT: &str = "first_received_at";
+const META_NOT_BEFORE: &str = "not_before";
+const META_PROCESSING_DEADLINE: &str = "processing_deadline";
+const META_ACTOR_ID: &str = "actor_id";
+const META_TENANT_ID: &str = "tenant_id";
+const META_SCHEMA_VERSION: &str = "schema_version";
+const META_MESSAGE_ID: &str = "message_id";
+const FIELDS: [&str; 12] = [
+    META_CORRELATION_ID,
+    META_RECV_TIMESTAMP,
+    META_CUSTOM,
+    META_CAUSATION_ID,
+    META_DELIVERY_ATTEMPT,
+    META_FIRST_RECEIVED_AT,
+    META_NOT_BEFORE,
+    META_PROCESSING_DEADLINE,
+    META_ACTOR_ID,
+    META_TENANT_ID,
+    META_SCHEMA_VERSION,
+    META_MESSAGE_ID,
+];
 
 impl<'de, T, ID> Deserialize<'de> for MetaData<T, ID>
 where
@@ -290,6 +832,15 @@ where
             CorrelationId,
             RecvTimestamp,
             Custom,
+            CausationId,
+            DeliveryAttempt,
+            FirstReceivedAt,
+            NotBefore,
+            ProcessingDeadline,
+            ActorId,
+            TenantId,
+            SchemaVersion,
+            MessageId,
         }
 
         impl<'de> Deserialize<'de> for Field {
@@ -303,7 +854,12 @@ where
                     type Value = Field;
 
                     fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-                        f.write_str("`correlation_id`, `recv_timestamp` or `custom`")
+                        f.write_str(
+                            "`correlation_id`, `recv_timestamp`, `custom`, `causation_id`, \
+                             `delivery_attempt`, `first_received_at`, `not_before`, \
+                             `processing_deadline`, `actor_id`, `tenant_id`, `schema_version` or \
+                             `message_id`",
+                        )
                     }
 
                     fn visit_str<E>(self, value: &str) -> Result<Field, E>
@@ -314,6 +870,15 @@ where
                             META_CORRELATION_ID => Ok(Self::Value::CorrelationId),
                             META_RECV_TIMESTAMP => Ok(Self::Value::RecvTimestamp),
                             META_CUSTOM => Ok(Self::Value::Custom),
+                            META_CAUSATION_ID => Ok(Self::Value::CausationId),
+                            META_DELIVERY_ATTEMPT => Ok(Self::Value::DeliveryAttempt),
+                            META_FIRST_RECEIVED_AT => Ok(Self::Value::FirstReceivedAt),
+                            META_NOT_BEFORE => Ok(Self::Value::NotBefore),
+                            META_PROCESSING_DEADLINE => Ok(Self::Value::ProcessingDeadline),
+                            META_ACTOR_ID => Ok(Self::Value::ActorId),
+                            META_TENANT_ID => Ok(Self::Value::TenantId),
+                            META_SCHEMA_VERSION => Ok(Self::Value::SchemaVersion),
+                            META_MESSAGE_ID => Ok(Self::Value::MessageId),
                             _ => Err(de::Error::unknown_field(value, &FIELDS)),
                         }
                     }
@@ -359,11 +924,28 @@ where
                 let custom: HashMap<String, String> = seq
                     .next_element()?
                     .ok_or_else(|| de::Error::invalid_length(2, &self))?;
-                Ok(MetaData::from_parts(
-                    correlation_id,
-                    recv_timestamp,
-                    Some(custom),
-                ))
+                let causation_id: Option<Option<Id<T0, ID0>>> = seq.next_element()?;
+                let delivery_attempt: Option<u32> = seq.next_element()?;
+                let first_received_at: Option<Option<Timestamp>> = seq.next_element()?;
+                let not_before: Option<Option<Timestamp>> = seq.next_element()?;
+                let processing_deadline: Option<Option<Timestamp>> = seq.next_element()?;
+                let actor_id: Option<Option<ID0>> = seq.next_element()?;
+                let tenant_id: Option<Option<ID0>> = seq.next_element()?;
+                let schema_version: Option<Option<u32>> = seq.next_element()?;
+                let message_id: Option<Option<Id<T0, ID0>>> = seq.next_element()?;
+                let metadata = MetaData::from_parts(correlation_id, recv_timestamp, Some(custom));
+                Ok(MetaData {
+                    causation_id: causation_id.flatten(),
+                    delivery_attempt: delivery_attempt.unwrap_or(FIRST_DELIVERY_ATTEMPT),
+                    first_received_at: first_received_at.flatten(),
+                    not_before: not_before.flatten(),
+                    processing_deadline: processing_deadline.flatten(),
+                    actor_id: actor_id.flatten(),
+                    tenant_id: tenant_id.flatten(),
+                    schema_version: schema_version.flatten(),
+                    message_id: message_id.flatten(),
+                    ..metadata
+                })
             }
 
             fn visit_map<V>(self, mut map: V) -> Result<Self::Value, V::Error>
@@ -373,6 +955,15 @@ where
                 let mut correlation_id = None;
                 let mut recv_timestamp = None;
                 let mut custom = None;
+                let mut causation_id = None;
+                let mut delivery_attempt = None;
+                let mut first_received_at = None;
+                let mut not_before = None;
+                let mut processing_deadline = None;
+                let mut actor_id = None;
+                let mut tenant_id = None;
+                let mut schema_version = None;
+                let mut message_id = None;
 
                 while let Some(key) = map.next_key()? {
                     match key {
@@ -396,6 +987,69 @@ where
                             }
                             custom = Some(map.next_value()?);
                         }
+
+                        Field::CausationId => {
+                            if causation_id.is_some() {
+                                return Err(de::Error::duplicate_field(META_CAUSATION_ID));
+                            }
+                            causation_id = Some(map.next_value()?);
+                        }
+
+                        Field::DeliveryAttempt => {
+                            if delivery_attempt.is_some() {
+                                return Err(de::Error::duplicate_field(META_DELIVERY_ATTEMPT));
+                            }
+                            delivery_attempt = Some(map.next_value()?);
+                        }
+
+                        Field::FirstReceivedAt => {
+                            if first_received_at.is_some() {
+                                return Err(de::Error::duplicate_field(META_FIRST_RECEIVED_AT));
+                            }
+                            first_received_at = Some(map.next_value()?);
+                        }
+
+                        Field::NotBefore => {
+                            if not_before.is_some() {
+                                return Err(de::Error::duplicate_field(META_NOT_BEFORE));
+                            }
+                            not_before = Some(map.next_value()?);
+                        }
+
+                        Field::ProcessingDeadline => {
+                            if processing_deadline.is_some() {
+                                return Err(de::Error::duplicate_field(META_PROCESSING_DEADLINE));
+                            }
+                            processing_deadline = Some(map.next_value()?);
+                        }
+
+                        Field::ActorId => {
+                            if actor_id.is_some() {
+                                return Err(de::Error::duplicate_field(META_ACTOR_ID));
+                            }
+                            actor_id = Some(map.next_value()?);
+                        }
+
+                        Field::TenantId => {
+                            if tenant_id.is_some() {
+                                return Err(de::Error::duplicate_field(META_TENANT_ID));
+                            }
+                            tenant_id = Some(map.next_value()?);
+                        }
+
+                        Field::SchemaVersion => {
+                            if schema_version.is_some() {
+                                return Err(de::Error::duplicate_field(META_SCHEMA_VERSION));
+                            }
+                            schema_version = Some(map.next_value()?);
+                        }
+
+                        Field::MessageId => {
+                            if message_id.is_some() {
+                                return Err(de::Error::duplicate_field(META_MESSAGE_ID));
+                            }
+                            message_id = Some(map.next_value()?);
+                        }
                     }
                 }
 
@@ -405,11 +1059,19 @@ where
                     recv_timestamp.ok_or_else(|| de::Error::missing_field(META_RECV_TIMESTAMP))?;
                 let custom: HashMap<String, String> =
                     custom.ok_or_else(|| de::Error::missing_field(META_CUSTOM))?;
-                Ok(MetaData::from_parts(
-                    correlation_id,
-                    recv_timestamp,
-                    Some(custom),
-                ))
+                let metadata = MetaData::from_parts(correlation_id, recv_timestamp, Some(custom));
+                Ok(MetaData {
+                    causation_id: causation_id.flatten(),
+                    delivery_attempt: delivery_attempt.unwrap_or(FIRST_DELIVERY_ATTEMPT),
+                    first_received_at: first_received_at.flatten(),
+                    not_before: not_before.flatten(),
+                    processing_deadline: processing_deadline.flatten(),
+                    actor_id: actor_id.flatten(),
+                    tenant_id: tenant_id.flatten(),
+                    schema_version: schema_version.flatten(),
+                    message_id: message_id.flatten(),
+                    ..metadata
+                })
             }
         }
 
@@ -422,6 +1084,7 @@ mod tests {
     use super::*;
     use crate::envelope::Envelope;
     use crate::{Entity, Label, Labeling, MakeLabeling};
+    use claim::*;
     use once_cell::sync::Lazy;
     use pretty_assertions::assert_eq;
     use serde_test::Configure;
@@ -434,15 +1097,15 @@ mod tests {
         MetaData::default().with_recv_timestamp(ts)
     });
 
+    static NEXT_ID: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+
     struct TestGenerator;
     impl IdGenerator for TestGenerator {
         type IdType = String;
 
         fn next_id_rep() -> Self::IdType {
-            std::time::SystemTime::UNIX_EPOCH
-                .elapsed()
-                .unwrap()
-                .as_millis()
+            NEXT_ID
+                .fetch_add(1, std::sync::atomic::Ordering::Relaxed)
                 .to_string()
         }
     }
@@ -534,6 +1197,377 @@ mod tests {
         assert_eq!(actual.as_ref(), &expected);
     }
 
+    #[test]
+    fn test_envelope_zip() {
+        let ts = Timestamp::parse(METADATA_TS).unwrap();
+        let later = ts + std::time::Duration::from_secs(60);
+        let data = Envelope::from_parts(META_DATA.clone(), TestData(13));
+        let container = Envelope::from_parts(
+            META_DATA.clone().relabel().with_recv_timestamp(later),
+            TestContainer(TestData(17)),
+        );
+
+        let zipped = assert_ok!(data.zip(container));
+        assert_eq!(
+            zipped.metadata().correlation(),
+            &META_DATA.correlation().relabel()
+        );
+        assert_eq!(zipped.recv_timestamp(), later);
+        assert_eq!(
+            zipped.as_ref(),
+            &(TestData(13), TestContainer(TestData(17)))
+        );
+
+        let (data, container) = zipped.unzip();
+        assert_eq!(data.metadata().correlation(), META_DATA.correlation());
+        assert_eq!(container.metadata().correlation().label, "TestContainer");
+        assert_eq!(container.recv_timestamp(), later);
+
+        let other = Envelope::from_parts(
+            MetaData::from_parts(Id::for_labeled("other".to_string()), ts, None),
+            TestData(17),
+        );
+        let err = assert_err!(data.zip(other));
+        assert_eq!(err.left, META_DATA.correlation().id);
+        assert_eq!(err.right, "other");
+    }
+
+    #[test]
+    fn test_envelope_collect_transpose_vec() {
+        let ts = Timestamp::parse(METADATA_TS).unwrap();
+        let later = MetaData::from_parts(
+            Id::for_labeled("later".to_string()),
+            ts + std::time::Duration::from_secs(60),
+            None,
+        );
+        let envelopes = vec![
+            Envelope::from_parts(META_DATA.clone(), TestData(1)),
+            Envelope::from_parts(later.clone(), TestData(2)),
+            Envelope::from_parts(META_DATA.clone(), TestData(3)),
+        ];
+
+        let collected = assert_some!(Envelope::collect_enveloped(envelopes));
+        assert_eq!(collected.metadata(), &later.clone().relabel());
+        assert_eq!(
+            collected.as_ref(),
+            &vec![TestData(1), TestData(2), TestData(3)]
+        );
+        assert_none!(Envelope::<Vec<TestData>, String>::collect_enveloped(
+            Vec::new()
+        ));
+
+        let envelopes = collected.transpose_vec();
+        assert_eq!(envelopes.len(), 3);
+        for (envelope, i) in envelopes.iter().zip(1..) {
+            assert_eq!(envelope.metadata(), &later);
+            assert_eq!(envelope.as_ref(), &TestData(i));
+        }
+    }
+
+    #[test]
+    fn test_envelope_caused_by() {
+        let command = Envelope::from_parts(META_DATA.clone(), TestData(17));
+        assert_eq!(command.metadata().causation_id(), None);
+
+        assert_eq!(
+            command.metadata().message_id(),
+            command.metadata().correlation()
+        );
+
+        let event: Envelope<TestContainer, String> =
+            Envelope::caused_by::<TestGenerator, _>(TestContainer(TestData(19)), &command);
+        let correlation_id = event.metadata().correlation();
+        let message_id = event.metadata().message_id();
+        assert_eq!(correlation_id.id, command.metadata().correlation().id);
+        assert_eq!(correlation_id.label, "TestContainer");
+        assert_eq!(message_id.label, "TestContainer");
+        assert_ne!(message_id.id, correlation_id.id);
+        assert_eq!(
+            event.metadata().causation_id().map(|id| &id.id),
+            Some(&command.metadata().message_id().id)
+        );
+
+        let followup: Envelope<TestData, String> =
+            Envelope::caused_by::<TestGenerator, _>(TestData(23), &event);
+        assert_eq!(followup.metadata().correlation().id, correlation_id.id);
+        assert_eq!(
+            followup.metadata().causation_id().map(|id| &id.id),
+            Some(&message_id.id)
+        );
+        assert_ne!(
+            followup.metadata().causation_id().map(|id| &id.id),
+            event.metadata().causation_id().map(|id| &id.id)
+        );
+        assert_ne!(followup.metadata().message_id().id, message_id.id);
+
+        let map: HashMap<String, String> = event.metadata().clone().into();
+        assert_eq!(map.get(MESSAGE_ID_KEY), Some(&message_id.id));
+        assert_eq!(map.get(CAUSATION_ID_KEY), Some(&correlation_id.id));
+        let metadata = map.into_metadata::<TestGenerator>();
+        assert_eq!(metadata.message_id().id, message_id.id);
+        assert_eq!(
+            metadata.causation_id().map(|id| &id.id),
+            Some(&correlation_id.id)
+        );
+
+        let json = serde_json::to_string(followup.metadata()).unwrap();
+        assert!(json.contains(r#""causation_id":"#));
+        assert!(json.contains(r#""message_id":"#));
+        let actual: MetaData<TestData, String> = serde_json::from_str(&json).unwrap();
+        assert_eq!(actual.message_id(), followup.metadata().message_id());
+        assert_eq!(actual.causation_id(), followup.metadata().causation_id());
+
+        let json = serde_json::to_string(command.metadata()).unwrap();
+        assert!(!json.contains("causation_id"));
+        assert!(!json.contains("message_id"));
+        let actual: MetaData<TestData, String> = serde_json::from_str(&json).unwrap();
+        assert_eq!(actual.causation_id(), None);
+    }
+
+    #[test]
+    fn test_btree_map_into_metadata() {
+        let map: HashMap<String, String> = META_DATA.clone().with_custom("cat", "Otis").into();
+        let map: BTreeMap<String, String> = map.into_iter().collect();
+        let actual = map.clone().into_metadata_for::<TestData, TestGenerator>();
+        assert_eq!(actual, *META_DATA);
+        assert_eq!(actual.correlation().label, "TestData");
+        assert_eq!(actual.custom()["cat"], "Otis");
+
+        let actual = map.into_metadata::<TestGenerator>();
+        assert_eq!(actual.correlation().id, META_DATA.correlation().id);
+        assert_eq!(actual.correlation().label, "");
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn test_metadata_uses_installed_clock() {
+        let ts = Timestamp::parse(METADATA_TS).unwrap();
+        let (clock, _guard) = crate::testing::FixedClock::install(ts.into());
+        let metadata = MetaData::<TestData, String>::default();
+        assert_eq!(metadata.recv_timestamp(), ts);
+
+        clock.advance(std::time::Duration::from_secs(60));
+        let metadata = metadata.redelivered();
+        assert_eq!(
+            metadata.recv_timestamp(),
+            ts + std::time::Duration::from_secs(60)
+        );
+        assert_eq!(metadata.first_received_at(), ts);
+    }
+
+    #[cfg(feature = "testing")]
+    #[test]
+    fn test_metadata_age_and_processing_deadline() {
+        use std::time::Duration;
+
+        let ts = Timestamp::parse(METADATA_TS).unwrap();
+        let (clock, _guard) = crate::testing::FixedClock::install(ts.into());
+        let deadline = ts + Duration::from_secs(30);
+        let envelope = Envelope::from_parts(
+            META_DATA.clone().with_processing_deadline(deadline),
+            TestData(17),
+        );
+        assert_eq!(envelope.age(), Duration::ZERO);
+        assert!(!envelope.is_expired());
+
+        clock.advance(Duration::from_secs(30));
+        assert_eq!(envelope.age(), Duration::from_secs(30));
+        assert!(!envelope.is_expired());
+
+        clock.advance(Duration::from_millis(1));
+        assert!(envelope.is_expired());
+        assert!(!META_DATA.is_expired());
+
+        let map: HashMap<String, String> = envelope.metadata().clone().into();
+        let actual = map.into_metadata::<TestGenerator>();
+        assert_eq!(actual.processing_deadline(), Some(deadline));
+
+        let json = serde_json::to_string(envelope.metadata()).unwrap();
+        let actual: MetaData<TestData, String> = serde_json::from_str(&json).unwrap();
+        assert_eq!(actual.processing_deadline(), Some(deadline));
+        assert_eq!(actual.not_before(), None);
+
+        let received_later = META_DATA
+            .clone()
+            .redelivered_at(ts + Duration::from_secs(60));
+        assert_eq!(received_later.elapsed_since_recv(), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_metadata_actor_tenant_and_schema_version() {
+        struct User;
+        impl Label for User {
+            type Labeler = MakeLabeling<Self>;
+
+            fn labeler() -> Self::Labeler {
+                MakeLabeling::default()
+            }
+        }
+
+        let metadata = META_DATA
+            .clone()
+            .with_actor_id(Id::<User, _>::for_labeled("user-7".to_string()))
+            .with_tenant_id(Id::<(), _>::direct("Org", "acme".to_string()));
+        let actor_id = assert_some!(metadata.actor_id::<User>());
+        assert_eq!(actor_id.id, "user-7");
+        assert_eq!(actor_id.label, "User");
+        assert_eq!(
+            metadata.tenant_id::<()>().map(|id| id.id),
+            Some("acme".to_string())
+        );
+        assert_eq!(META_DATA.actor_id::<User>(), None);
+
+        let json = assert_ok!(serde_json::to_string(&metadata));
+        assert!(json.ends_with(r#""actor_id":"user-7","tenant_id":"acme"}"#));
+        let actual: MetaData<TestData, String> = assert_ok!(serde_json::from_str(&json));
+        assert_eq!(
+            assert_some!(actual.actor_id::<User>()).to_string(),
+            "User::user-7"
+        );
+        assert_eq!(
+            actual.tenant_id::<()>().map(|id| id.id),
+            Some("acme".to_string())
+        );
+
+        let metadata = metadata.with_schema_version(3);
+        let json = assert_ok!(serde_json::to_string(&metadata));
+        assert!(json.ends_with(r#""tenant_id":"acme","schema_version":3}"#));
+        let actual: MetaData<TestData, String> = assert_ok!(serde_json::from_str(&json));
+        assert_eq!(actual.schema_version(), Some(3));
+
+        let map: HashMap<String, String> = metadata.relabel::<User>().into();
+        assert_eq!(map[SCHEMA_VERSION_KEY], "3");
+        assert_eq!(map[ACTOR_ID_KEY], "user-7");
+        assert_eq!(map[TENANT_ID_KEY], "acme");
+        let actual = map.into_metadata_for::<TestData, TestGenerator>();
+        assert_eq!(
+            actual.actor_id::<User>().map(|id| id.id),
+            Some("user-7".to_string())
+        );
+        assert_eq!(
+            actual.tenant_id::<()>().map(|id| id.id),
+            Some("acme".to_string())
+        );
+        assert_eq!(actual.schema_version(), Some(3));
+    }
+
+    #[test]
+    fn test_metadata_redelivery() {
+        let metadata = META_DATA.clone();
+        assert_eq!(metadata.delivery_attempt(), 1);
+        assert!(!metadata.is_redelivery());
+        assert_eq!(metadata.first_received_at(), metadata.recv_timestamp());
+
+        let first_received_at = metadata.recv_timestamp();
+        let backoff = Timestamp::parse("2022-11-30T03:44:18.068Z").unwrap();
+        let metadata = metadata.with_not_before(backoff);
+        assert!(!metadata.is_due_at(first_received_at));
+        assert!(metadata.is_due_at(backoff));
+
+        let metadata = metadata.redelivered_at(backoff).redelivered();
+        assert_eq!(metadata.delivery_attempt(), 3);
+        assert!(metadata.is_redelivery());
+        assert_eq!(metadata.first_received_at(), first_received_at);
+        assert!(backoff < metadata.recv_timestamp());
+        assert_eq!(metadata.not_before(), None);
+
+        let metadata = metadata.with_not_before(backoff);
+        let map: HashMap<String, String> = metadata.clone().into();
+        assert_eq!(map[DELIVERY_ATTEMPT_KEY], "3");
+        let actual = map.into_metadata::<TestGenerator>();
+        assert_eq!(actual.delivery_attempt(), 3);
+        assert_eq!(actual.first_received_at(), first_received_at);
+        assert_eq!(actual.not_before(), Some(backoff));
+
+        let json = serde_json::to_string(&metadata).unwrap();
+        let actual: MetaData<TestData, String> = serde_json::from_str(&json).unwrap();
+        assert_eq!(actual.delivery_attempt(), 3);
+        assert_eq!(actual.first_received_at(), first_received_at);
+        assert_eq!(actual.not_before(), Some(backoff));
+        assert_eq!(actual.causation_id(), None);
+
+        let json = serde_json::to_string(&META_DATA.clone().with_delivery_attempt(2)).unwrap();
+        assert!(json.ends_with(r#""causation_id":null,"delivery_attempt":2}"#));
+        let actual: MetaData<TestData, String> = serde_json::from_str(&json).unwrap();
+        assert!(actual.is_redelivery());
+        assert_eq!(actual.first_received_at(), actual.recv_timestamp());
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Tenant(String);
+
+    impl MetaExtension for Tenant {
+        const KEY: &'static str = "tenant";
+
+        fn to_meta_value(&self) -> String {
+            self.0.clone()
+        }
+
+        fn from_meta_value(value: &str) -> Option<Self> {
+            Some(Self(value.to_string()))
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct RetryCount(u32);
+
+    #[test]
+    fn test_metadata_extensions() {
+        let mut metadata = META_DATA
+            .clone()
+            .with_projected_extension(Tenant("acme".to_string()))
+            .with_extension(RetryCount(1));
+        metadata.extensions_mut().get_mut::<RetryCount>().unwrap().0 += 1;
+
+        let relabeled: MetaData<TestContainer, String> = metadata.clone().relabel();
+        assert_eq!(
+            relabeled.extensions().get::<RetryCount>(),
+            Some(&RetryCount(2))
+        );
+        assert!(metadata.custom().is_empty());
+
+        let map: HashMap<String, String> = metadata.clone().into();
+        assert_eq!(map.get(Tenant::KEY).map(String::as_str), Some("acme"));
+        assert_eq!(map.len(), 3);
+
+        let json = serde_json::to_string(&metadata).unwrap();
+        assert!(json.contains(r#""custom":{"tenant":"acme"}"#));
+        let mut actual: MetaData<TestData, String> = serde_json::from_str(&json).unwrap();
+        assert!(actual.extensions().is_empty());
+        assert_eq!(
+            actual.restore_extension::<Tenant>(),
+            Some(&Tenant("acme".to_string()))
+        );
+        assert!(actual.custom().is_empty());
+        assert_eq!(
+            actual.restore_extension::<Tenant>(),
+            Some(&Tenant("acme".to_string()))
+        );
+        assert_eq!(serde_json::to_string(&actual).unwrap(), json);
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_envelope_instrument_span() {
+        let command = Envelope::from_parts(META_DATA.clone(), TestData(17));
+        let event = command.reply_with(TestData(19));
+        let log = crate::id::tracing::tests::capture_log(|| {
+            let _entered = command.instrument_span().entered();
+            let _entered = event.instrument_span().entered();
+            tracing::info!("handled");
+        });
+
+        let correlation_id = &META_DATA.correlation_id.id;
+        let command_span = format!(
+            "envelope{{correlation_id={correlation_id} label=TestData recv_timestamp={METADATA_TS}}}"
+        );
+        assert!(log.contains(&command_span), "{log}");
+        assert!(
+            log.contains(&format!("causation_id={correlation_id}}}")),
+            "{log}"
+        );
+    }
+
     #[test]
     fn test_envelope_serde_tokens() {
         let data = TestData(17);
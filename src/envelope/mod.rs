@@ -1,9 +1,34 @@
+#[cfg(feature = "amqp")]
+pub mod amqp;
+mod batch;
+mod builder;
+mod dead_letter;
 #[allow(clippy::module_inception)]
 mod envelope;
+mod extensions;
+pub mod flattened;
+#[cfg(feature = "http")]
+pub mod http;
+#[cfg(feature = "kafka")]
+pub mod kafka;
 mod metadata;
+#[cfg(feature = "sqlx")]
+pub mod outbox;
+#[cfg(feature = "futures")]
+mod stream;
 
-pub use envelope::{Envelope, IntoEnvelope};
-pub use metadata::{IntoMetaData, MetaData};
+pub use batch::EnvelopeBatch;
+pub use builder::EnvelopeBuilder;
+pub use dead_letter::DeadLetter;
+pub use envelope::{CorrelationMismatch, Envelope, IntoEnvelope};
+pub use extensions::{Extensions, MetaExtension};
+#[cfg(feature = "kafka")]
+pub use kafka::KafkaRecord;
+pub use metadata::{IntoMetaData, MetaData, MetaHeaderError};
+#[cfg(feature = "futures")]
+pub use stream::EnvelopeStreamExt;
+#[cfg(feature = "derive")]
+pub use tagid_derive::{Correlation, IntoEnvelope};
 
 use crate::Id;
 use iso8601_timestamp::Timestamp;
@@ -0,0 +1,214 @@
+//! Transactional outbox persistence of envelopes, via [`sqlx`].
+//!
+//! An [`OutboxRecord`] is the generic outbox row of an envelope: the row id, the content label
+//! and correlation id for routing and querying, the JSON serialized envelope as the payload, and
+//! when it was recorded. Services write the record in the same transaction as their state change,
+//! and a relay later hydrates and publishes it.
+//!
+//! ```rust
+//! use tagid::envelope::outbox::OutboxRecord;
+//! use tagid::envelope::{Correlation, Envelope};
+//! use tagid::{CuidGenerator, Entity, Label};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, PartialEq, Label, Serialize, Deserialize)]
+//! struct Order(u32);
+//! impl Entity for Order { type IdGen = CuidGenerator; }
+//!
+//! let envelope = Envelope::from_entity(Order(17));
+//! let record = envelope.to_outbox_record::<CuidGenerator>().unwrap();
+//! assert_eq!(record.label, "Order");
+//! assert_eq!(&record.correlation_id, &envelope.correlation().id);
+//!
+//! let actual: Envelope<Order, String> = record.into_envelope().unwrap();
+//! assert_eq!(actual, envelope);
+//! assert_eq!(actual.as_ref(), &Order(17));
+//! ```
+//!
+//! Records load via [`sqlx::FromRow`] from the `id`, `label`, `correlation_id`, `payload` and
+//! `recorded_at` columns, where `payload` and `recorded_at`, as an ISO 8601 timestamp, are text.
+
+use crate::clock;
+use crate::envelope::{Correlation, Envelope};
+use crate::id::IdGenerator;
+use crate::{Label, Labeling};
+use iso8601_timestamp::Timestamp;
+use serde::{de, Serialize};
+use sqlx::{ColumnIndex, Decode, Row, Type};
+use thiserror::Error;
+
+/// Error converting between envelopes and [`OutboxRecord`]s.
+#[derive(Debug, Error)]
+pub enum OutboxError {
+    #[error("failed to serialize envelope payload: {0}")]
+    Serialize(#[source] serde_json::Error),
+
+    #[error("failed to deserialize envelope payload: {0}")]
+    Deserialize(#[source] serde_json::Error),
+
+    #[error("outbox record label mismatch: expected `{expected}` but was `{actual}`")]
+    LabelMismatch { expected: String, actual: String },
+}
+
+/// The outbox row of an envelope.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutboxRecord<ID> {
+    pub id: ID,
+    pub label: String,
+    pub correlation_id: ID,
+    pub payload: String,
+    pub recorded_at: Timestamp,
+}
+
+impl<ID> OutboxRecord<ID> {
+    /// Records the envelope now, under a row id generated via `G`.
+    pub fn from_envelope<T, G>(envelope: &Envelope<T, ID>) -> Result<Self, OutboxError>
+    where
+        T: Serialize,
+        ID: Serialize + Clone,
+        G: IdGenerator<IdType = ID>,
+    {
+        let payload = serde_json::to_string(envelope).map_err(OutboxError::Serialize)?;
+        let correlation_id = envelope.metadata().correlation();
+        Ok(Self {
+            id: G::next_id_rep(),
+            label: correlation_id.label.to_string(),
+            correlation_id: correlation_id.id.clone(),
+            payload,
+            recorded_at: clock::now_timestamp(),
+        })
+    }
+
+    /// Hydrates the recorded envelope, which must be labeled for `T`.
+    pub fn into_envelope<T>(self) -> Result<Envelope<T, ID>, OutboxError>
+    where
+        T: Label + de::DeserializeOwned,
+        ID: de::DeserializeOwned,
+    {
+        let labeler = T::labeler();
+        if self.label != labeler.label() {
+            return Err(OutboxError::LabelMismatch {
+                expected: labeler.label().to_string(),
+                actual: self.label,
+            });
+        }
+
+        serde_json::from_str(&self.payload).map_err(OutboxError::Deserialize)
+    }
+}
+
+impl<T, ID> Envelope<T, ID>
+where
+    T: Serialize,
+    ID: Serialize + Clone,
+{
+    /// Records the envelope as an [`OutboxRecord`] with a row id generated via `G`.
+    pub fn to_outbox_record<G>(&self) -> Result<OutboxRecord<ID>, OutboxError>
+    where
+        G: IdGenerator<IdType = ID>,
+    {
+        OutboxRecord::from_envelope::<T, G>(self)
+    }
+}
+
+impl<'r, R, ID> sqlx::FromRow<'r, R> for OutboxRecord<ID>
+where
+    R: Row,
+    &'r str: ColumnIndex<R>,
+    ID: Decode<'r, R::Database> + Type<R::Database>,
+    String: Decode<'r, R::Database> + Type<R::Database>,
+{
+    fn from_row(row: &'r R) -> Result<Self, sqlx::Error> {
+        let recorded_at: String = row.try_get("recorded_at")?;
+        let recorded_at =
+            Timestamp::parse(&recorded_at).ok_or_else(|| sqlx::Error::ColumnDecode {
+                index: "recorded_at".to_string(),
+                source: format!("not an ISO 8601 timestamp: {recorded_at}").into(),
+            })?;
+
+        Ok(Self {
+            id: row.try_get("id")?,
+            label: row.try_get("label")?,
+            correlation_id: row.try_get("correlation_id")?,
+            payload: row.try_get("payload")?,
+            recorded_at,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::envelope::{MetaData, ReceivedAt};
+    use crate::{CustomLabeling, Id};
+    use claim::*;
+    use pretty_assertions::assert_eq;
+    use serde::Deserialize;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Order(u32);
+
+    impl Label for Order {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("Order")
+        }
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Invoice(u32);
+
+    impl Label for Invoice {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("Invoice")
+        }
+    }
+
+    struct RowIdGenerator;
+
+    impl IdGenerator for RowIdGenerator {
+        type IdType = u64;
+
+        fn next_id_rep() -> Self::IdType {
+            7
+        }
+    }
+
+    #[test]
+    fn test_outbox_record() {
+        let ts = Timestamp::parse("2022-11-30T03:43:18.068Z").unwrap();
+        let metadata = MetaData::from_parts(Id::for_labeled(17_u64), ts, None)
+            .with_causation_id(Id::for_labeled(3));
+        let envelope = Envelope::from_parts(metadata, Order(42));
+
+        let record = assert_ok!(envelope.to_outbox_record::<RowIdGenerator>());
+        assert_eq!(record.id, 7);
+        assert_eq!(record.label, "Order");
+        assert_eq!(record.correlation_id, 17);
+        assert!(ts < record.recorded_at);
+
+        let actual: Envelope<Order, u64> = assert_ok!(record.clone().into_envelope());
+        assert_eq!(actual.as_ref(), &Order(42));
+        assert_eq!(actual.metadata().correlation().id, 17);
+        assert_eq!(actual.metadata().causation_id().map(|id| id.id), Some(3));
+        assert_eq!(actual.recv_timestamp(), ts);
+
+        let error = assert_err!(record.clone().into_envelope::<Invoice>());
+        assert_eq!(
+            error.to_string(),
+            "outbox record label mismatch: expected `Invoice` but was `Order`"
+        );
+
+        let corrupt = OutboxRecord {
+            payload: "{".to_string(),
+            ..record
+        };
+        assert!(matches!(
+            corrupt.into_envelope::<Order>(),
+            Err(OutboxError::Deserialize(_))
+        ));
+    }
+}
@@ -0,0 +1,168 @@
+use crate::envelope::{Correlation, Envelope, MetaData};
+use crate::{Id, Label};
+use futures::{Stream, StreamExt};
+
+/// Combinators for streams of envelopes that transform the content while carrying the metadata
+/// along.
+///
+/// ```rust
+/// use futures::{executor, stream, StreamExt};
+/// use tagid::envelope::{Envelope, EnvelopeStreamExt};
+/// use tagid::{CuidGenerator, Entity, Label};
+///
+/// #[derive(Label)]
+/// struct Order(u32);
+/// impl Entity for Order { type IdGen = CuidGenerator; }
+///
+/// #[derive(Debug, PartialEq, Label)]
+/// struct Invoice(u32);
+///
+/// let orders = vec![Envelope::from_entity(Order(1)), Envelope::from_entity(Order(2))];
+/// let invoices: Vec<_> = executor::block_on(
+///     stream::iter(orders)
+///         .map_enveloped(|order| Invoice(order.0 * 10))
+///         .map(Envelope::into_inner)
+///         .collect(),
+/// );
+/// assert_eq!(invoices, vec![Invoice(10), Invoice(20)]);
+/// ```
+pub trait EnvelopeStreamExt<T, ID>: Stream<Item = Envelope<T, ID>> + Sized {
+    /// Maps the content of each envelope, keeping its metadata.
+    fn map_enveloped<U, F>(self, mut f: F) -> impl Stream<Item = Envelope<U, ID>>
+    where
+        U: Label,
+        ID: Clone,
+        F: FnMut(T) -> U,
+    {
+        self.map(move |envelope| {
+            let (metadata, content) = envelope.into_parts();
+            Envelope::from_parts(metadata.relabel(), f(content))
+        })
+    }
+
+    /// Maps the content of each envelope with a fallible function, keeping the metadata of the
+    /// envelopes mapped successfully.
+    fn try_map_enveloped<U, E, F>(self, mut f: F) -> impl Stream<Item = Result<Envelope<U, ID>, E>>
+    where
+        U: Label,
+        ID: Clone,
+        F: FnMut(T) -> Result<U, E>,
+    {
+        self.map(move |envelope| {
+            let (metadata, content) = envelope.into_parts();
+            f(content).map(|content| Envelope::from_parts(metadata.relabel(), content))
+        })
+    }
+
+    /// Correlates each envelope with the `cause` message, carrying forward its correlation id
+    /// and recording its message id as the causation id, as [`Envelope::caused_by()`] does. Each
+    /// envelope keeps its own message id.
+    fn correlate_with<C>(self, cause: &MetaData<C, ID>) -> impl Stream<Item = Envelope<T, ID>>
+    where
+        T: Label,
+        ID: Clone,
+    {
+        let correlation_id: Id<T, ID> = cause.correlation().relabel();
+        let causation_id: Id<T, ID> = cause.message_id().relabel();
+        self.map(move |envelope| {
+            let (metadata, content) = envelope.into_parts();
+            let message_id = metadata.message_id().clone();
+            let metadata = metadata
+                .with_message_id(message_id)
+                .with_correlation_id(correlation_id.clone())
+                .with_causation_id(causation_id.clone());
+            Envelope::from_parts(metadata, content)
+        })
+    }
+}
+
+impl<S, T, ID> EnvelopeStreamExt<T, ID> for S where S: Stream<Item = Envelope<T, ID>> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::envelope::ReceivedAt;
+    use crate::CustomLabeling;
+    use futures::{executor, stream};
+    use iso8601_timestamp::Timestamp;
+    use pretty_assertions::assert_eq;
+
+    #[derive(Debug, PartialEq)]
+    struct Order(u32);
+
+    impl Label for Order {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("Order")
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Invoice(u32);
+
+    impl Label for Invoice {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("Invoice")
+        }
+    }
+
+    fn orders() -> impl Stream<Item = Envelope<Order, u64>> {
+        let ts = Timestamp::parse("2022-11-30T03:43:18.068Z").unwrap();
+        stream::iter((1..=3).map(move |id| {
+            Envelope::from_parts(
+                MetaData::from_parts(Id::for_labeled(id), ts, None),
+                Order(id as u32),
+            )
+        }))
+    }
+
+    #[test]
+    fn test_envelope_stream_map() {
+        let invoices: Vec<_> =
+            executor::block_on(orders().map_enveloped(|o| Invoice(o.0 * 10)).collect());
+        assert_eq!(invoices.len(), 3);
+        for (invoice, id) in invoices.iter().zip(1..) {
+            assert_eq!(invoice.as_ref(), &Invoice(id as u32 * 10));
+            assert_eq!(invoice.metadata().correlation().id, id);
+            assert_eq!(invoice.metadata().correlation().label, "Invoice");
+            assert_eq!(
+                invoice.recv_timestamp(),
+                Timestamp::parse("2022-11-30T03:43:18.068Z").unwrap()
+            );
+        }
+
+        let results: Vec<_> = executor::block_on(
+            orders()
+                .try_map_enveloped(|o| {
+                    if o.0 == 2 {
+                        Err("rejected")
+                    } else {
+                        Ok(Invoice(o.0))
+                    }
+                })
+                .collect(),
+        );
+        assert_eq!(results[0].as_ref().unwrap().metadata().correlation().id, 1);
+        assert_eq!(results[1].as_ref().unwrap_err(), &"rejected");
+        assert_eq!(results[2].as_ref().unwrap().as_ref(), &Invoice(3));
+    }
+
+    #[test]
+    fn test_envelope_stream_correlate_with() {
+        let ts = Timestamp::parse("2022-11-30T03:40:00Z").unwrap();
+        let cause = MetaData::<Invoice, u64>::from_parts(Id::for_labeled(42), ts, None);
+
+        let orders: Vec<_> = executor::block_on(orders().correlate_with(&cause).collect());
+        for (order, id) in orders.iter().zip(1..) {
+            assert_eq!(order.as_ref(), &Order(id));
+            assert_eq!(order.metadata().correlation().id, 42);
+            assert_eq!(order.metadata().correlation().label, "Order");
+            assert_eq!(order.metadata().message_id().id, u64::from(id));
+            assert_eq!(order.metadata().causation_id().map(|id| id.id), Some(42));
+            assert_ne!(order.recv_timestamp(), ts);
+        }
+    }
+}
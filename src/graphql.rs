@@ -0,0 +1,229 @@
+//! Relay [Global Object Identification](https://relay.dev/graphql/objectidentification.htm) for
+//! typed ids.
+//!
+//! A [`GlobalId`] packs an id as base64-encoded `Label:id`, so a single `node(id: ID!)` resolver
+//! can dispatch on the label, and decoding verifies the label matches the expected entity:
+//!
+//! ```rust
+//! use tagid::graphql::GlobalId;
+//! use tagid::{Id, Label};
+//!
+//! #[derive(Label)]
+//! struct Order;
+//!
+//! let id: Id<Order, u64> = Id::for_labeled(42);
+//! let global_id = GlobalId::encode(&id);
+//! assert_eq!(global_id.as_str(), "T3JkZXI6NDI=");
+//! assert_eq!(global_id.label().unwrap(), "Order");
+//! assert_eq!(global_id.decode::<Order, u64>().unwrap(), id);
+//! ```
+
+use crate::{accepts_label, Id, Label, Labeling};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Separates the label from the id in the decoded global id, per the Relay convention.
+pub const GLOBAL_ID_DELIMITER: char = ':';
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum GlobalIdError {
+    #[error("global id is not valid base64: {0}")]
+    InvalidEncoding(String),
+
+    #[error("global id is missing its `Label{GLOBAL_ID_DELIMITER}` prefix")]
+    MissingLabel,
+
+    #[error("global id label `{actual}` does not match expected label `{expected}`")]
+    LabelMismatch { expected: String, actual: String },
+
+    #[error("failed to parse global id: {0}")]
+    InvalidId(String),
+}
+
+/// An opaque, base64-encoded `Label:id` node id.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct GlobalId(String);
+
+impl GlobalId {
+    pub fn encode<T: ?Sized, ID: fmt::Display>(id: &Id<T, ID>) -> Self {
+        Self(STANDARD.encode(format!("{}{GLOBAL_ID_DELIMITER}{}", id.label, id.id)))
+    }
+
+    /// Decodes the id, verifying its label matches `T` or one of its aliases.
+    pub fn decode<T, ID>(&self) -> Result<Id<T, ID>, GlobalIdError>
+    where
+        T: ?Sized + Label,
+        ID: FromStr,
+        <ID as FromStr>::Err: fmt::Display,
+    {
+        let (label, id) = self.parts()?;
+        let labeler = <T as Label>::labeler();
+        if !accepts_label::<T>(&label) {
+            return Err(GlobalIdError::LabelMismatch {
+                expected: labeler.label().to_string(),
+                actual: label,
+            });
+        }
+
+        let id = ID::from_str(&id).map_err(|err| GlobalIdError::InvalidId(err.to_string()))?;
+        Ok(Id::direct_labeled(labeler.label(), id))
+    }
+
+    /// The label of the encoded id, for dispatching a `node` query to the entity's resolver.
+    pub fn label(&self) -> Result<String, GlobalIdError> {
+        self.parts().map(|(label, _)| label)
+    }
+
+    /// The decoded label and id.
+    pub fn parts(&self) -> Result<(String, String), GlobalIdError> {
+        let bytes = STANDARD
+            .decode(&self.0)
+            .map_err(|err| GlobalIdError::InvalidEncoding(err.to_string()))?;
+        let rep = String::from_utf8(bytes)
+            .map_err(|err| GlobalIdError::InvalidEncoding(err.to_string()))?;
+        let (label, id) = rep
+            .split_once(GLOBAL_ID_DELIMITER)
+            .ok_or(GlobalIdError::MissingLabel)?;
+        Ok((label.to_string(), id.to_string()))
+    }
+
+    pub const fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl fmt::Display for GlobalId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl AsRef<str> for GlobalId {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+/// Wraps the opaque representation without validating it; validation happens on decode.
+impl From<String> for GlobalId {
+    fn from(rep: String) -> Self {
+        Self(rep)
+    }
+}
+
+impl From<&str> for GlobalId {
+    fn from(rep: &str) -> Self {
+        Self(rep.to_string())
+    }
+}
+
+impl<T: ?Sized, ID: fmt::Display> From<&Id<T, ID>> for GlobalId {
+    fn from(id: &Id<T, ID>) -> Self {
+        Self::encode(id)
+    }
+}
+
+impl<T: ?Sized, ID: fmt::Display> Id<T, ID> {
+    /// Encodes the id as a Relay global id.
+    pub fn to_global_id(&self) -> GlobalId {
+        GlobalId::encode(self)
+    }
+}
+
+impl<T, ID> TryFrom<&GlobalId> for Id<T, ID>
+where
+    T: ?Sized + Label,
+    ID: FromStr,
+    <ID as FromStr>::Err: fmt::Display,
+{
+    type Error = GlobalIdError;
+
+    fn try_from(global_id: &GlobalId) -> Result<Self, Self::Error> {
+        global_id.decode()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CustomLabeling;
+    use claim::*;
+    use pretty_assertions::assert_eq;
+
+    struct Order;
+
+    impl Label for Order {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("Order")
+        }
+
+        fn aliases() -> &'static [&'static str] {
+            &["PurchaseOrder"]
+        }
+    }
+
+    struct User;
+
+    impl Label for User {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("User")
+        }
+    }
+
+    #[test]
+    fn test_global_id_roundtrip() {
+        let id: Id<Order, String> = Id::for_labeled("a:b".to_string());
+        let global_id = id.to_global_id();
+        assert_eq!(global_id.to_string(), "T3JkZXI6YTpi");
+        assert_eq!(
+            assert_ok!(global_id.parts()),
+            ("Order".to_string(), "a:b".to_string())
+        );
+        assert_eq!(assert_ok!(Id::<Order, String>::try_from(&global_id)), id);
+
+        let json = assert_ok!(serde_json::to_string(&global_id));
+        assert_eq!(json, r#""T3JkZXI6YTpi""#);
+
+        let alias = GlobalId::from(STANDARD.encode("PurchaseOrder:7"));
+        let id: Id<Order, u64> = assert_ok!(alias.decode());
+        assert_eq!(id.label, "Order");
+    }
+
+    #[test]
+    fn test_global_id_errors() {
+        let global_id = GlobalId::encode(&Id::<User, u64>::for_labeled(42));
+        assert_eq!(
+            assert_err!(global_id.decode::<Order, u64>()),
+            GlobalIdError::LabelMismatch {
+                expected: "Order".to_string(),
+                actual: "User".to_string(),
+            }
+        );
+
+        assert_matches!(
+            GlobalId::from("not base64!").decode::<Order, u64>(),
+            Err(GlobalIdError::InvalidEncoding(_))
+        );
+        assert_eq!(
+            assert_err!(GlobalId::from(STANDARD.encode("42")).label()),
+            GlobalIdError::MissingLabel
+        );
+        assert_matches!(
+            GlobalId::from(STANDARD.encode("Order:x")).decode::<Order, u64>(),
+            Err(GlobalIdError::InvalidId(_))
+        );
+    }
+}
@@ -0,0 +1,131 @@
+//! Extraction of ids from actix-web requests.
+//!
+//! `Id<T, ID>` is itself an extractor for a route's single path parameter, accepting either the
+//! bare id or its labeled `Label::id` form:
+//!
+//! ```rust
+//! use actix_web::{web, App};
+//! use tagid::{Id, Label};
+//!
+//! #[derive(Label)]
+//! struct Order;
+//!
+//! async fn get_order(id: Id<Order, u64>) -> String {
+//!     format!("order {}", id.id)
+//! }
+//!
+//! let app = App::new().route("/orders/{id}", web::get().to(get_order));
+//! ```
+//!
+//! `web::Path<Id<T, ID>>` also works via `Id`'s serde representation, but accepts only the bare
+//! id. Ids in query strings or multi-parameter paths can deserialize leniently via
+//! `#[serde(deserialize_with = "tagid::labeled::deserialize_lenient")]`.
+
+use crate::{Id, IdParseError, Label, Labeling};
+use actix_web::dev::Payload;
+use actix_web::http::StatusCode;
+use actix_web::{web, FromRequest, HttpRequest, ResponseError};
+use std::fmt;
+use std::future::{ready, Ready};
+use std::str::FromStr;
+use thiserror::Error;
+
+/// The id did not parse, or was labeled for a different entity; responds with
+/// `400 Bad Request`.
+#[derive(Debug, Error)]
+#[error("invalid {label} id `{rep}`: {source}")]
+pub struct TypedIdError {
+    pub label: String,
+    pub rep: String,
+    #[source]
+    pub source: IdParseError,
+}
+
+impl ResponseError for TypedIdError {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::BAD_REQUEST
+    }
+}
+
+impl<T, ID> FromRequest for Id<T, ID>
+where
+    T: ?Sized + Label,
+    ID: FromStr,
+    <ID as FromStr>::Err: fmt::Display,
+{
+    type Error = actix_web::Error;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let result = web::Path::<String>::from_request(req, payload)
+            .into_inner()
+            .and_then(|rep| {
+                let rep = rep.into_inner();
+                Self::parse_lenient(&rep).map_err(|source| {
+                    TypedIdError {
+                        label: <T as Label>::labeler().label().to_string(),
+                        rep,
+                        source,
+                    }
+                    .into()
+                })
+            });
+        ready(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CustomLabeling, Id, Label};
+    use actix_web::http::StatusCode;
+    use actix_web::test::{call_service, init_service, read_body, TestRequest};
+    use actix_web::{rt, web, App};
+    use pretty_assertions::assert_eq;
+
+    struct Order;
+
+    impl Label for Order {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("Order")
+        }
+    }
+
+    async fn get_order(id: Id<Order, u64>) -> String {
+        id.to_string()
+    }
+
+    fn request(uri: &str) -> (StatusCode, String) {
+        rt::System::new().block_on(async {
+            let app =
+                init_service(App::new().route("/orders/{id}", web::get().to(get_order))).await;
+            let response = call_service(&app, TestRequest::get().uri(uri).to_request()).await;
+            let status = response.status();
+            let body = read_body(response).await;
+            (status, String::from_utf8(body.to_vec()).unwrap())
+        })
+    }
+
+    #[test]
+    fn test_extract_id_from_path() {
+        assert_eq!(
+            request("/orders/42"),
+            (StatusCode::OK, "Order::42".to_string())
+        );
+        assert_eq!(
+            request("/orders/Order::42"),
+            (StatusCode::OK, "Order::42".to_string())
+        );
+    }
+
+    #[test]
+    fn test_reject_invalid_id() {
+        let (status, body) = request("/orders/User::42");
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body,
+            "invalid Order id `User::42`: id label `User` does not match expected label `Order`"
+        );
+    }
+}
@@ -0,0 +1,127 @@
+//! [`proptest`] strategies for the crate's id types, generating only representations their
+//! generators could produce.
+
+use crate::{Id, Label};
+use proptest::arbitrary::{any_with, Arbitrary};
+use proptest::strategy::{BoxedStrategy, Strategy};
+
+/// Labels generated ids for `T`, with the inner id drawn from `ID`'s strategy.
+impl<T, ID> Arbitrary for Id<T, ID>
+where
+    T: ?Sized + Label + 'static,
+    ID: Arbitrary + 'static,
+{
+    type Parameters = ID::Parameters;
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(args: Self::Parameters) -> Self::Strategy {
+        any_with::<ID>(args).prop_map(Self::for_labeled).boxed()
+    }
+}
+
+#[cfg(feature = "snowflake")]
+mod snowflake {
+    use super::*;
+    use crate::snowflake::pretty::{AlphabetCodec, IdPrettifier, PrettySnowflakeId, BASE_23};
+
+    /// Prettifies non-negative snowflakes with the global prettifier, initializing it with the
+    /// [`BASE_23`] alphabet if needed.
+    impl Arbitrary for PrettySnowflakeId {
+        type Parameters = ();
+        type Strategy = BoxedStrategy<Self>;
+
+        fn arbitrary_with((): Self::Parameters) -> Self::Strategy {
+            IdPrettifier::<AlphabetCodec>::global_initialize(BASE_23.clone());
+            (0..=i64::MAX).prop_map(Self::from_snowflake).boxed()
+        }
+    }
+}
+
+#[cfg(feature = "ksuid")]
+mod ksuid {
+    use super::*;
+    use crate::Ksuid;
+
+    /// Any 20 bytes form a valid KSUID, so its full space is drawn.
+    impl Arbitrary for Ksuid {
+        type Parameters = ();
+        type Strategy = BoxedStrategy<Self>;
+
+        fn arbitrary_with((): Self::Parameters) -> Self::Strategy {
+            proptest::arbitrary::any::<[u8; 20]>()
+                .prop_map(Self::from_bytes)
+                .boxed()
+        }
+    }
+}
+
+#[cfg(feature = "ulid")]
+mod ulid {
+    use super::*;
+    use crate::Ulid;
+
+    /// Any 128 bits form a valid ULID, so its full space is drawn.
+    impl Arbitrary for Ulid {
+        type Parameters = ();
+        type Strategy = BoxedStrategy<Self>;
+
+        fn arbitrary_with((): Self::Parameters) -> Self::Strategy {
+            proptest::arbitrary::any::<u128>()
+                .prop_map(Self::from_u128)
+                .boxed()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CustomLabeling, Id, Label};
+    use pretty_assertions::assert_eq;
+    use proptest::prelude::*;
+
+    struct Order;
+
+    impl Label for Order {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("Order")
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_arbitrary_id_is_labeled(id in any::<Id<Order, u64>>()) {
+            assert_eq!(id.label, "Order");
+            let parsed: Id<Order, u64> = id.to_string().parse().unwrap();
+            assert_eq!(parsed, id);
+        }
+
+        #[cfg(feature = "snowflake")]
+        #[test]
+        fn test_arbitrary_pretty_snowflake_uses_alphabet(
+            id in any::<crate::snowflake::pretty::PrettySnowflakeId>()
+        ) {
+            use crate::snowflake::pretty::{AlphabetCodec, IdPrettifier};
+            let prettifier = IdPrettifier::<AlphabetCodec>::summon();
+            let alphabet = &prettifier.encoder.alphabet().elements;
+            let is_pretty = id.split(prettifier.delimiter.as_str()).all(|part| {
+                !part.is_empty()
+                    && part.chars().all(|c| c.is_ascii_digit() || alphabet.contains(c))
+            });
+            prop_assert!(is_pretty, "unexpected pretty id: {id}");
+        }
+
+        #[cfg(feature = "ksuid")]
+        #[test]
+        fn test_arbitrary_ksuid_roundtrips(ksuid in any::<crate::Ksuid>()) {
+            assert_eq!(ksuid.to_string().parse::<crate::Ksuid>().unwrap(), ksuid);
+        }
+
+        #[cfg(feature = "ulid")]
+        #[test]
+        fn test_arbitrary_ulid_roundtrips(ulid in any::<crate::Ulid>()) {
+            assert_eq!(ulid.to_string().parse::<crate::Ulid>().unwrap(), ulid);
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use crate::id::{IdGenError, TryIdGenerator};
+use crate::{Id, Label};
+use std::future::Future;
+use std::marker::PhantomData;
+
+/// Generates ids from sources that require I/O, such as database sequences or external ticket
+/// servers. Implementations may use `async fn`:
+///
+#[cfg_attr(feature = "derive", doc = "```rust")]
+#[cfg_attr(not(feature = "derive"), doc = "```ignore")]
+/// use tagid::{AsyncEntity, AsyncIdGenerator, IdGenError, Label};
+///
+/// struct TicketServer;
+///
+/// impl AsyncIdGenerator for TicketServer {
+///     type IdType = u64;
+///
+///     async fn next_id_rep() -> Result<Self::IdType, IdGenError> {
+///         // e.g., `SELECT nextval('order_seq')`
+///         Ok(17)
+///     }
+/// }
+///
+/// #[derive(Label)]
+/// struct Order;
+///
+/// impl AsyncEntity for Order {
+///     type IdGen = TicketServer;
+/// }
+///
+/// let id = futures::executor::block_on(Order::next_id()).unwrap();
+/// assert_eq!(id.to_string(), "Order::17");
+/// ```
+pub trait AsyncIdGenerator {
+    type IdType: Send;
+    fn next_id_rep() -> impl Future<Output = Result<Self::IdType, IdGenError>> + Send;
+}
+
+/// The async counterpart of [`Entity`](crate::Entity), for entities whose ids come from an
+/// [`AsyncIdGenerator`].
+pub trait AsyncEntity: Label {
+    type IdGen: AsyncIdGenerator;
+
+    fn next_id(
+    ) -> impl Future<Output = Result<Id<Self, <Self::IdGen as AsyncIdGenerator>::IdType>, IdGenError>>
+           + Send {
+        async {
+            let id = Self::IdGen::next_id_rep().await?;
+            Ok(Id::for_labeled(id))
+        }
+    }
+}
+
+/// Adapts a synchronous [`TryIdGenerator`] for use as an [`AsyncIdGenerator`], so async entities
+/// can use the built-in generators.
+pub struct Immediate<G> {
+    marker: PhantomData<fn() -> G>,
+}
+
+impl<G: TryIdGenerator> AsyncIdGenerator for Immediate<G> {
+    type IdType = G::IdType;
+
+    fn next_id_rep() -> impl Future<Output = Result<Self::IdType, IdGenError>> + Send {
+        std::future::ready(G::try_next_id_rep())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CustomLabeling, IdGenerator};
+    use claim::*;
+    use futures::executor::block_on;
+    use pretty_assertions::assert_eq;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    static SEQUENCE: AtomicU64 = AtomicU64::new(100);
+
+    struct DbSequence;
+
+    impl AsyncIdGenerator for DbSequence {
+        type IdType = u64;
+
+        async fn next_id_rep() -> Result<Self::IdType, IdGenError> {
+            futures::future::ready(()).await;
+            Ok(SEQUENCE.fetch_add(1, Ordering::SeqCst))
+        }
+    }
+
+    struct Invoice;
+
+    impl Label for Invoice {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("Invoice")
+        }
+    }
+
+    impl AsyncEntity for Invoice {
+        type IdGen = DbSequence;
+    }
+
+    struct Counter;
+
+    impl IdGenerator for Counter {
+        type IdType = u64;
+
+        fn next_id_rep() -> Self::IdType {
+            SEQUENCE.fetch_add(1, Ordering::SeqCst)
+        }
+    }
+
+    impl TryIdGenerator for Counter {}
+
+    fn assert_send<F: Future + Send>(future: F) -> F {
+        future
+    }
+
+    #[test]
+    fn test_async_entity_next_id() {
+        let a = assert_ok!(block_on(assert_send(Invoice::next_id())));
+        let b = assert_ok!(block_on(Invoice::next_id()));
+        assert_eq!(a.label, "Invoice");
+        assert!(a.id < b.id);
+
+        let c = assert_ok!(block_on(Immediate::<Counter>::next_id_rep()));
+        assert!(b.id < c);
+    }
+}
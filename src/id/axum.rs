@@ -0,0 +1,144 @@
+//! Extraction of ids from axum requests.
+//!
+//! `Id<T, ID>` is itself an extractor for a route's single path parameter, accepting either the
+//! bare id or its labeled `Label::id` form:
+//!
+//! ```rust
+//! use axum::{routing::get, Router};
+//! use tagid::{Id, Label};
+//!
+//! #[derive(Label)]
+//! struct Order;
+//!
+//! async fn get_order(id: Id<Order, u64>) -> String {
+//!     format!("order {}", id.id)
+//! }
+//!
+//! let app: Router = Router::new().route("/orders/{id}", get(get_order));
+//! ```
+//!
+//! `Path<Id<T, ID>>` also works via `Id`'s serde representation, but accepts only the bare id.
+//! Ids in query strings or multi-parameter paths can deserialize leniently via
+//! `#[serde(deserialize_with = "tagid::labeled::deserialize_lenient")]`.
+
+use crate::{Id, IdParseError, Label, Labeling};
+use ::axum::extract::rejection::PathRejection;
+use ::axum::extract::{FromRequestParts, Path};
+use ::axum::http::request::Parts;
+use ::axum::http::StatusCode;
+use ::axum::response::{IntoResponse, Response};
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Rejection for an id that could not be extracted from the request.
+#[derive(Debug, Error)]
+pub enum TypedIdRejection {
+    #[error(transparent)]
+    Path(#[from] PathRejection),
+
+    /// The id did not parse, or was labeled for a different entity; rejected with
+    /// `400 Bad Request`.
+    #[error("invalid {label} id `{rep}`: {source}")]
+    Invalid {
+        label: String,
+        rep: String,
+        #[source]
+        source: IdParseError,
+    },
+}
+
+impl IntoResponse for TypedIdRejection {
+    fn into_response(self) -> Response {
+        match self {
+            Self::Path(rejection) => rejection.into_response(),
+            invalid @ Self::Invalid { .. } => {
+                (StatusCode::BAD_REQUEST, invalid.to_string()).into_response()
+            }
+        }
+    }
+}
+
+impl<S, T, ID> FromRequestParts<S> for Id<T, ID>
+where
+    S: Send + Sync,
+    T: ?Sized + Label,
+    ID: FromStr + Send,
+    <ID as FromStr>::Err: fmt::Display,
+{
+    type Rejection = TypedIdRejection;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Path(rep) = Path::<String>::from_request_parts(parts, state).await?;
+        Self::parse_lenient(&rep).map_err(|source| TypedIdRejection::Invalid {
+            label: <T as Label>::labeler().label().to_string(),
+            rep,
+            source,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CustomLabeling, Id, Label};
+    use ::axum::body::{to_bytes, Body};
+    use ::axum::http::{Request, StatusCode};
+    use ::axum::routing::get;
+    use ::axum::Router;
+    use futures::executor::block_on;
+    use pretty_assertions::assert_eq;
+    use tower::ServiceExt;
+
+    struct Order;
+
+    impl Label for Order {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("Order")
+        }
+    }
+
+    async fn get_order(id: Id<Order, u64>) -> String {
+        id.to_string()
+    }
+
+    fn request(uri: &str) -> (StatusCode, String) {
+        let app = Router::new().route("/orders/{id}", get(get_order));
+        block_on(async {
+            let response = app
+                .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            let status = response.status();
+            let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            (status, String::from_utf8(body.to_vec()).unwrap())
+        })
+    }
+
+    #[test]
+    fn test_extract_id_from_path() {
+        assert_eq!(
+            request("/orders/42"),
+            (StatusCode::OK, "Order::42".to_string())
+        );
+        assert_eq!(
+            request("/orders/Order::42"),
+            (StatusCode::OK, "Order::42".to_string())
+        );
+    }
+
+    #[test]
+    fn test_reject_invalid_id() {
+        let (status, body) = request("/orders/User::42");
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body,
+            "invalid Order id `User::42`: id label `User` does not match expected label `Order`"
+        );
+
+        let (status, body) = request("/orders/forty-two");
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body.starts_with("invalid Order id `forty-two`: failed to parse id"));
+    }
+}
@@ -0,0 +1,149 @@
+//! [`borsh`] support, encoding typed ids as their bare `ID` as serde does, so they cross borsh
+//! boundaries without first being copied into plain strings.
+
+use crate::{Id, Label};
+use ::borsh::{BorshDeserialize, BorshSerialize};
+use std::io;
+
+impl<T: ?Sized, ID: BorshSerialize> BorshSerialize for Id<T, ID> {
+    fn serialize<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.id.serialize(writer)
+    }
+}
+
+/// Labels the decoded id for `T`, as deserialization does.
+impl<T: ?Sized + Label, ID: BorshDeserialize> BorshDeserialize for Id<T, ID> {
+    fn deserialize_reader<R: io::Read>(reader: &mut R) -> io::Result<Self> {
+        ID::deserialize_reader(reader).map(Self::for_labeled)
+    }
+}
+
+#[cfg(feature = "snowflake")]
+mod pretty {
+    use crate::snowflake::pretty::PrettySnowflakeId;
+    use ::borsh::{BorshDeserialize, BorshSerialize};
+    use smol_str::SmolStr;
+    use std::io;
+
+    impl BorshSerialize for PrettySnowflakeId {
+        fn serialize<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+            AsRef::<str>::as_ref(self).serialize(writer)
+        }
+    }
+
+    impl BorshDeserialize for PrettySnowflakeId {
+        fn deserialize_reader<R: io::Read>(reader: &mut R) -> io::Result<Self> {
+            String::deserialize_reader(reader).map(|rep| Self::from_rep(SmolStr::from(rep)))
+        }
+    }
+}
+
+#[cfg(feature = "ksuid")]
+mod ksuid {
+    use crate::id::ksuid::BYTES_LEN;
+    use crate::id::Ksuid;
+    use ::borsh::{BorshDeserialize, BorshSerialize};
+    use std::io;
+
+    impl BorshSerialize for Ksuid {
+        fn serialize<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+            writer.write_all(self.as_bytes())
+        }
+    }
+
+    impl BorshDeserialize for Ksuid {
+        fn deserialize_reader<R: io::Read>(reader: &mut R) -> io::Result<Self> {
+            <[u8; BYTES_LEN]>::deserialize_reader(reader).map(Self::from_bytes)
+        }
+    }
+}
+
+#[cfg(feature = "ulid")]
+mod ulid {
+    use crate::id::ulid::BYTES_LEN;
+    use crate::Ulid;
+    use ::borsh::{BorshDeserialize, BorshSerialize};
+    use std::io;
+
+    impl BorshSerialize for Ulid {
+        fn serialize<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+            writer.write_all(self.as_bytes())
+        }
+    }
+
+    impl BorshDeserialize for Ulid {
+        fn deserialize_reader<R: io::Read>(reader: &mut R) -> io::Result<Self> {
+            <[u8; BYTES_LEN]>::deserialize_reader(reader).map(Self::from_bytes)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CustomLabeling, Id, Label};
+    use ::borsh::{from_slice, to_vec};
+    use claim::*;
+    use pretty_assertions::assert_eq;
+
+    struct Order;
+
+    impl Label for Order {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("Order")
+        }
+    }
+
+    #[test]
+    fn test_borsh_roundtrip() {
+        let id: Id<Order, u64> = Id::for_labeled(17);
+        let bytes = assert_ok!(to_vec(&id));
+        assert_eq!(bytes, 17_u64.to_le_bytes());
+        let actual: Id<Order, u64> = assert_ok!(from_slice(&bytes));
+        assert_eq!(actual, id);
+        assert_eq!(actual.to_string(), "Order::17");
+
+        let id: Id<Order, String> = Id::for_labeled("o-17".to_string());
+        let actual: Id<Order, String> = assert_ok!(from_slice(&assert_ok!(to_vec(&id))));
+        assert_eq!(actual, id);
+
+        assert_err!(from_slice::<Id<Order, u64>>(&[1, 2, 3]));
+    }
+
+    #[cfg(feature = "snowflake")]
+    #[test]
+    fn test_borsh_pretty_snowflake_roundtrip() {
+        use crate::snowflake::pretty::PrettySnowflakeId;
+
+        let id = PrettySnowflakeId::from_rep("ABC-DE2-FGH-J34".into());
+        let bytes = assert_ok!(to_vec(&id));
+        assert_eq!(bytes, assert_ok!(to_vec("ABC-DE2-FGH-J34")));
+        assert_eq!(assert_ok!(from_slice::<PrettySnowflakeId>(&bytes)), id);
+    }
+
+    #[cfg(feature = "ksuid")]
+    #[test]
+    fn test_borsh_ksuid_roundtrip() {
+        use crate::id::ksuid::BYTES_LEN;
+        use crate::id::Ksuid;
+
+        let id: Id<Order, Ksuid> = Id::for_labeled(Ksuid::new());
+        let bytes = assert_ok!(to_vec(&id));
+        assert_eq!(bytes.len(), BYTES_LEN);
+        let actual: Id<Order, Ksuid> = assert_ok!(from_slice(&bytes));
+        assert_eq!(actual, id);
+    }
+
+    #[cfg(feature = "ulid")]
+    #[test]
+    fn test_borsh_ulid_roundtrip() {
+        use crate::Ulid;
+
+        let id: Id<Order, Ulid> = Id::for_labeled(Ulid::new());
+        let bytes = assert_ok!(to_vec(&id));
+        assert_eq!(bytes, id.id.as_bytes());
+        let actual: Id<Order, Ulid> = assert_ok!(from_slice(&bytes));
+        assert_eq!(actual, id);
+    }
+}
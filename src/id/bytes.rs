@@ -0,0 +1,218 @@
+//! Canonical binary encodings of ids, for use as keys in ordered key-value stores such as
+//! RocksDB.
+//!
+//! Each [`IdBytes`] encoding has a fixed size and sorts bytewise in the order of its ids, so a
+//! range scan over the keys visits entities in id order; e.g., creation order for time-ordered
+//! ids.
+//!
+#![cfg_attr(feature = "derive", doc = "```rust")]
+#![cfg_attr(not(feature = "derive"), doc = "```ignore")]
+//! use tagid::{Id, Label};
+//!
+//! #[derive(Label)]
+//! struct Order;
+//!
+//! let earlier: Id<Order, i64> = Id::for_labeled(-3);
+//! let later: Id<Order, i64> = Id::for_labeled(1_029);
+//! assert!(earlier.to_bytes() < later.to_bytes());
+//!
+//! let key = later.to_bytes();
+//! assert_eq!(Id::<Order, i64>::from_bytes(&key).unwrap(), later);
+//! ```
+
+use crate::{Id, Label};
+use thiserror::Error;
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum IdBytesError {
+    #[error("id encoding must be {expected} bytes but was {actual}")]
+    InvalidLength { expected: usize, actual: usize },
+}
+
+/// An id representation with a fixed-size binary encoding whose bytewise order matches the order
+/// of the ids.
+pub trait IdBytes: Sized {
+    /// The encoded size in bytes.
+    const LEN: usize;
+
+    type Bytes: AsRef<[u8]>;
+
+    fn to_id_bytes(&self) -> Self::Bytes;
+
+    fn from_id_bytes(bytes: &[u8]) -> Result<Self, IdBytesError>;
+}
+
+fn fixed<const N: usize>(bytes: &[u8]) -> Result<[u8; N], IdBytesError> {
+    bytes.try_into().map_err(|_| IdBytesError::InvalidLength {
+        expected: N,
+        actual: bytes.len(),
+    })
+}
+
+impl<T: ?Sized, ID: IdBytes> Id<T, ID> {
+    /// The canonical binary encoding of the id; the label is not encoded.
+    pub fn to_bytes(&self) -> ID::Bytes {
+        self.id.to_id_bytes()
+    }
+}
+
+impl<T: ?Sized + Label, ID: IdBytes> Id<T, ID> {
+    /// Decodes an id from its [`Id::to_bytes()`] encoding, labeled for `T`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, IdBytesError> {
+        ID::from_id_bytes(bytes).map(Self::for_labeled)
+    }
+}
+
+const SIGN_BIT: u64 = 1 << 63;
+
+/// Big-endian with the sign bit flipped, so negative ids sort before positive ones.
+impl IdBytes for i64 {
+    const LEN: usize = 8;
+    type Bytes = [u8; 8];
+
+    fn to_id_bytes(&self) -> Self::Bytes {
+        (*self as u64 ^ SIGN_BIT).to_be_bytes()
+    }
+
+    fn from_id_bytes(bytes: &[u8]) -> Result<Self, IdBytesError> {
+        fixed(bytes).map(|bytes| (u64::from_be_bytes(bytes) ^ SIGN_BIT) as Self)
+    }
+}
+
+impl IdBytes for u64 {
+    const LEN: usize = 8;
+    type Bytes = [u8; 8];
+
+    fn to_id_bytes(&self) -> Self::Bytes {
+        self.to_be_bytes()
+    }
+
+    fn from_id_bytes(bytes: &[u8]) -> Result<Self, IdBytesError> {
+        fixed(bytes).map(Self::from_be_bytes)
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl IdBytes for ::uuid::Uuid {
+    const LEN: usize = 16;
+    type Bytes = [u8; 16];
+
+    fn to_id_bytes(&self) -> Self::Bytes {
+        self.into_bytes()
+    }
+
+    fn from_id_bytes(bytes: &[u8]) -> Result<Self, IdBytesError> {
+        fixed(bytes).map(Self::from_bytes)
+    }
+}
+
+#[cfg(feature = "ulid")]
+impl IdBytes for crate::Ulid {
+    const LEN: usize = 16;
+    type Bytes = [u8; 16];
+
+    fn to_id_bytes(&self) -> Self::Bytes {
+        *self.as_bytes()
+    }
+
+    fn from_id_bytes(bytes: &[u8]) -> Result<Self, IdBytesError> {
+        fixed(bytes).map(Self::from_bytes)
+    }
+}
+
+#[cfg(feature = "ksuid")]
+impl IdBytes for crate::Ksuid {
+    const LEN: usize = 20;
+    type Bytes = [u8; 20];
+
+    fn to_id_bytes(&self) -> Self::Bytes {
+        *self.as_bytes()
+    }
+
+    fn from_id_bytes(bytes: &[u8]) -> Result<Self, IdBytesError> {
+        fixed(bytes).map(Self::from_bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CustomLabeling;
+    use claim::*;
+    use pretty_assertions::assert_eq;
+
+    struct Order;
+
+    impl Label for Order {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("Order")
+        }
+    }
+
+    fn assert_round_trip_ordered<ID: IdBytes + Ord + std::fmt::Debug>(mut ids: Vec<ID>) {
+        ids.sort();
+        let keys: Vec<Vec<u8>> = ids
+            .iter()
+            .map(|id| id.to_id_bytes().as_ref().to_vec())
+            .collect();
+        assert!(keys.iter().all(|key| key.len() == ID::LEN));
+        assert!(keys.windows(2).all(|pair| pair[0] < pair[1]));
+        for (id, key) in ids.iter().zip(&keys) {
+            assert_eq!(&assert_ok!(ID::from_id_bytes(key)), id);
+        }
+    }
+
+    #[test]
+    fn test_integer_id_bytes() {
+        assert_round_trip_ordered(vec![
+            i64::MIN,
+            -1,
+            0,
+            1,
+            7_014_829_537_245_790_208,
+            i64::MAX,
+        ]);
+        assert_round_trip_ordered(vec![0_u64, 1, 256, u64::MAX]);
+
+        let id: Id<Order, i64> = Id::for_labeled(17);
+        let actual = assert_ok!(Id::<Order, i64>::from_bytes(&id.to_bytes()));
+        assert_eq!(actual.label, "Order");
+        assert_eq!(actual.id, 17);
+
+        assert_eq!(
+            assert_err!(Id::<Order, i64>::from_bytes(&[0; 4])),
+            IdBytesError::InvalidLength {
+                expected: 8,
+                actual: 4
+            }
+        );
+    }
+
+    #[cfg(feature = "ulid")]
+    #[test]
+    fn test_ulid_id_bytes() {
+        let start =
+            std::time::SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_700_000_000);
+        assert_round_trip_ordered(
+            (0..16)
+                .map(|i| {
+                    crate::UlidGenerator::next_id_at(start + std::time::Duration::from_millis(i))
+                })
+                .collect(),
+        );
+    }
+
+    #[cfg(feature = "ksuid")]
+    #[test]
+    fn test_ksuid_id_bytes() {
+        assert_round_trip_ordered((0..16).map(|_| crate::Ksuid::new()).collect());
+    }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn test_uuid_id_bytes() {
+        assert_round_trip_ordered((0..16).map(|_| ::uuid::Uuid::now_v7()).collect());
+    }
+}
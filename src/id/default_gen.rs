@@ -0,0 +1,127 @@
+#[cfg(feature = "uuid")]
+use crate::config::{self, GeneratorKind};
+#[cfg(any(
+    feature = "cuid",
+    feature = "ksuid",
+    feature = "ulid",
+    feature = "snowflake"
+))]
+use crate::id::IdGenError;
+#[cfg(any(
+    feature = "cuid",
+    feature = "uuid",
+    feature = "ksuid",
+    feature = "ulid",
+    feature = "snowflake"
+))]
+use crate::id::{IdGenerator, TryIdGenerator};
+use std::marker::PhantomData;
+
+/// Generates `ID`s via the generator the application configured for that id type.
+///
+/// The whole application switches generators at one point, the `default_generator` of the applied
+/// [`TagIdConfig`](crate::config::TagIdConfig) or [`config::set_default_generator()`], while
+/// entities keep concrete id types such as `Id<T, Uuid>`. Only id types with more than one
+/// generator are configurable, and the configuration offers only their alternatives; other id
+/// types always use their standard generator:
+///
+/// | `ID`                | standard generator         | configurable alternatives |
+/// |---------------------|----------------------------|---------------------------|
+/// | `String`            | `CuidGenerator`            |                           |
+/// | `Uuid`              | `UuidGenerator` (v4)       | `uuid_v7`                 |
+/// | `Ksuid`             | `KsuidGenerator`           |                           |
+/// | `Ulid`              | `UlidGenerator`            |                           |
+/// | `i64`               | `SnowflakeGenerator`       |                           |
+/// | `PrettySnowflakeId` | `PrettySnowflakeGenerator` |                           |
+///
+/// [`config::set_default_generator()`]: crate::config::set_default_generator
+pub struct DefaultIdGenerator<ID>(PhantomData<fn() -> ID>);
+
+/// Implements [`DefaultIdGenerator`] for an id type with a single generator.
+macro_rules! standard_generator {
+    ($(#[$meta:meta])* $id:ty => $generator:ty) => {
+        $(#[$meta])*
+        impl IdGenerator for DefaultIdGenerator<$id> {
+            type IdType = $id;
+
+            fn next_id_rep() -> Self::IdType {
+                <$generator as IdGenerator>::next_id_rep()
+            }
+
+            fn is_available() -> bool {
+                <$generator as IdGenerator>::is_available()
+            }
+        }
+
+        $(#[$meta])*
+        impl TryIdGenerator for DefaultIdGenerator<$id> {
+            fn try_next_id_rep() -> Result<Self::IdType, IdGenError> {
+                <$generator as TryIdGenerator>::try_next_id_rep()
+            }
+        }
+    };
+}
+
+standard_generator!(#[cfg(feature = "cuid")] String => crate::CuidGenerator);
+standard_generator!(#[cfg(feature = "ksuid")] crate::Ksuid => crate::KsuidGenerator);
+standard_generator!(#[cfg(feature = "ulid")] crate::Ulid => crate::UlidGenerator);
+standard_generator!(#[cfg(feature = "snowflake")] i64 => crate::SnowflakeGenerator);
+standard_generator!(
+    #[cfg(feature = "snowflake")]
+    crate::snowflake::pretty::PrettySnowflakeId => crate::snowflake::pretty::PrettySnowflakeGenerator
+);
+
+/// Generates random (v4) UUIDs unless `uuid_v7` is configured.
+///
+/// ```rust
+/// use tagid::config::{self, GeneratorKind};
+/// use tagid::{DefaultIdGenerator, Entity, Id, Label};
+/// use uuid::Uuid;
+///
+/// #[derive(Label)]
+/// struct Order;
+/// impl Entity for Order { type IdGen = DefaultIdGenerator<Uuid>; }
+///
+/// config::set_default_generator(GeneratorKind::UuidV7).unwrap();
+/// let id: Id<Order, Uuid> = Order::next_id();
+/// assert_eq!(id.id.get_version_num(), 7);
+/// ```
+#[cfg(feature = "uuid")]
+impl IdGenerator for DefaultIdGenerator<::uuid::Uuid> {
+    type IdType = ::uuid::Uuid;
+
+    fn next_id_rep() -> Self::IdType {
+        match config::default_generator() {
+            Some(GeneratorKind::UuidV7) => <crate::UuidV7Generator as IdGenerator>::next_id_rep(),
+            Some(GeneratorKind::Uuid) | None => {
+                <crate::UuidGenerator as IdGenerator>::next_id_rep()
+            }
+        }
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl TryIdGenerator for DefaultIdGenerator<::uuid::Uuid> {}
+
+#[cfg(all(test, feature = "uuid"))]
+mod tests {
+    use super::*;
+    use claim::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_default_id_generator() {
+        assert_ok!(config::set_default_generator(GeneratorKind::UuidV7));
+        assert!(DefaultIdGenerator::<::uuid::Uuid>::is_available());
+        let id = assert_ok!(DefaultIdGenerator::<::uuid::Uuid>::try_next_id_rep());
+        assert_eq!(id.get_version_num(), 7);
+
+        #[cfg(feature = "cuid")]
+        assert_eq!(DefaultIdGenerator::<String>::next_id_rep().len(), 24);
+
+        assert_matches!(
+            config::set_default_generator(GeneratorKind::Uuid),
+            Err(config::ConfigError::AlreadyInitialized(_))
+        );
+    }
+}
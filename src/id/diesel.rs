@@ -0,0 +1,213 @@
+//! Diesel integration, mapping `Id<T, ID>` to the SQL type of its inner `ID` so typed ids can be
+//! used directly in Diesel schemas, queries, and `Queryable` structs.
+
+use crate::{Id, Label};
+use ::diesel::backend::Backend;
+use ::diesel::deserialize::{self, FromSql, FromStaticSqlRow, Queryable};
+use ::diesel::expression::{AsExpression, TypedExpressionType};
+use ::diesel::serialize::{self, Output, ToSql};
+#[cfg(feature = "ulid")]
+use ::diesel::sql_types::Binary;
+use ::diesel::sql_types::{SingleValue, SqlType};
+
+impl<T, ID, ST, DB> ToSql<ST, DB> for Id<T, ID>
+where
+    T: ?Sized,
+    ID: ToSql<ST, DB>,
+    DB: Backend,
+{
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, DB>) -> serialize::Result {
+        self.id.to_sql(out)
+    }
+}
+
+/// Labels the decoded id for `T`, as deserialization does.
+impl<T, ID, ST, DB> FromSql<ST, DB> for Id<T, ID>
+where
+    T: ?Sized + Label,
+    ID: FromSql<ST, DB>,
+    DB: Backend,
+{
+    fn from_sql(bytes: DB::RawValue<'_>) -> deserialize::Result<Self> {
+        ID::from_sql(bytes).map(Self::for_labeled)
+    }
+}
+
+impl<T, ID, ST, DB> Queryable<ST, DB> for Id<T, ID>
+where
+    T: ?Sized,
+    ST: SingleValue,
+    DB: Backend,
+    Self: FromStaticSqlRow<ST, DB>,
+{
+    type Row = Self;
+
+    fn build(row: Self::Row) -> deserialize::Result<Self> {
+        Ok(row)
+    }
+}
+
+impl<T, ID, ST> AsExpression<ST> for Id<T, ID>
+where
+    T: ?Sized,
+    ID: AsExpression<ST>,
+    ST: SqlType + TypedExpressionType,
+{
+    type Expression = ID::Expression;
+
+    fn as_expression(self) -> Self::Expression {
+        self.id.as_expression()
+    }
+}
+
+impl<'a, T, ID, ST> AsExpression<ST> for &'a Id<T, ID>
+where
+    T: ?Sized,
+    &'a ID: AsExpression<ST>,
+    ST: SqlType + TypedExpressionType,
+{
+    type Expression = <&'a ID as AsExpression<ST>>::Expression;
+
+    fn as_expression(self) -> Self::Expression {
+        (&self.id).as_expression()
+    }
+}
+
+/// ULIDs are stored as their 16 byte binary form, which sorts as the ULIDs do.
+#[cfg(feature = "ulid")]
+impl<DB> ToSql<Binary, DB> for crate::Ulid
+where
+    DB: Backend,
+    [u8]: ToSql<Binary, DB>,
+{
+    fn to_sql<'b>(&'b self, out: &mut Output<'b, '_, DB>) -> serialize::Result {
+        self.as_bytes().as_slice().to_sql(out)
+    }
+}
+
+#[cfg(feature = "ulid")]
+impl<DB> FromSql<Binary, DB> for crate::Ulid
+where
+    DB: Backend,
+    Vec<u8>: FromSql<Binary, DB>,
+{
+    fn from_sql(bytes: DB::RawValue<'_>) -> deserialize::Result<Self> {
+        let bytes = Vec::<u8>::from_sql(bytes)?;
+        let bytes = <[u8; 16]>::try_from(bytes.as_slice())
+            .map_err(|_| format!("expected 16 bytes for ULID but got {}", bytes.len()))?;
+        Ok(Self::from_bytes(bytes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CustomLabeling, Id, Label};
+    use ::diesel::prelude::*;
+    use ::diesel::sqlite::SqliteConnection;
+    use pretty_assertions::assert_eq;
+
+    ::diesel::table! {
+        orders (id) {
+            id -> BigInt,
+            customer_id -> Text,
+            parent_id -> Nullable<BigInt>,
+        }
+    }
+
+    struct Order;
+
+    impl Label for Order {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("Order")
+        }
+    }
+
+    struct Customer;
+
+    impl Label for Customer {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("Customer")
+        }
+    }
+
+    #[derive(Debug, PartialEq, Queryable, Insertable)]
+    #[diesel(table_name = orders)]
+    struct OrderRow {
+        id: Id<Order, i64>,
+        customer_id: Id<Customer, String>,
+        parent_id: Option<Id<Order, i64>>,
+    }
+
+    #[test]
+    fn test_diesel_roundtrip() {
+        let mut conn = SqliteConnection::establish(":memory:").unwrap();
+        ::diesel::sql_query(
+            "CREATE TABLE orders (id BIGINT PRIMARY KEY, customer_id TEXT NOT NULL, parent_id BIGINT)",
+        )
+        .execute(&mut conn)
+        .unwrap();
+
+        let parent = OrderRow {
+            id: Id::for_labeled(1),
+            customer_id: Id::for_labeled("c-17".to_string()),
+            parent_id: None,
+        };
+        let child = OrderRow {
+            id: Id::for_labeled(2),
+            customer_id: Id::for_labeled("c-17".to_string()),
+            parent_id: Some(Id::for_labeled(1)),
+        };
+        ::diesel::insert_into(orders::table)
+            .values([&parent, &child])
+            .execute(&mut conn)
+            .unwrap();
+
+        let actual: OrderRow = orders::table
+            .filter(orders::parent_id.eq(&parent.id))
+            .first(&mut conn)
+            .unwrap();
+        assert_eq!(actual, child);
+        assert_eq!(actual.customer_id.to_string(), "Customer::c-17");
+
+        let ids: Vec<Id<Order, i64>> = orders::table
+            .select(orders::id)
+            .order(orders::id)
+            .load(&mut conn)
+            .unwrap();
+        assert_eq!(ids, vec![parent.id, child.id]);
+    }
+
+    #[cfg(feature = "ulid")]
+    #[test]
+    fn test_diesel_ulid_roundtrip() {
+        use crate::Ulid;
+
+        ::diesel::table! {
+            imports (id) {
+                id -> Binary,
+            }
+        }
+
+        let mut conn = SqliteConnection::establish(":memory:").unwrap();
+        ::diesel::sql_query("CREATE TABLE imports (id BLOB PRIMARY KEY)")
+            .execute(&mut conn)
+            .unwrap();
+
+        let id: Id<Order, Ulid> = Id::for_labeled(Ulid::new());
+        ::diesel::insert_into(imports::table)
+            .values(imports::id.eq(&id))
+            .execute(&mut conn)
+            .unwrap();
+
+        let actual: Id<Order, Ulid> = imports::table
+            .select(imports::id)
+            .filter(imports::id.eq(&id))
+            .first(&mut conn)
+            .unwrap();
+        assert_eq!(actual, id);
+    }
+}
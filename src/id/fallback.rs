@@ -0,0 +1,216 @@
+use crate::id::{AsRawId, IdGenError, IdGenerator, RawId, TryIdGenerator};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::fmt;
+use std::marker::PhantomData;
+
+/// Generates ids via the primary generator `P` when it is available, falling back to `F`
+/// otherwise.
+///
+/// For example, a snowflake primary with a random fallback keeps entities working in dev/test
+/// environments that never initialize the snowflake generator. Each id records which generator
+/// produced it as a [`FallbackId`].
+///
+#[cfg_attr(all(feature = "derive", feature = "cuid"), doc = "```rust")]
+#[cfg_attr(not(all(feature = "derive", feature = "cuid")), doc = "```ignore")]
+/// use tagid::{CuidGenerator, Entity, FallbackGenerator, FallbackId, IdGenerator, Label};
+///
+/// struct Unavailable;
+/// impl IdGenerator for Unavailable {
+///     type IdType = i64;
+///     fn next_id_rep() -> i64 { unreachable!() }
+///     fn is_available() -> bool { false }
+/// }
+///
+/// #[derive(Label)]
+/// struct Order;
+/// impl Entity for Order { type IdGen = FallbackGenerator<Unavailable, CuidGenerator>; }
+///
+/// let id = Order::next_id();
+/// assert!(id.id.is_fallback());
+/// ```
+pub struct FallbackGenerator<P, F> {
+    marker: PhantomData<fn() -> (P, F)>,
+}
+
+impl<P: IdGenerator, F: IdGenerator> IdGenerator for FallbackGenerator<P, F> {
+    type IdType = FallbackId<P::IdType, F::IdType>;
+
+    fn next_id_rep() -> Self::IdType {
+        if P::is_available() {
+            FallbackId::Primary(P::next_id_rep())
+        } else {
+            FallbackId::Fallback(F::next_id_rep())
+        }
+    }
+
+    fn is_available() -> bool {
+        P::is_available() || F::is_available()
+    }
+}
+
+/// Falls back to `F` when the primary generator fails, reporting the fallback's error if both do.
+impl<P: TryIdGenerator, F: TryIdGenerator> TryIdGenerator for FallbackGenerator<P, F> {
+    fn try_next_id_rep() -> Result<Self::IdType, IdGenError> {
+        P::try_next_id_rep()
+            .map(FallbackId::Primary)
+            .or_else(|_| F::try_next_id_rep().map(FallbackId::Fallback))
+    }
+}
+
+/// An id produced by a [`FallbackGenerator`], recording whether the primary or fallback
+/// generator produced it. Serializes as the underlying id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum FallbackId<P, F> {
+    Primary(P),
+    Fallback(F),
+}
+
+impl<P, F> FallbackId<P, F> {
+    pub const fn is_primary(&self) -> bool {
+        matches!(self, Self::Primary(_))
+    }
+
+    pub const fn is_fallback(&self) -> bool {
+        matches!(self, Self::Fallback(_))
+    }
+}
+
+impl<P: fmt::Display, F: fmt::Display> fmt::Display for FallbackId<P, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Primary(id) => id.fmt(f),
+            Self::Fallback(id) => id.fmt(f),
+        }
+    }
+}
+
+impl<P: AsRawId, F: AsRawId> AsRawId for FallbackId<P, F> {
+    fn as_str(&self) -> Cow<'_, str> {
+        match self {
+            Self::Primary(id) => id.as_str(),
+            Self::Fallback(id) => id.as_str(),
+        }
+    }
+
+    fn as_i64(&self) -> Option<i64> {
+        match self {
+            Self::Primary(id) => id.as_i64(),
+            Self::Fallback(id) => id.as_i64(),
+        }
+    }
+
+    fn as_u128(&self) -> Option<u128> {
+        match self {
+            Self::Primary(id) => id.as_u128(),
+            Self::Fallback(id) => id.as_u128(),
+        }
+    }
+
+    fn as_bytes(&self) -> Option<Cow<'_, [u8]>> {
+        match self {
+            Self::Primary(id) => id.as_bytes(),
+            Self::Fallback(id) => id.as_bytes(),
+        }
+    }
+}
+
+impl<P: Into<Self>, F: Into<Self>> From<FallbackId<P, F>> for RawId {
+    fn from(id: FallbackId<P, F>) -> Self {
+        match id {
+            FallbackId::Primary(id) => id.into(),
+            FallbackId::Fallback(id) => id.into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CustomLabeling, Entity, Label};
+    use pretty_assertions::assert_eq;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    static PRIMARY_AVAILABLE: AtomicBool = AtomicBool::new(false);
+
+    struct Primary;
+
+    impl IdGenerator for Primary {
+        type IdType = i64;
+
+        fn next_id_rep() -> Self::IdType {
+            assert!(Self::is_available(), "primary generator is not available");
+            17
+        }
+
+        fn is_available() -> bool {
+            PRIMARY_AVAILABLE.load(Ordering::SeqCst)
+        }
+    }
+
+    struct Secondary;
+
+    impl IdGenerator for Secondary {
+        type IdType = String;
+
+        fn next_id_rep() -> Self::IdType {
+            "fallback".to_string()
+        }
+    }
+
+    struct Order;
+
+    impl Label for Order {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("Order")
+        }
+    }
+
+    impl Entity for Order {
+        type IdGen = FallbackGenerator<Primary, Secondary>;
+    }
+
+    #[test]
+    fn test_fallback_generator() {
+        let id = Order::next_id();
+        assert_eq!(id.id, FallbackId::Fallback("fallback".to_string()));
+        assert_eq!(id.to_string(), "Order::fallback");
+        assert_eq!(serde_json::to_string(&id).unwrap(), r#""fallback""#);
+
+        PRIMARY_AVAILABLE.store(true, Ordering::SeqCst);
+        let id = Order::next_id();
+        assert!(id.id.is_primary());
+        assert_eq!(id.to_string(), "Order::17");
+        assert_eq!(RawId::from(id), RawId::I64(17));
+    }
+
+    struct Failing;
+
+    impl IdGenerator for Failing {
+        type IdType = i64;
+
+        fn next_id_rep() -> Self::IdType {
+            Self::try_next_id_rep().expect("failing generator")
+        }
+    }
+
+    impl TryIdGenerator for Failing {
+        fn try_next_id_rep() -> Result<Self::IdType, IdGenError> {
+            Err(IdGenError::Unavailable("Failing"))
+        }
+    }
+
+    impl TryIdGenerator for Secondary {}
+
+    #[test]
+    fn test_try_fallback_generator() {
+        let id = FallbackGenerator::<Failing, Secondary>::try_next_id_rep().unwrap();
+        assert_eq!(id, FallbackId::Fallback("fallback".to_string()));
+
+        let err = FallbackGenerator::<Failing, Failing>::try_next_id_rep().unwrap_err();
+        assert_eq!(err.to_string(), "Failing is not initialized");
+    }
+}
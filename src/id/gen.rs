@@ -1,22 +1,75 @@
+use std::error::Error;
+use std::time::SystemTime;
+use thiserror::Error;
+
 pub trait IdGenerator {
     type IdType: Send;
     fn next_id_rep() -> Self::IdType;
+
+    /// Whether the generator is ready to produce ids; e.g., generators backed by global state
+    /// report `false` until that state is initialized.
+    fn is_available() -> bool {
+        true
+    }
+}
+
+/// Generates ids from sources that can fail, such as clocks, networks, or an exhaustible sequence
+/// space.
+///
+/// Infallible generators take the default, which wraps [`IdGenerator::next_id_rep()`]. Fallible
+/// generators override [`try_next_id_rep()`](Self::try_next_id_rep) and keep
+/// [`IdGenerator::next_id_rep()`] as a wrapper that panics on failure, so they remain usable as an
+/// [`Entity::IdGen`](crate::Entity::IdGen), while
+/// [`Entity::try_next_id()`](crate::Entity::try_next_id) surfaces the error.
+pub trait TryIdGenerator: IdGenerator {
+    fn try_next_id_rep() -> Result<Self::IdType, IdGenError> {
+        Ok(Self::next_id_rep())
+    }
+}
+
+/// Generates time-ordered ids dated at an explicit timestamp rather than now; e.g., to backfill
+/// historical records while preserving their time order relative to the original events.
+pub trait TimeOrderedIdGenerator: IdGenerator {
+    fn next_id_rep_at(timestamp: SystemTime) -> Self::IdType;
+}
+
+#[derive(Debug, Error)]
+pub enum IdGenError {
+    #[error("{0} is not initialized")]
+    Unavailable(&'static str),
+
+    #[cfg(feature = "snowflake")]
+    #[error("{0}")]
+    ClockDrift(#[from] crate::snowflake::ClockDriftError),
+
+    #[error("id space exhausted: {0}")]
+    Exhausted(String),
+
+    #[error("id source failed: {0}")]
+    Source(#[source] Box<dyn Error + Send + Sync>),
 }
 
 #[cfg(feature = "cuid")]
-pub use self::cuid::{CuidGenerator, CuidId};
+pub use self::cuid::{CuidGenerator, CuidId, SharedCuidGenerator, SharedCuidId};
 
 #[cfg(feature = "uuid")]
-pub use self::uuid::UuidGenerator;
+pub use self::uuid::{UuidGenerator, UuidV7Generator};
+
+#[cfg(feature = "nanoid")]
+pub use self::nanoid::{NanoIdAlphabet, NanoIdGenerator, UrlSafeAlphabet};
 
 #[cfg(feature = "cuid")]
 mod cuid {
     use super::*;
     use crate::Id;
+    use smol_str::SmolStr;
 
     #[allow(dead_code)]
     pub type CuidId<T> = Id<T, String>;
 
+    #[allow(dead_code)]
+    pub type SharedCuidId<T> = Id<T, SmolStr>;
+
     pub struct CuidGenerator;
 
     impl IdGenerator for CuidGenerator {
@@ -26,6 +79,37 @@ mod cuid {
             ::cuid2::create_id()
         }
     }
+
+    impl TryIdGenerator for CuidGenerator {}
+
+    /// Generates CUIDs as [`SmolStr`]s, which share rather than copy their text on clone; e.g.,
+    /// for ids held in envelope metadata cloned per subscriber.
+    ///
+    /// ```rust
+    /// use tagid::{Entity, Label, SharedCuidGenerator, SharedCuidId};
+    ///
+    /// #[derive(Label)]
+    /// struct Order;
+    ///
+    /// impl Entity for Order {
+    ///     type IdGen = SharedCuidGenerator;
+    /// }
+    ///
+    /// let id: SharedCuidId<Order> = Order::next_id();
+    /// let subscriber_id = id.clone();
+    /// assert_eq!(subscriber_id.id.as_ptr(), id.id.as_ptr());
+    /// ```
+    pub struct SharedCuidGenerator;
+
+    impl IdGenerator for SharedCuidGenerator {
+        type IdType = SmolStr;
+
+        fn next_id_rep() -> Self::IdType {
+            ::cuid2::create_id().into()
+        }
+    }
+
+    impl TryIdGenerator for SharedCuidGenerator {}
 }
 
 #[cfg(feature = "uuid")]
@@ -41,4 +125,174 @@ mod uuid {
             ::uuid::Uuid::new_v4()
         }
     }
+
+    impl TryIdGenerator for UuidGenerator {}
+
+    /// Generates time-ordered version 7 UUIDs, whose index locality suits database primary keys.
+    pub struct UuidV7Generator;
+
+    impl IdGenerator for UuidV7Generator {
+        type IdType = ::uuid::Uuid;
+
+        fn next_id_rep() -> Self::IdType {
+            ::uuid::Uuid::now_v7()
+        }
+    }
+
+    impl TimeOrderedIdGenerator for UuidV7Generator {
+        fn next_id_rep_at(timestamp: SystemTime) -> Self::IdType {
+            let since = timestamp
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap_or_default();
+            let timestamp = ::uuid::Timestamp::from_unix(
+                ::uuid::NoContext,
+                since.as_secs(),
+                since.subsec_nanos(),
+            );
+            ::uuid::Uuid::new_v7(timestamp)
+        }
+    }
+
+    impl TryIdGenerator for UuidV7Generator {}
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        #[test]
+        fn test_uuid_v7_generator_is_time_ordered() {
+            let ids: Vec<_> = (0..100)
+                .map(|_| <UuidV7Generator as IdGenerator>::next_id_rep())
+                .collect();
+            assert!(ids.iter().all(|id| id.get_version_num() == 7));
+
+            let mut sorted = ids.clone();
+            sorted.sort();
+            assert_eq!(sorted, ids);
+
+            assert_eq!(
+                <UuidGenerator as IdGenerator>::next_id_rep().get_version_num(),
+                4
+            );
+        }
+
+        #[test]
+        fn test_uuid_v7_generator_at_timestamp() {
+            let timestamp =
+                SystemTime::UNIX_EPOCH + std::time::Duration::from_millis(1_469_922_850_259);
+            let backfilled = UuidV7Generator::next_id_rep_at(timestamp);
+            assert_eq!(backfilled.get_version_num(), 7);
+            let (secs, nanos) = backfilled.get_timestamp().unwrap().to_unix();
+            assert_eq!((secs, nanos / 1_000_000), (1_469_922_850, 259));
+            assert!(backfilled < <UuidV7Generator as IdGenerator>::next_id_rep());
+        }
+    }
+}
+
+#[cfg(feature = "nanoid")]
+mod nanoid {
+    use super::*;
+    use smol_str::SmolStr;
+    use std::marker::PhantomData;
+
+    /// The characters a [`NanoIdGenerator`] draws from.
+    ///
+    /// An alphabet must have between 1 and 255 characters; a [`NanoIdGenerator`] over any other
+    /// alphabet fails to compile.
+    ///
+    /// ```compile_fail
+    /// use tagid::{IdGenerator, NanoIdAlphabet, NanoIdGenerator};
+    ///
+    /// struct Empty;
+    ///
+    /// impl NanoIdAlphabet for Empty {
+    ///     const ALPHABET: &'static [char] = &[];
+    /// }
+    ///
+    /// let rep = <NanoIdGenerator<8, Empty> as IdGenerator>::next_id_rep();
+    /// ```
+    pub trait NanoIdAlphabet {
+        const ALPHABET: &'static [char];
+    }
+
+    /// The default NanoID alphabet, `A-Za-z0-9_-`, whose ids are safe in URLs without escaping.
+    pub struct UrlSafeAlphabet;
+
+    impl NanoIdAlphabet for UrlSafeAlphabet {
+        const ALPHABET: &'static [char] = &::nanoid::alphabet::SAFE;
+    }
+
+    /// Generates short, random NanoIDs of `LEN` characters drawn from the alphabet `A`, suited to
+    /// public-facing resources; e.g., `NanoIdGenerator<12>` for 12 URL-safe characters.
+    ///
+    /// ```rust
+    /// use tagid::{Entity, Label, NanoIdAlphabet, NanoIdGenerator};
+    ///
+    /// struct Digits;
+    ///
+    /// impl NanoIdAlphabet for Digits {
+    ///     const ALPHABET: &'static [char] = &['0', '1', '2', '3', '4', '5', '6', '7', '8', '9'];
+    /// }
+    ///
+    /// #[derive(Label)]
+    /// struct Invite;
+    ///
+    /// impl Entity for Invite {
+    ///     type IdGen = NanoIdGenerator<8, Digits>;
+    /// }
+    ///
+    /// let id = Invite::next_id();
+    /// assert_eq!(id.id.len(), 8);
+    /// assert!(id.id.chars().all(|c| c.is_ascii_digit()));
+    /// ```
+    pub struct NanoIdGenerator<const LEN: usize = 21, A: NanoIdAlphabet = UrlSafeAlphabet> {
+        marker: PhantomData<fn() -> A>,
+    }
+
+    impl<const LEN: usize, A: NanoIdAlphabet> NanoIdGenerator<LEN, A> {
+        /// `A::ALPHABET`, checked at compile time to hold as many characters as NanoID can draw
+        /// from.
+        const ALPHABET: &'static [char] = {
+            assert!(!A::ALPHABET.is_empty(), "NanoID alphabet must not be empty");
+            assert!(
+                A::ALPHABET.len() <= u8::MAX as usize,
+                "NanoID alphabet must have at most 255 characters"
+            );
+            A::ALPHABET
+        };
+    }
+
+    impl<const LEN: usize, A: NanoIdAlphabet> IdGenerator for NanoIdGenerator<LEN, A> {
+        type IdType = SmolStr;
+
+        fn next_id_rep() -> Self::IdType {
+            ::nanoid::format(::nanoid::rngs::default, Self::ALPHABET, LEN).into()
+        }
+    }
+
+    impl<const LEN: usize, A: NanoIdAlphabet> TryIdGenerator for NanoIdGenerator<LEN, A> {}
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use pretty_assertions::assert_eq;
+        use std::collections::HashSet;
+
+        #[test]
+        fn test_nanoid_generator() {
+            let ids: HashSet<_> = (0..1_000)
+                .map(|_| <NanoIdGenerator as IdGenerator>::next_id_rep())
+                .collect();
+            assert_eq!(ids.len(), 1_000);
+            assert!(ids.iter().all(|id| id.len() == 21));
+            assert!(ids
+                .iter()
+                .flat_map(|id| id.chars())
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-'));
+
+            let id = <NanoIdGenerator<12> as IdGenerator>::next_id_rep();
+            assert_eq!(id.len(), 12);
+        }
+    }
 }
@@ -0,0 +1,164 @@
+use crate::Id;
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+
+/// A borrowed view of an [`Id`], for parameters that only read the id, so callers holding an id
+/// need not clone its representation.
+///
+#[cfg_attr(feature = "derive", doc = "```rust")]
+#[cfg_attr(not(feature = "derive"), doc = "```ignore")]
+/// use tagid::{Id, IdRef, Label};
+///
+/// #[derive(Label)]
+/// struct User;
+///
+/// fn audit(user_id: IdRef<'_, User, String>) -> String {
+///     format!("changed by {user_id}")
+/// }
+///
+/// let user_id: Id<User, String> = Id::for_labeled("x2f8k9q3".to_string());
+/// assert_eq!(audit(user_id.as_id_ref()), "changed by User::x2f8k9q3");
+/// assert_eq!(audit((&user_id).into()), audit(user_id.as_id_ref()));
+/// ```
+pub struct IdRef<'a, T: ?Sized, ID> {
+    pub label: &'a str,
+    pub id: &'a ID,
+    delimiter: &'static str,
+    marker: PhantomData<&'a T>,
+}
+
+impl<T: ?Sized, ID> Id<T, ID> {
+    pub fn as_id_ref(&self) -> IdRef<'_, T, ID> {
+        IdRef {
+            label: self.label.as_str(),
+            id: &self.id,
+            delimiter: self.delimiter(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, T: ?Sized, ID> IdRef<'a, T, ID> {
+    pub fn to_id(&self) -> Id<T, ID>
+    where
+        ID: Clone,
+    {
+        Id::direct(self.label, self.id.clone()).with_delimiter(self.delimiter)
+    }
+}
+
+impl<'a, T: ?Sized, ID> From<&'a Id<T, ID>> for IdRef<'a, T, ID> {
+    fn from(id: &'a Id<T, ID>) -> Self {
+        id.as_id_ref()
+    }
+}
+
+impl<T: ?Sized, ID> Clone for IdRef<'_, T, ID> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized, ID> Copy for IdRef<'_, T, ID> {}
+
+impl<T: ?Sized, ID: fmt::Debug> fmt::Debug for IdRef<'_, T, ID> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            f.debug_struct("IdRef")
+                .field("label", &self.label)
+                .field("id", self.id)
+                .finish()
+        } else if self.label.is_empty() {
+            write!(f, "{:?}", self.id)
+        } else {
+            write!(f, "{}{}{:?}", self.label, self.delimiter, self.id)
+        }
+    }
+}
+
+impl<T: ?Sized, ID: fmt::Display> fmt::Display for IdRef<'_, T, ID> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() || self.label.is_empty() {
+            write!(f, "{}", self.id)
+        } else {
+            write!(f, "{}{}{}", self.label, self.delimiter, self.id)
+        }
+    }
+}
+
+impl<T: ?Sized, ID: PartialEq> PartialEq for IdRef<'_, T, ID> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<T: ?Sized, ID: Eq> Eq for IdRef<'_, T, ID> {}
+
+impl<T: ?Sized, ID: PartialEq> PartialEq<Id<T, ID>> for IdRef<'_, T, ID> {
+    fn eq(&self, other: &Id<T, ID>) -> bool {
+        *self.id == other.id
+    }
+}
+
+impl<T: ?Sized, ID: Ord> Ord for IdRef<'_, T, ID> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.id.cmp(other.id)
+    }
+}
+
+impl<T: ?Sized, ID: PartialOrd> PartialOrd for IdRef<'_, T, ID> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.id.partial_cmp(other.id)
+    }
+}
+
+/// Hashes as the referenced [`Id`] does, by its id alone.
+impl<T: ?Sized, ID: Hash> Hash for IdRef<'_, T, ID> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CustomLabeling, Label};
+    use pretty_assertions::assert_eq;
+    use std::collections::hash_map::DefaultHasher;
+
+    struct User;
+
+    impl Label for User {
+        type Labeler = CustomLabeling;
+        const DELIMITER: &'static str = "/";
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("User")
+        }
+    }
+
+    fn hash_of(value: impl Hash) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn test_id_ref() {
+        let id: Id<User, String> = Id::for_labeled("abc".to_string());
+        let id_ref = id.as_id_ref();
+        assert_eq!(id_ref.to_string(), id.to_string());
+        assert_eq!(format!("{id_ref:?}"), format!("{id:?}"));
+        assert_eq!(format!("{id_ref:#}"), "abc");
+        assert_eq!(hash_of(id_ref), hash_of(&id));
+        assert!(id_ref == id);
+        assert_eq!(id_ref, IdRef::from(&id));
+        assert_eq!(id_ref.to_id(), id);
+        assert_eq!(id_ref.to_id().to_string(), "User/abc");
+
+        let other: Id<User, String> = Id::for_labeled("abd".to_string());
+        assert!(id_ref < other.as_id_ref());
+    }
+}
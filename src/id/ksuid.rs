@@ -0,0 +1,340 @@
+use crate::clock;
+use crate::id::{AsRawId, IdGenerator, RawId, RawIdError, TimeOrderedIdGenerator, TryIdGenerator};
+use rand::RngCore;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
+use thiserror::Error;
+
+/// KSUID timestamps count seconds from 2014-05-13T16:53:20Z.
+const KSUID_EPOCH_SECS: u64 = 1_400_000_000;
+const TIMESTAMP_LEN: usize = 4;
+const PAYLOAD_LEN: usize = 16;
+pub const BYTES_LEN: usize = TIMESTAMP_LEN + PAYLOAD_LEN;
+const ENCODED_LEN: usize = 27;
+const BASE62: &[u8; 62] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum KsuidError {
+    #[error("KSUID must be {ENCODED_LEN} characters, but was {0}")]
+    InvalidLength(usize),
+
+    #[error("invalid KSUID character: {0:?}")]
+    InvalidCharacter(char),
+
+    #[error("KSUID value exceeds 160 bits")]
+    Overflow,
+}
+
+/// A K-Sortable Unique IDentifier: a 32-bit timestamp (in seconds) followed by 128 random bits,
+/// rendered as 27 base62 characters that sort lexicographically in generation order.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Ksuid([u8; BYTES_LEN]);
+
+impl Ksuid {
+    /// Creates a KSUID for the current time with a random payload.
+    pub fn new() -> Self {
+        let mut payload = [0; PAYLOAD_LEN];
+        rand::thread_rng().fill_bytes(&mut payload);
+        Self::from_parts(clock::now(), payload)
+    }
+
+    /// Creates a KSUID from its timestamp and payload. Timestamps outside the KSUID range
+    /// saturate to the nearest representable second.
+    pub fn from_parts(timestamp: SystemTime, payload: [u8; PAYLOAD_LEN]) -> Self {
+        let secs = timestamp
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs())
+            .saturating_sub(KSUID_EPOCH_SECS);
+        let secs = u32::try_from(secs).unwrap_or(u32::MAX);
+
+        let mut bytes = [0; BYTES_LEN];
+        bytes[..TIMESTAMP_LEN].copy_from_slice(&secs.to_be_bytes());
+        bytes[TIMESTAMP_LEN..].copy_from_slice(&payload);
+        Self(bytes)
+    }
+
+    pub const fn from_bytes(bytes: [u8; BYTES_LEN]) -> Self {
+        Self(bytes)
+    }
+
+    pub const fn as_bytes(&self) -> &[u8; BYTES_LEN] {
+        &self.0
+    }
+
+    pub fn timestamp(&self) -> SystemTime {
+        let mut secs = [0; TIMESTAMP_LEN];
+        secs.copy_from_slice(&self.0[..TIMESTAMP_LEN]);
+        let secs = u64::from(u32::from_be_bytes(secs)) + KSUID_EPOCH_SECS;
+        SystemTime::UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    pub fn payload(&self) -> &[u8] {
+        &self.0[TIMESTAMP_LEN..]
+    }
+
+    fn words(&self) -> [u32; 5] {
+        let mut words = [0; 5];
+        for (word, chunk) in words.iter_mut().zip(self.0.chunks_exact(4)) {
+            *word = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+        words
+    }
+
+    fn from_words(words: [u32; 5]) -> Self {
+        let mut bytes = [0; BYTES_LEN];
+        for (chunk, word) in bytes.chunks_exact_mut(4).zip(words) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+        Self(bytes)
+    }
+}
+
+impl Default for Ksuid {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for Ksuid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut words = self.words();
+        let mut encoded = [BASE62[0]; ENCODED_LEN];
+        for digit in encoded.iter_mut().rev() {
+            let mut remainder = 0_u64;
+            for word in words.iter_mut() {
+                let value = (remainder << 32) | u64::from(*word);
+                *word = (value / 62) as u32;
+                remainder = value % 62;
+            }
+            *digit = BASE62[remainder as usize];
+        }
+
+        let encoded = std::str::from_utf8(&encoded).map_err(|_| fmt::Error)?;
+        f.write_str(encoded)
+    }
+}
+
+impl fmt::Debug for Ksuid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Ksuid({self})")
+    }
+}
+
+impl FromStr for Ksuid {
+    type Err = KsuidError;
+
+    fn from_str(rep: &str) -> Result<Self, Self::Err> {
+        if rep.len() != ENCODED_LEN {
+            return Err(KsuidError::InvalidLength(rep.chars().count()));
+        }
+
+        let mut words = [0_u32; 5];
+        for c in rep.chars() {
+            let digit = match c {
+                '0'..='9' => c as u64 - '0' as u64,
+                'A'..='Z' => c as u64 - 'A' as u64 + 10,
+                'a'..='z' => c as u64 - 'a' as u64 + 36,
+                _ => return Err(KsuidError::InvalidCharacter(c)),
+            };
+
+            let mut carry = digit;
+            for word in words.iter_mut().rev() {
+                let value = u64::from(*word) * 62 + carry;
+                *word = value as u32;
+                carry = value >> 32;
+            }
+
+            if carry != 0 {
+                return Err(KsuidError::Overflow);
+            }
+        }
+
+        Ok(Self::from_words(words))
+    }
+}
+
+impl Serialize for Ksuid {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Ksuid {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let rep = Cow::<'de, str>::deserialize(deserializer)?;
+        Self::from_str(&rep).map_err(de::Error::custom)
+    }
+}
+
+impl AsRawId for Ksuid {
+    fn as_str(&self) -> Cow<'_, str> {
+        Cow::Owned(self.to_string())
+    }
+
+    fn as_bytes(&self) -> Option<Cow<'_, [u8]>> {
+        Some(Cow::Borrowed(self.0.as_slice()))
+    }
+}
+
+impl From<Ksuid> for RawId {
+    fn from(id: Ksuid) -> Self {
+        Self::Str(id.to_string().into())
+    }
+}
+
+impl TryFrom<RawId> for Ksuid {
+    type Error = RawIdError;
+
+    fn try_from(raw: RawId) -> Result<Self, Self::Error> {
+        match &raw {
+            RawId::Str(rep) => Self::from_str(rep).map_err(|_| RawIdError::new("Ksuid", raw)),
+            _ => Err(RawIdError::new("Ksuid", raw)),
+        }
+    }
+}
+
+/// KSUIDs are stored as their 27 character text form.
+#[cfg(feature = "sqlx")]
+impl<'q, DB> sqlx::Encode<'q, DB> for Ksuid
+where
+    String: sqlx::Encode<'q, DB>,
+    DB: sqlx::Database,
+{
+    fn encode_by_ref(
+        &self,
+        buf: &mut <DB as sqlx::database::HasArguments<'q>>::ArgumentBuffer,
+    ) -> sqlx::encode::IsNull {
+        <String as sqlx::Encode<DB>>::encode(self.to_string(), buf)
+    }
+}
+
+#[cfg(feature = "sqlx")]
+impl<'r, DB> sqlx::Decode<'r, DB> for Ksuid
+where
+    String: sqlx::Decode<'r, DB>,
+    DB: sqlx::Database,
+{
+    fn decode(
+        value: <DB as sqlx::database::HasValueRef<'r>>::ValueRef,
+    ) -> Result<Self, sqlx::error::BoxDynError> {
+        let rep = <String as sqlx::Decode<DB>>::decode(value)?;
+        Ok(Self::from_str(&rep)?)
+    }
+}
+
+#[cfg(feature = "sqlx")]
+impl<DB> sqlx::Type<DB> for Ksuid
+where
+    String: sqlx::Type<DB>,
+    DB: sqlx::Database,
+{
+    fn type_info() -> DB::TypeInfo {
+        <String as sqlx::Type<DB>>::type_info()
+    }
+
+    fn compatible(ty: &DB::TypeInfo) -> bool {
+        <String as sqlx::Type<DB>>::compatible(ty)
+    }
+}
+
+pub struct KsuidGenerator;
+
+impl IdGenerator for KsuidGenerator {
+    type IdType = Ksuid;
+
+    fn next_id_rep() -> Self::IdType {
+        Ksuid::new()
+    }
+}
+
+impl TryIdGenerator for KsuidGenerator {}
+
+impl TimeOrderedIdGenerator for KsuidGenerator {
+    fn next_id_rep_at(timestamp: SystemTime) -> Self::IdType {
+        let mut payload = [0; PAYLOAD_LEN];
+        rand::thread_rng().fill_bytes(&mut payload);
+        Ksuid::from_parts(timestamp, payload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use claim::*;
+    use pretty_assertions::assert_eq;
+
+    const EXAMPLE_REP: &str = "0ujtsYcgvSTl8PAuAdqWYSMnLOv";
+    const EXAMPLE_PAYLOAD: [u8; PAYLOAD_LEN] = [
+        0xb5, 0xa1, 0xcd, 0x34, 0xb5, 0xf9, 0x9d, 0x11, 0x54, 0xfb, 0x68, 0x53, 0x34, 0x5c, 0x97,
+        0x35,
+    ];
+
+    #[test]
+    fn test_ksuid_encoding() {
+        let timestamp =
+            SystemTime::UNIX_EPOCH + Duration::from_secs(KSUID_EPOCH_SECS + 107_608_047);
+        let ksuid = Ksuid::from_parts(timestamp, EXAMPLE_PAYLOAD);
+        assert_eq!(ksuid.to_string(), EXAMPLE_REP);
+        assert_eq!(ksuid.timestamp(), timestamp);
+        assert_eq!(ksuid.payload(), EXAMPLE_PAYLOAD.as_slice());
+
+        let parsed: Ksuid = assert_ok!(EXAMPLE_REP.parse());
+        assert_eq!(parsed, ksuid);
+
+        assert_eq!(
+            Ksuid::from_bytes([0; BYTES_LEN]).to_string(),
+            "000000000000000000000000000"
+        );
+        assert_eq!(
+            Ksuid::from_bytes([0xff; BYTES_LEN]).to_string(),
+            "aWgEPTl1tmebfsQzFP4bxwgy80V"
+        );
+    }
+
+    #[test]
+    fn test_ksuid_parse_errors() {
+        assert_eq!(
+            assert_err!("0ujtsYcgvSTl8PAuAdqWYSMnLO".parse::<Ksuid>()),
+            KsuidError::InvalidLength(26)
+        );
+        assert_eq!(
+            assert_err!("0ujtsYcgvSTl8PAuAdqWYSMnLO-".parse::<Ksuid>()),
+            KsuidError::InvalidCharacter('-')
+        );
+        assert_eq!(
+            assert_err!("aWgEPTl1tmebfsQzFP4bxwgy80W".parse::<Ksuid>()),
+            KsuidError::Overflow
+        );
+    }
+
+    #[test]
+    fn test_ksuid_generator_sorts_by_time() {
+        let earlier = Ksuid::from_parts(
+            SystemTime::UNIX_EPOCH + Duration::from_secs(KSUID_EPOCH_SECS + 10),
+            [0xff; PAYLOAD_LEN],
+        );
+        let generated = KsuidGenerator::next_id_rep();
+        assert!(earlier < generated);
+        assert!(earlier.to_string() < generated.to_string());
+        assert_ne!(generated, KsuidGenerator::next_id_rep());
+    }
+
+    #[test]
+    fn test_ksuid_serde() {
+        let ksuid: Ksuid = assert_ok!(EXAMPLE_REP.parse());
+        let json = assert_ok!(serde_json::to_string(&ksuid));
+        assert_eq!(json, format!("\"{EXAMPLE_REP}\""));
+        let actual: Ksuid = assert_ok!(serde_json::from_str(&json));
+        assert_eq!(actual, ksuid);
+        assert_eq!(assert_ok!(Ksuid::try_from(RawId::from(ksuid))), ksuid);
+    }
+}
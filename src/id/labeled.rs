@@ -0,0 +1,155 @@
+//! Strict serde representation of ids in their labeled `Label::id` form.
+//!
+//! By default, `Id` (de)serializes as its bare id and stamps the target's label on whatever it
+//! reads. Fields using this module instead carry the label and reject payloads labeled for a
+//! different entity, catching cross-entity id mix-ups at the boundary:
+//!
+#![cfg_attr(feature = "derive", doc = "```rust")]
+#![cfg_attr(not(feature = "derive"), doc = "```ignore")]
+//! use serde::{Deserialize, Serialize};
+//! use tagid::{Id, Label};
+//!
+//! #[derive(Label)]
+//! struct User;
+//!
+//! #[derive(Label)]
+//! struct Order;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Request {
+//!     #[serde(with = "tagid::labeled")]
+//!     user_id: Id<User, u64>,
+//! }
+//!
+//! let request: Request = serde_json::from_str(r#"{ "user_id": "User::42" }"#).unwrap();
+//! assert_eq!(request.user_id.id, 42);
+//! assert!(serde_json::from_str::<Request>(r#"{ "user_id": "Order::42" }"#).is_err());
+//! ```
+
+use crate::{Id, Label};
+use serde::{de, Deserialize, Deserializer, Serializer};
+use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
+
+pub fn serialize<T, ID, S>(id: &Id<T, ID>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: ?Sized,
+    ID: fmt::Display,
+    S: Serializer,
+{
+    id.serialize_labeled(serializer)
+}
+
+pub fn deserialize<'de, T, ID, D>(deserializer: D) -> Result<Id<T, ID>, D::Error>
+where
+    T: ?Sized + Label,
+    ID: FromStr,
+    <ID as FromStr>::Err: fmt::Display,
+    D: Deserializer<'de>,
+{
+    Id::deserialize_checked(deserializer)
+}
+
+/// Deserializes either the labeled `Label::id` form or a bare id, as [`Id::parse_lenient()`] does;
+/// e.g., via `#[serde(deserialize_with = "tagid::labeled::deserialize_lenient")]` on query
+/// parameters.
+pub fn deserialize_lenient<'de, T, ID, D>(deserializer: D) -> Result<Id<T, ID>, D::Error>
+where
+    T: ?Sized + Label,
+    ID: FromStr,
+    <ID as FromStr>::Err: fmt::Display,
+    D: Deserializer<'de>,
+{
+    let rep = Cow::<'de, str>::deserialize(deserializer)?;
+    Id::parse_lenient(&rep).map_err(de::Error::custom)
+}
+
+impl<T: ?Sized, ID: fmt::Display> Id<T, ID> {
+    /// Serializes the id in its labeled `Label::id` form.
+    pub fn serialize_labeled<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<T, ID> Id<T, ID>
+where
+    T: ?Sized + Label,
+    ID: FromStr,
+    <ID as FromStr>::Err: fmt::Display,
+{
+    /// Deserializes an id from its labeled `Label::id` form, rejecting labels other than `T`'s
+    /// (or its aliases).
+    pub fn deserialize_checked<'de, D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let rep = Cow::<'de, str>::deserialize(deserializer)?;
+        Self::from_str(&rep).map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CustomLabeling, Id, Label};
+    use claim::*;
+    use pretty_assertions::assert_eq;
+    use serde::{Deserialize, Serialize};
+
+    struct User;
+
+    impl Label for User {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("User")
+        }
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Session {
+        #[serde(with = "crate::labeled")]
+        user_id: Id<User, u64>,
+    }
+
+    #[test]
+    fn test_labeled_serde_roundtrip() {
+        let session = Session {
+            user_id: Id::for_labeled(42),
+        };
+        let json = assert_ok!(serde_json::to_string(&session));
+        assert_eq!(json, r#"{"user_id":"User::42"}"#);
+        let actual: Session = assert_ok!(serde_json::from_str(&json));
+        assert_eq!(actual, session);
+    }
+
+    #[test]
+    fn test_labeled_serde_rejects_mismatch() {
+        let err = assert_err!(serde_json::from_str::<Session>(
+            r#"{"user_id":"Order::42"}"#
+        ));
+        assert!(err
+            .to_string()
+            .contains("id label `Order` does not match expected label `User`"));
+        assert_err!(serde_json::from_str::<Session>(r#"{"user_id":"42"}"#));
+        assert_err!(serde_json::from_str::<Session>(r#"{"user_id":42}"#));
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Query {
+        #[serde(deserialize_with = "crate::labeled::deserialize_lenient")]
+        user_id: Id<User, u64>,
+    }
+
+    #[test]
+    fn test_lenient_deserialize() {
+        let query: Query = assert_ok!(serde_json::from_str(r#"{"user_id":"User::42"}"#));
+        assert_eq!(query.user_id.id, 42);
+        let query: Query = assert_ok!(serde_json::from_str(r#"{"user_id":"42"}"#));
+        assert_eq!(query.user_id.id, 42);
+        assert_err!(serde_json::from_str::<Query>(r#"{"user_id":"Order::42"}"#));
+    }
+}
@@ -0,0 +1,222 @@
+//! Deserialization of ids from either their bare or labeled `Label::id` string forms, used via
+//! [`tagid::serde::id_lenient`](crate::serde::id_lenient).
+//!
+//! [`LabelStripping`] wraps the deserializer handed to the id's own `Deserialize` impl. Strings
+//! read through it lose a label prefix that matches `T` (or its aliases) before reaching the id,
+//! while a prefix naming another label fails. Non-string reps, such as numeric ids, pass through
+//! untouched.
+
+use crate::{accepts_label, Id, IdParseError, Label, Labeling};
+use serde::de::{self, Deserializer, Visitor};
+use serde::{Deserialize, Serialize, Serializer};
+use std::fmt;
+use std::marker::PhantomData;
+
+pub fn serialize<T, ID, S>(id: &Id<T, ID>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: ?Sized,
+    ID: Serialize,
+    S: Serializer,
+{
+    id.id.serialize(serializer)
+}
+
+pub fn deserialize<'de, T, ID, D>(deserializer: D) -> Result<Id<T, ID>, D::Error>
+where
+    T: ?Sized + Label,
+    ID: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    ID::deserialize(LabelStripping::<T, D>::new(deserializer)).map(Id::for_labeled)
+}
+
+pub struct LabelStripping<T: ?Sized, D> {
+    inner: D,
+    marker: PhantomData<T>,
+}
+
+impl<T: ?Sized, D> LabelStripping<T, D> {
+    pub const fn new(inner: D) -> Self {
+        Self {
+            inner,
+            marker: PhantomData,
+        }
+    }
+}
+
+/// Returns the id part of `rep`, or `None` if `rep` is already a bare id.
+fn strip_label<T: ?Sized + Label>(rep: &str) -> Result<Option<&str>, IdParseError> {
+    let labeler = <T as Label>::labeler();
+    if labeler.label().is_empty() {
+        return Ok(None);
+    }
+
+    match rep.split_once(T::DELIMITER) {
+        None => Ok(None),
+        Some((label, id)) if accepts_label::<T>(label) => Ok(Some(id)),
+        Some((label, _)) => Err(IdParseError::LabelMismatch {
+            expected: labeler.label().to_string(),
+            actual: label.to_string(),
+        }),
+    }
+}
+
+macro_rules! forward_deserialize {
+    ($($method:ident($($arg:ident: $ty:ty),*)),* $(,)?) => {
+        $(
+            fn $method<V>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, Self::Error>
+            where
+                V: Visitor<'de>,
+            {
+                self.inner.$method($($arg,)* StripLabel::<T, V>::new(visitor))
+            }
+        )*
+    };
+}
+
+impl<'de, T: ?Sized + Label, D: Deserializer<'de>> Deserializer<'de> for LabelStripping<T, D> {
+    type Error = D::Error;
+
+    forward_deserialize!(
+        deserialize_any(),
+        deserialize_bool(),
+        deserialize_i8(),
+        deserialize_i16(),
+        deserialize_i32(),
+        deserialize_i64(),
+        deserialize_i128(),
+        deserialize_u8(),
+        deserialize_u16(),
+        deserialize_u32(),
+        deserialize_u64(),
+        deserialize_u128(),
+        deserialize_f32(),
+        deserialize_f64(),
+        deserialize_char(),
+        deserialize_str(),
+        deserialize_string(),
+        deserialize_bytes(),
+        deserialize_byte_buf(),
+        deserialize_option(),
+        deserialize_unit(),
+        deserialize_unit_struct(name: &'static str),
+        deserialize_newtype_struct(name: &'static str),
+        deserialize_seq(),
+        deserialize_tuple(len: usize),
+        deserialize_tuple_struct(name: &'static str, len: usize),
+        deserialize_map(),
+        deserialize_struct(name: &'static str, fields: &'static [&'static str]),
+        deserialize_enum(name: &'static str, variants: &'static [&'static str]),
+        deserialize_identifier(),
+        deserialize_ignored_any(),
+    );
+
+    fn is_human_readable(&self) -> bool {
+        self.inner.is_human_readable()
+    }
+}
+
+struct StripLabel<T: ?Sized, V> {
+    visitor: V,
+    marker: PhantomData<T>,
+}
+
+impl<T: ?Sized, V> StripLabel<T, V> {
+    const fn new(visitor: V) -> Self {
+        Self {
+            visitor,
+            marker: PhantomData,
+        }
+    }
+}
+
+macro_rules! forward_visit {
+    ($($method:ident($ty:ty)),* $(,)?) => {
+        $(
+            fn $method<E: de::Error>(self, v: $ty) -> Result<Self::Value, E> {
+                self.visitor.$method(v)
+            }
+        )*
+    };
+}
+
+impl<'de, T: ?Sized + Label, V: Visitor<'de>> Visitor<'de> for StripLabel<T, V> {
+    type Value = V::Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.visitor.expecting(f)
+    }
+
+    forward_visit!(
+        visit_bool(bool),
+        visit_i8(i8),
+        visit_i16(i16),
+        visit_i32(i32),
+        visit_i64(i64),
+        visit_i128(i128),
+        visit_u8(u8),
+        visit_u16(u16),
+        visit_u32(u32),
+        visit_u64(u64),
+        visit_u128(u128),
+        visit_f32(f32),
+        visit_f64(f64),
+        visit_char(char),
+        visit_bytes(&[u8]),
+        visit_borrowed_bytes(&'de [u8]),
+        visit_byte_buf(Vec<u8>),
+    );
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        match strip_label::<T>(v).map_err(E::custom)? {
+            Some(id) => self.visitor.visit_str(id),
+            None => self.visitor.visit_str(v),
+        }
+    }
+
+    fn visit_borrowed_str<E: de::Error>(self, v: &'de str) -> Result<Self::Value, E> {
+        match strip_label::<T>(v).map_err(E::custom)? {
+            Some(id) => self.visitor.visit_borrowed_str(id),
+            None => self.visitor.visit_borrowed_str(v),
+        }
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
+        match strip_label::<T>(&v).map_err(E::custom)? {
+            Some(id) => self.visitor.visit_string(id.to_string()),
+            None => self.visitor.visit_string(v),
+        }
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+        self.visitor.visit_none()
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        self.visitor.visit_some(deserializer)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        self.visitor.visit_unit()
+    }
+
+    fn visit_newtype_struct<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> Result<Self::Value, D::Error> {
+        self.visitor
+            .visit_newtype_struct(LabelStripping::<T, D>::new(deserializer))
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, seq: A) -> Result<Self::Value, A::Error> {
+        self.visitor.visit_seq(seq)
+    }
+
+    fn visit_map<A: de::MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        self.visitor.visit_map(map)
+    }
+
+    fn visit_enum<A: de::EnumAccess<'de>>(self, data: A) -> Result<Self::Value, A::Error> {
+        self.visitor.visit_enum(data)
+    }
+}
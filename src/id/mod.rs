@@ -1,26 +1,123 @@
+#[cfg(feature = "actix-web")]
+mod actix;
+#[cfg(feature = "proptest")]
+mod arbitrary;
+mod async_gen;
+#[cfg(feature = "axum")]
+mod axum;
+#[cfg(feature = "borsh")]
+mod borsh;
+mod bytes;
+mod default_gen;
+#[cfg(feature = "diesel")]
+mod diesel;
+mod fallback;
 mod gen;
-pub use gen::IdGenerator;
+mod id_ref;
+#[cfg(feature = "ksuid")]
+mod ksuid;
+pub mod labeled;
+pub mod lenient;
+mod nil;
+#[cfg(feature = "objectid")]
+pub mod objectid;
+#[cfg(feature = "utoipa")]
+mod openapi;
+mod parse;
+#[cfg(feature = "postgres")]
+mod postgres;
+#[cfg(feature = "prost")]
+mod proto;
+mod raw;
+mod resource_name;
+#[cfg(feature = "schemars")]
+mod schema;
+mod scoped;
+#[cfg(feature = "zeroize")]
+mod sensitive;
+#[cfg(feature = "sqids")]
+mod sqids;
+mod timestamped;
+#[cfg(feature = "tracing")]
+pub mod tracing;
+#[cfg(feature = "typeid")]
+pub mod typeid;
+#[cfg(feature = "ulid")]
+mod ulid;
+mod urn;
+#[cfg(feature = "uuid")]
+mod uuid_format;
+#[cfg(feature = "actix-web")]
+pub use self::actix::TypedIdError;
+#[cfg(feature = "axum")]
+pub use self::axum::TypedIdRejection;
+pub use async_gen::{AsyncEntity, AsyncIdGenerator, Immediate};
+pub use bytes::{IdBytes, IdBytesError};
+pub use default_gen::DefaultIdGenerator;
+pub use fallback::{FallbackGenerator, FallbackId};
+pub use gen::{IdGenError, IdGenerator, TimeOrderedIdGenerator, TryIdGenerator};
+pub use id_ref::IdRef;
+pub use nil::NilId;
+pub use parse::IdParseError;
+pub use raw::{AsRawId, RawId, RawIdError};
+pub use resource_name::{
+    ResourceName, ResourceNameBuilder, ResourceNameError, RESOURCE_NAME_SCHEME,
+};
+pub use scoped::{ScopedId, SCOPE_SEPARATOR};
+pub use timestamped::TimestampedId;
+pub use urn::{UrnError, URN_SCHEME};
+
+#[cfg(feature = "prost")]
+pub use proto::{IdProtoError, ProtoBytes};
+
+#[cfg(feature = "zeroize")]
+pub use sensitive::SensitiveId;
+
+#[cfg(feature = "sqids")]
+pub use self::sqids::{ObfuscatedIdGenerator, Obfuscation, ObfuscationError, Obfuscator};
 
 #[cfg(feature = "cuid")]
-pub use gen::{CuidGenerator, CuidId};
+pub use gen::{CuidGenerator, CuidId, SharedCuidGenerator, SharedCuidId};
 
 #[cfg(feature = "uuid")]
-pub use gen::UuidGenerator;
+pub use gen::{UuidGenerator, UuidV7Generator};
+#[cfg(feature = "uuid")]
+pub use uuid_format::UuidFormat;
+
+#[cfg(feature = "nanoid")]
+pub use gen::{NanoIdAlphabet, NanoIdGenerator, UrlSafeAlphabet};
+
+#[cfg(feature = "ksuid")]
+pub use self::ksuid::{Ksuid, KsuidError, KsuidGenerator};
+
+#[cfg(feature = "objectid")]
+pub use self::objectid::ObjectIdGenerator;
+
+#[cfg(feature = "typeid")]
+pub use self::typeid::{TypeIdError, TypeIdGenerator};
+
+#[cfg(feature = "ulid")]
+pub use self::ulid::{Ulid, UlidError, UlidGenerator};
+
+#[cfg(all(feature = "ulid", feature = "sqlx"))]
+pub use self::ulid::UlidStorage;
 
 #[cfg(feature = "snowflake")]
 pub mod snowflake;
 
 #[cfg(feature = "snowflake")]
-pub use self::snowflake::{pretty, MachineNode, SnowflakeGenerator};
+pub use self::snowflake::{MachineNode, SnowflakeGenerator};
 
-use crate::{Label, Labeling, DELIMITER};
+use crate::{Label, Labeling};
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use smol_str::SmolStr;
+use std::borrow::Borrow;
 use std::cmp::Ordering;
 use std::fmt;
 use std::hash::{Hash, Hasher};
 use std::marker::PhantomData;
+use std::time::SystemTime;
 
 pub trait Entity: Label {
     type IdGen: IdGenerator;
@@ -28,11 +125,80 @@ pub trait Entity: Label {
     fn next_id() -> Id<Self, <Self::IdGen as IdGenerator>::IdType> {
         Id::new()
     }
+
+    /// Generates the next id, surfacing failures of generators that also implement
+    /// [`TryIdGenerator`] rather than panicking.
+    fn try_next_id() -> Result<Id<Self, <Self::IdGen as IdGenerator>::IdType>, IdGenError>
+    where
+        Self::IdGen: TryIdGenerator,
+    {
+        Id::try_new()
+    }
+
+    /// Generates an id dated at `timestamp` rather than now; e.g., when backfilling records, so
+    /// their ids keep the time order of the original events.
+    fn next_id_at(timestamp: SystemTime) -> Id<Self, <Self::IdGen as IdGenerator>::IdType>
+    where
+        Self::IdGen: TimeOrderedIdGenerator,
+    {
+        Id::new_at(timestamp)
+    }
+}
+
+/// Declares an entity marker struct along with its [`Label`], optionally a custom label, and
+/// [`Entity`] impls.
+///
+#[cfg_attr(all(feature = "derive", feature = "cuid"), doc = "```rust")]
+#[cfg_attr(not(all(feature = "derive", feature = "cuid")), doc = "```ignore")]
+/// use tagid::{define_entity, CuidGenerator, Entity, Label, Labeling};
+///
+/// define_entity!(pub Order, label = "ord", id_gen = CuidGenerator);
+/// define_entity!(
+///     /// A line of an order.
+///     #[derive(Debug)]
+///     pub(crate) OrderLine,
+///     id_gen = tagid::CuidGenerator
+/// );
+///
+/// assert_eq!(Order::next_id().label, "ord");
+/// assert_eq!(<OrderLine as Label>::labeler().label(), "OrderLine");
+/// ```
+#[macro_export]
+macro_rules! define_entity {
+    ($(#[$meta:meta])* $vis:vis $name:ident, label = $label:literal, id_gen = $id_gen:ty $(,)?) => {
+        $(#[$meta])*
+        $vis struct $name;
+
+        $crate::define_entity!(@entity $name, $label, $id_gen);
+    };
+    ($(#[$meta:meta])* $vis:vis $name:ident, id_gen = $id_gen:ty $(,)?) => {
+        $(#[$meta])*
+        $vis struct $name;
+
+        $crate::define_entity!(@entity $name, stringify!($name), $id_gen);
+    };
+    (@entity $name:ident, $label:expr, $id_gen:ty) => {
+        impl $crate::Label for $name {
+            type Labeler = $crate::StaticLabeling;
+            const LABEL: Option<&'static str> = Some($label);
+
+            fn labeler() -> Self::Labeler {
+                $crate::StaticLabeling::new($label)
+            }
+        }
+
+        impl $crate::Entity for $name {
+            type IdGen = $id_gen;
+        }
+
+        $crate::__register_label!($name);
+    };
 }
 
 pub struct Id<T: ?Sized, ID> {
     pub label: SmolStr,
     pub id: ID,
+    delimiter: &'static str,
     marker: PhantomData<T>,
 }
 
@@ -49,13 +215,41 @@ where
     pub fn new() -> Self {
         let labeler = <E as Label>::labeler();
         Self {
-            label: SmolStr::new(labeler.label()),
+            label: labeler.label_smol(),
             id: E::IdGen::next_id_rep(),
+            delimiter: E::DELIMITER,
             marker: PhantomData,
         }
     }
 }
 
+impl<E> Id<E, <<E as Entity>::IdGen as IdGenerator>::IdType>
+where
+    E: ?Sized + Entity + Label,
+    E::IdGen: TryIdGenerator,
+{
+    pub fn try_new() -> Result<Self, IdGenError> {
+        let labeler = <E as Label>::labeler();
+        Ok(Self {
+            label: labeler.label_smol(),
+            id: <E::IdGen as TryIdGenerator>::try_next_id_rep()?,
+            delimiter: E::DELIMITER,
+            marker: PhantomData,
+        })
+    }
+}
+
+impl<E> Id<E, <<E as Entity>::IdGen as IdGenerator>::IdType>
+where
+    E: ?Sized + Entity + Label,
+    E::IdGen: TimeOrderedIdGenerator,
+{
+    /// Generates an id dated at `timestamp` rather than now.
+    pub fn new_at(timestamp: SystemTime) -> Self {
+        Self::for_labeled(E::IdGen::next_id_rep_at(timestamp))
+    }
+}
+
 impl<E: ?Sized + Entity + Label> Default for Id<E, <<E as Entity>::IdGen as IdGenerator>::IdType> {
     fn default() -> Self {
         Self::new()
@@ -66,29 +260,58 @@ impl<T: ?Sized + Label, ID> Id<T, ID> {
     pub fn for_labeled(id: ID) -> Self {
         let labeler = <T as Label>::labeler();
         Self {
-            label: SmolStr::new(labeler.label()),
+            label: labeler.label_smol(),
             id,
+            delimiter: T::DELIMITER,
             marker: PhantomData,
         }
     }
+
+    /// Creates an id with the given label after checking it against `T`'s [`Label::DELIMITER`]
+    /// via [`validate_label()`](crate::validate_label), so the id's `Display` form parses back.
+    pub fn try_direct(label: impl AsRef<str>, id: ID) -> Result<Self, crate::LabelError> {
+        let label = label.as_ref();
+        crate::validate_label(label, T::DELIMITER)?;
+        Ok(Self::direct_labeled(label, id))
+    }
+
+    /// Creates an id with the given label, rendered with `T`'s [`Label::DELIMITER`].
+    pub(crate) fn direct_labeled(label: impl AsRef<str>, id: ID) -> Self {
+        Self::direct(label, id).with_delimiter(T::DELIMITER)
+    }
 }
 
 impl<T: ?Sized, ID> Id<T, ID> {
+    /// Creates an id with the given label, which is not checked; see [`Id::try_direct()`].
+    ///
+    /// Without `T: Label` in scope, the id renders with the default [`DELIMITER`](crate::DELIMITER)
+    /// rather than `T`'s [`Label::DELIMITER`].
     pub fn direct(label: impl AsRef<str>, id: ID) -> Self {
         Self {
             label: SmolStr::new(label.as_ref()),
             id,
+            delimiter: crate::DELIMITER,
             marker: PhantomData,
         }
     }
+
+    /// Separates the label from the id in the id's `Display` and `Debug` forms.
+    pub const fn delimiter(&self) -> &'static str {
+        self.delimiter
+    }
+
+    const fn with_delimiter(mut self, delimiter: &'static str) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
 }
 
 impl<T: ?Sized, ID: Clone> Id<T, ID> {
     pub fn relabel<B: Label>(&self) -> Id<B, ID> {
-        let b_labeler = B::labeler();
         Id {
-            label: SmolStr::new(b_labeler.label()),
+            label: B::labeler().label_smol(),
             id: self.id.clone(),
+            delimiter: B::DELIMITER,
             marker: PhantomData,
         }
     }
@@ -99,6 +322,7 @@ impl<T: ?Sized, ID: Clone> Clone for Id<T, ID> {
         Self {
             label: self.label.clone(),
             id: self.id.clone(),
+            delimiter: self.delimiter,
             marker: PhantomData,
         }
     }
@@ -114,17 +338,51 @@ impl<T: ?Sized, ID: fmt::Debug> fmt::Debug for Id<T, ID> {
         } else if self.label.is_empty() {
             write!(f, "{:?}", self.id)
         } else {
-            write!(f, "{}{DELIMITER}{:?}", self.label, self.id)
+            write!(f, "{}{}{:?}", self.label, self.delimiter, self.id)
+        }
+    }
+}
+
+impl<T: ?Sized, ID: fmt::Display> Id<T, ID> {
+    /// Writes the id as [`Display`](fmt::Display) does, piece by piece into `out`; e.g., to reuse
+    /// one buffer across many ids in hot logging paths.
+    ///
+    #[cfg_attr(feature = "derive", doc = "```rust")]
+    #[cfg_attr(not(feature = "derive"), doc = "```ignore")]
+    /// use tagid::{Id, Label};
+    ///
+    /// #[derive(Label)]
+    /// struct Order;
+    ///
+    /// let mut line = String::with_capacity(64);
+    /// for rep in 1..=2_u64 {
+    ///     line.clear();
+    ///     Id::<Order, _>::for_labeled(rep).write_to(&mut line).unwrap();
+    ///     assert_eq!(line, format!("Order::{rep}"));
+    /// }
+    /// ```
+    pub fn write_to(&self, out: &mut impl fmt::Write) -> fmt::Result {
+        if !self.label.is_empty() {
+            out.write_str(&self.label)?;
+            out.write_str(self.delimiter)?;
         }
+        write!(out, "{}", self.id)
+    }
+}
+
+impl<T: ?Sized, ID: fmt::Display> Id<T, ID> {
+    /// Displays the id without its label, as the alternate `{:#}` form does.
+    pub fn display_compact(&self) -> impl fmt::Display + '_ {
+        &self.id
     }
 }
 
 impl<T: ?Sized, ID: fmt::Display> fmt::Display for Id<T, ID> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        if f.alternate() || self.label.is_empty() {
+        if f.alternate() {
             write!(f, "{}", self.id)
         } else {
-            write!(f, "{}{DELIMITER}{}", self.label, self.id)
+            self.write_to(f)
         }
     }
 }
@@ -149,12 +407,40 @@ impl<T: ?Sized, ID: PartialOrd> PartialOrd for Id<T, ID> {
     }
 }
 
+/// Compares against a raw id, e.g., `id == uuid`, as equality ignores the label.
+impl<T: ?Sized, ID: PartialEq> PartialEq<ID> for Id<T, ID> {
+    fn eq(&self, other: &ID) -> bool {
+        self.id == *other
+    }
+}
+
 impl<T: ?Sized, ID: Hash> Hash for Id<T, ID> {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.id.hash(state)
     }
 }
 
+/// Ids compare and hash by their id alone, so collections keyed by `Id` can be queried by the raw
+/// id without rebuilding the labeled `Id`; e.g., `users.get(&uuid)` on a
+/// `HashMap<Id<User, Uuid>, _>`.
+impl<T: ?Sized, ID> Borrow<ID> for Id<T, ID> {
+    fn borrow(&self) -> &ID {
+        &self.id
+    }
+}
+
+impl<T: ?Sized> Borrow<str> for Id<T, String> {
+    fn borrow(&self) -> &str {
+        self.id.as_str()
+    }
+}
+
+impl<T: ?Sized> Borrow<str> for Id<T, SmolStr> {
+    fn borrow(&self) -> &str {
+        self.id.as_str()
+    }
+}
+
 impl<T: ?Sized, ID: Serialize> Serialize for Id<T, ID> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -164,14 +450,15 @@ impl<T: ?Sized, ID: Serialize> Serialize for Id<T, ID> {
     }
 }
 
+/// Deserializes the bare id, labeling it for `T`. Fields that must also accept the labeled
+/// `Label::id` form opt in via [`tagid::serde::id_lenient`](crate::serde::id_lenient).
 impl<'de, T: ?Sized + Label, ID: DeserializeOwned> Deserialize<'de> for Id<T, ID> {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
     {
         let rep = ID::deserialize(deserializer)?;
-        let labeler = <T as Label>::labeler();
-        Ok(Self::direct(labeler.label(), rep))
+        Ok(Self::for_labeled(rep))
     }
 }
 
@@ -215,6 +502,76 @@ where
     }
 }
 
+/// Implements sqlx passthrough to the wrapped id for `#[derive(TypedId)]` newtypes.
+#[cfg(feature = "sqlx")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __typed_id_sqlx {
+    ($wrapper:ty, $id:ty) => {
+        impl<'q, DB> $crate::__private::sqlx::Encode<'q, DB> for $wrapper
+        where
+            $id: $crate::__private::sqlx::Encode<'q, DB>,
+            DB: $crate::__private::sqlx::Database,
+        {
+            fn encode_by_ref(
+                &self,
+                buf: &mut <DB as $crate::__private::sqlx::database::HasArguments<'q>>::ArgumentBuffer,
+            ) -> $crate::__private::sqlx::encode::IsNull {
+                <$id as $crate::__private::sqlx::Encode<DB>>::encode_by_ref(
+                    ::core::ops::Deref::deref(self),
+                    buf,
+                )
+            }
+        }
+
+        impl<'r, DB> $crate::__private::sqlx::Decode<'r, DB> for $wrapper
+        where
+            $id: $crate::__private::sqlx::Decode<'r, DB>,
+            DB: $crate::__private::sqlx::Database,
+        {
+            fn decode(
+                value: <DB as $crate::__private::sqlx::database::HasValueRef<'r>>::ValueRef,
+            ) -> ::core::result::Result<Self, $crate::__private::sqlx::error::BoxDynError> {
+                <$id as $crate::__private::sqlx::Decode<DB>>::decode(value).map(Self::from)
+            }
+        }
+
+        impl<DB> $crate::__private::sqlx::Type<DB> for $wrapper
+        where
+            $id: $crate::__private::sqlx::Type<DB>,
+            DB: $crate::__private::sqlx::Database,
+        {
+            fn type_info() -> DB::TypeInfo {
+                <$id as $crate::__private::sqlx::Type<DB>>::type_info()
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "sqlx"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __typed_id_sqlx {
+    ($wrapper:ty, $id:ty) => {};
+}
+
+#[cfg(feature = "defmt")]
+impl<T: ?Sized, ID: defmt::Format> defmt::Format for Id<T, ID> {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        if self.label.is_empty() {
+            defmt::write!(f, "{}", self.id)
+        } else {
+            defmt::write!(
+                f,
+                "{=str}{=str}{}",
+                self.label.as_str(),
+                self.delimiter,
+                self.id
+            )
+        }
+    }
+}
+
 #[cfg(feature = "disintegrate")]
 use disintegrate::{IdentifierType, IdentifierValue, IntoIdentifierValue};
 
@@ -230,7 +587,7 @@ impl<T, ID: fmt::Display> IntoIdentifierValue for Id<T, ID> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{CustomLabeling, MakeLabeling, NoLabeling};
+    use crate::{CustomLabeling, Labeling, MakeLabeling, NoLabeling};
     use claim::*;
     use pretty_assertions::assert_eq;
     use serde_test::{assert_tokens, Token};
@@ -288,12 +645,47 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_raw_id_lookup() {
+        use std::collections::{BTreeSet, HashMap};
+
+        let id: Id<Bar, u64> = Id::for_labeled(17);
+        let mut counts = HashMap::new();
+        counts.insert(id.clone(), 3);
+        assert_eq!(counts.get(&17), Some(&3));
+        assert_eq!(counts.get(&18), None);
+        assert_eq!(Borrow::<u64>::borrow(&id), &17);
+        assert!(id == 17);
+        assert!(id != 18);
+
+        let ids: BTreeSet<Id<Bar, String>> = ["a", "b"]
+            .into_iter()
+            .map(|rep| Id::for_labeled(rep.to_string()))
+            .collect();
+        assert!(ids.contains("a"));
+        assert!(!ids.contains("c"));
+    }
+
     #[test]
     fn test_display() {
         let a: Id<Foo, String> = Foo::next_id();
         assert_eq!(format!("{a}"), format!("MyFooferNut::{}", a.id));
     }
 
+    #[test]
+    fn test_write_to() {
+        let a: Id<Foo, u64> = Id::for_labeled(13);
+        let mut buffer = String::new();
+        assert_ok!(a.write_to(&mut buffer));
+        assert_eq!(buffer, a.to_string());
+        assert_eq!(a.display_compact().to_string(), format!("{a:#}"));
+
+        let z: Id<NoLabelZed, u64> = Id::for_labeled(17);
+        buffer.clear();
+        assert_ok!(z.write_to(&mut buffer));
+        assert_eq!(buffer, "17");
+    }
+
     #[test]
     fn test_alternate_display() {
         let a: Id<Foo, i64> = Id::direct(Foo::labeler().label(), 13);
@@ -311,6 +703,19 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_try_direct_rejects_unparseable_label() {
+        let id: Id<Foo, u64> = assert_ok!(Id::try_direct("billing.Invoice", 13));
+        assert_eq!(id.label, "billing.Invoice");
+        assert_matches!(
+            Id::<Foo, u64>::try_direct("billing::Invoice", 13),
+            Err(crate::LabelError::ContainsDelimiter { .. })
+        );
+
+        let id: Id<Foo, u64> = Id::direct("billing::Invoice", 13);
+        assert_eq!(id.label, "billing::Invoice");
+    }
+
     #[test]
     fn test_debug() {
         let a: Id<Foo, String> = Foo::next_id();
@@ -377,10 +782,19 @@ mod tests {
         let labeler = <Foo as Label>::labeler();
         let cuid = "ig6wv6nezj0jg51lg53dztqy".to_string();
         let id = Id::<Foo, String>::direct(labeler.label(), cuid);
-        assert_tokens(&id, &vec![Token::Str("ig6wv6nezj0jg51lg53dztqy")]);
+        assert_tokens(&id, &[Token::Str("ig6wv6nezj0jg51lg53dztqy")]);
 
         let id = Id::<Foo, u64>::direct(labeler.label(), 17);
-        assert_tokens(&id, &vec![Token::U64(17)]);
+        assert_tokens(&id, &[Token::U64(17)]);
+
+        #[cfg(feature = "uuid")]
+        {
+            use serde_test::Configure;
+
+            const BYTES: [u8; 16] = [7; 16];
+            let id = Id::<Foo, uuid::Uuid>::direct(labeler.label(), uuid::Uuid::from_bytes(BYTES));
+            assert_tokens(&id.compact(), &[Token::Bytes(&BYTES)]);
+        }
     }
 
     #[test]
@@ -401,10 +815,104 @@ mod tests {
         #[cfg(feature = "uuid")]
         {
             let uuid = uuid::Uuid::new_v4();
-            let id = Id::<Foo, uuid::Uuid>::direct(labeler.label(), uuid.clone());
+            let id = Id::<Foo, uuid::Uuid>::direct(labeler.label(), uuid);
             let json = assert_ok!(serde_json::to_string(&id));
             let actual: Id<Foo, uuid::Uuid> = assert_ok!(serde_json::from_str(&json));
             assert_eq!(actual, id);
         }
     }
+
+    fn from_lenient_json<T: Label, ID: DeserializeOwned>(
+        json: &str,
+    ) -> Result<Id<T, ID>, serde_json::Error> {
+        crate::serde::id_lenient::deserialize(&mut serde_json::Deserializer::from_str(json))
+    }
+
+    #[test]
+    fn test_id_deserialize_labeled_or_bare() {
+        let actual: Id<Foo, String> = assert_ok!(serde_json::from_str(r#""MyFooferNut::abc123""#));
+        assert_eq!(actual.id, "MyFooferNut::abc123");
+
+        let actual: Id<Foo, String> = assert_ok!(from_lenient_json(r#""abc123""#));
+        assert_eq!(actual.id, "abc123");
+        let actual: Id<Foo, String> = assert_ok!(from_lenient_json(r#""MyFooferNut::abc123""#));
+        assert_eq!(actual.id, "abc123");
+        assert_eq!(actual.label, "MyFooferNut");
+        assert_err!(from_lenient_json::<Foo, String>(r#""Bar::abc123""#));
+
+        let actual: Id<Foo, u64> = assert_ok!(from_lenient_json("17"));
+        assert_eq!(actual.id, 17);
+        let actual: Id<NoLabelZed, String> = assert_ok!(from_lenient_json(r#""a::b""#));
+        assert_eq!(actual.id, "a::b");
+
+        #[cfg(feature = "uuid")]
+        {
+            let uuid = uuid::Uuid::new_v4();
+            let actual: Id<Foo, uuid::Uuid> =
+                assert_ok!(from_lenient_json(&format!(r#""MyFooferNut::{uuid}""#)));
+            assert_eq!(actual.id, uuid);
+            let actual: Id<Foo, uuid::Uuid> =
+                assert_ok!(from_lenient_json(&format!(r#""{uuid}""#)));
+            assert_eq!(actual.id, uuid);
+        }
+    }
+
+    /// Issues ids until its three-id sequence space is exhausted.
+    struct BoundedGenerator;
+
+    static BOUNDED_NEXT: std::sync::atomic::AtomicU8 = std::sync::atomic::AtomicU8::new(0);
+
+    impl TryIdGenerator for BoundedGenerator {
+        fn try_next_id_rep() -> Result<Self::IdType, IdGenError> {
+            let next = BOUNDED_NEXT.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            if next < 3 {
+                Ok(next)
+            } else {
+                Err(IdGenError::Exhausted("BoundedGenerator".to_string()))
+            }
+        }
+    }
+
+    impl IdGenerator for BoundedGenerator {
+        type IdType = u8;
+
+        fn next_id_rep() -> Self::IdType {
+            Self::try_next_id_rep().expect("failed to generate bounded id")
+        }
+    }
+
+    struct Ticket;
+
+    impl Label for Ticket {
+        type Labeler = MakeLabeling<Self>;
+
+        fn labeler() -> Self::Labeler {
+            MakeLabeling::default()
+        }
+    }
+
+    impl Entity for Ticket {
+        type IdGen = BoundedGenerator;
+    }
+
+    #[test]
+    fn test_try_next_id() {
+        assert_eq!(assert_ok!(Ticket::try_next_id()).id, 0);
+        assert_eq!(Ticket::next_id().id, 1);
+        assert_eq!(
+            assert_ok!(Id::<Ticket, u8>::try_new()).to_string(),
+            "Ticket::2"
+        );
+        assert_matches!(Ticket::try_next_id(), Err(IdGenError::Exhausted(_)));
+    }
+
+    #[test]
+    fn test_define_entity() {
+        define_entity!(Widget, label = "wdg", id_gen = TestGenerator);
+        define_entity!(Gadget, id_gen = TestGenerator,);
+
+        assert_eq!(Widget::next_id().label, "wdg");
+        assert_eq!(Gadget::next_id().label, "Gadget");
+        assert_eq!(Widget::LABEL, Some("wdg"));
+    }
 }
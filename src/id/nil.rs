@@ -0,0 +1,190 @@
+//! Nil ids, representing an unassigned id without wrapping every id in an `Option`, and
+//! well-known ids declared via [`well_known_id!`](crate::well_known_id).
+//!
+#![cfg_attr(feature = "derive", doc = "```rust")]
+#![cfg_attr(not(feature = "derive"), doc = "```ignore")]
+//! use tagid::{well_known_id, Id, Label};
+//!
+//! #[derive(Label)]
+//! struct User;
+//!
+//! well_known_id! {
+//!     /// The user recorded as the actor of system initiated changes.
+//!     pub static SYSTEM_USER_ID: Id<User, String> = "system";
+//! }
+//!
+//! assert_eq!(SYSTEM_USER_ID.to_string(), "User::system");
+//! assert!(!SYSTEM_USER_ID.is_nil());
+//! assert!(Id::<User, String>::nil().is_nil());
+//! ```
+
+use crate::{Id, Label};
+use smol_str::SmolStr;
+
+/// An id representation with a nil value, such as the nil UUID, zero or the empty string, that
+/// generators never produce.
+pub trait NilId {
+    fn nil() -> Self;
+
+    fn is_nil(&self) -> bool;
+}
+
+impl<T: ?Sized + Label, ID: NilId> Id<T, ID> {
+    /// The nil id of `T`, standing in for an id not yet assigned.
+    pub fn nil() -> Self {
+        Self::for_labeled(ID::nil())
+    }
+}
+
+impl<T: ?Sized, ID: NilId> Id<T, ID> {
+    pub fn is_nil(&self) -> bool {
+        self.id.is_nil()
+    }
+}
+
+impl NilId for String {
+    fn nil() -> Self {
+        Self::new()
+    }
+
+    fn is_nil(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+impl NilId for SmolStr {
+    fn nil() -> Self {
+        Self::default()
+    }
+
+    fn is_nil(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+macro_rules! integer_nil_id {
+    ($i:ty) => {
+        impl NilId for $i {
+            fn nil() -> Self {
+                0
+            }
+
+            fn is_nil(&self) -> bool {
+                *self == 0
+            }
+        }
+    };
+}
+
+integer_nil_id!(i32);
+integer_nil_id!(i64);
+integer_nil_id!(u32);
+integer_nil_id!(u64);
+integer_nil_id!(u128);
+
+#[cfg(feature = "uuid")]
+impl NilId for ::uuid::Uuid {
+    fn nil() -> Self {
+        Self::nil()
+    }
+
+    fn is_nil(&self) -> bool {
+        Self::is_nil(self)
+    }
+}
+
+#[cfg(feature = "ulid")]
+impl NilId for crate::Ulid {
+    fn nil() -> Self {
+        Self::nil()
+    }
+
+    fn is_nil(&self) -> bool {
+        *self == Self::nil()
+    }
+}
+
+/// Declares lazily initialized static ids, such as the ids of system users or default tenants,
+/// from their id representations.
+///
+#[cfg_attr(feature = "derive", doc = "```rust")]
+#[cfg_attr(not(feature = "derive"), doc = "```ignore")]
+/// use tagid::{well_known_id, Id, Label};
+///
+/// #[derive(Label)]
+/// struct Tenant;
+///
+/// well_known_id! {
+///     pub static DEFAULT_TENANT_ID: Id<Tenant, u64> = 1_u64;
+///     static UNASSIGNED_TENANT_ID: Id<Tenant, u64> = 0_u64;
+/// }
+///
+/// assert_eq!(DEFAULT_TENANT_ID.id, 1);
+/// assert!(UNASSIGNED_TENANT_ID.is_nil());
+/// ```
+#[macro_export]
+macro_rules! well_known_id {
+    ($($(#[$attr:meta])* $vis:vis static $name:ident: Id<$label:ty, $id:ty> = $rep:expr;)+) => {
+        $(
+            $(#[$attr])*
+            $vis static $name: $crate::__private::Lazy<$crate::Id<$label, $id>> =
+                $crate::__private::Lazy::new(|| {
+                    $crate::Id::for_labeled(::core::convert::Into::<$id>::into($rep))
+                });
+        )+
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CustomLabeling;
+    use pretty_assertions::assert_eq;
+
+    struct User;
+
+    impl Label for User {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("User")
+        }
+    }
+
+    crate::well_known_id! {
+        static SYSTEM_USER_ID: Id<User, String> = "system";
+        static ANONYMOUS_USER_ID: Id<User, u64> = 0_u64;
+    }
+
+    #[test]
+    fn test_nil_ids() {
+        let nil: Id<User, String> = Id::nil();
+        assert_eq!(nil.label, "User");
+        assert!(nil.is_nil());
+        assert!(!Id::<User, String>::for_labeled("a".to_string()).is_nil());
+
+        assert_eq!(Id::<User, u64>::nil().id, 0);
+        assert!(!Id::<User, i64>::for_labeled(-1).is_nil());
+        assert!(Id::<User, SmolStr>::nil().is_nil());
+
+        #[cfg(feature = "uuid")]
+        {
+            assert_eq!(Id::<User, ::uuid::Uuid>::nil().id, ::uuid::Uuid::nil());
+            assert!(!Id::<User, ::uuid::Uuid>::for_labeled(::uuid::Uuid::new_v4()).is_nil());
+        }
+
+        #[cfg(feature = "ulid")]
+        {
+            assert_eq!(Id::<User, crate::Ulid>::nil().id.to_u128(), 0);
+            assert!(!Id::<User, crate::Ulid>::for_labeled(crate::Ulid::new()).is_nil());
+        }
+    }
+
+    #[test]
+    fn test_well_known_ids() {
+        assert_eq!(SYSTEM_USER_ID.to_string(), "User::system");
+        assert!(!SYSTEM_USER_ID.is_nil());
+        assert_eq!(ANONYMOUS_USER_ID.label, "User");
+        assert!(ANONYMOUS_USER_ID.is_nil());
+    }
+}
@@ -0,0 +1,163 @@
+//! MongoDB ObjectId support.
+//!
+//! `Id<T, ObjectId>` serializes as its bare `ObjectId`, so it round-trips through BSON documents
+//! as a native ObjectId (e.g., as a document's `_id`). For JSON APIs, which would otherwise render
+//! the extended JSON `{"$oid": "..."}` form, the [`hex`] helpers use the 24 character hex string.
+
+use crate::id::{AsRawId, IdGenerator, RawId, RawIdError, TryIdGenerator};
+use crate::Id;
+use bson::oid::ObjectId;
+use bson::Bson;
+use std::borrow::Cow;
+
+/// Generates MongoDB ObjectIds, which embed their creation time in their first four bytes.
+pub struct ObjectIdGenerator;
+
+impl IdGenerator for ObjectIdGenerator {
+    type IdType = ObjectId;
+
+    fn next_id_rep() -> Self::IdType {
+        ObjectId::new()
+    }
+}
+
+impl TryIdGenerator for ObjectIdGenerator {}
+
+impl AsRawId for ObjectId {
+    fn as_str(&self) -> Cow<'_, str> {
+        Cow::Owned(self.to_hex())
+    }
+
+    fn as_bytes(&self) -> Option<Cow<'_, [u8]>> {
+        Some(Cow::Owned(self.bytes().to_vec()))
+    }
+}
+
+/// ObjectIds are carried in their hex string form, as `RawId`'s binary form is 16 bytes.
+impl From<ObjectId> for RawId {
+    fn from(rep: ObjectId) -> Self {
+        Self::Str(rep.to_hex().into())
+    }
+}
+
+impl TryFrom<RawId> for ObjectId {
+    type Error = RawIdError;
+
+    fn try_from(raw: RawId) -> Result<Self, Self::Error> {
+        match &raw {
+            RawId::Str(rep) => Self::parse_str(rep).map_err(|_| RawIdError::new("ObjectId", raw)),
+            _ => Err(RawIdError::new("ObjectId", raw)),
+        }
+    }
+}
+
+impl<T: ?Sized> From<Id<T, Self>> for ObjectId {
+    fn from(id: Id<T, Self>) -> Self {
+        id.id
+    }
+}
+
+/// Embeds the id in BSON documents and queries as a native ObjectId; e.g.,
+/// `doc! { "_id": order_id }`.
+impl<T: ?Sized> From<Id<T, ObjectId>> for Bson {
+    fn from(id: Id<T, ObjectId>) -> Self {
+        Self::ObjectId(id.id)
+    }
+}
+
+/// Serde helpers representing `Id<T, ObjectId>` as its hex string, for use via
+/// `#[serde(with = "tagid::objectid::hex")]`.
+pub mod hex {
+    use crate::{Id, Label};
+    use bson::oid::ObjectId;
+    use serde::{de, Deserialize, Deserializer, Serializer};
+    use std::borrow::Cow;
+
+    pub fn serialize<T, S>(id: &Id<T, ObjectId>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: ?Sized,
+        S: Serializer,
+    {
+        serializer.serialize_str(&id.id.to_hex())
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Id<T, ObjectId>, D::Error>
+    where
+        T: ?Sized + Label,
+        D: Deserializer<'de>,
+    {
+        let rep = Cow::<'de, str>::deserialize(deserializer)?;
+        let id = ObjectId::parse_str(rep.as_ref()).map_err(de::Error::custom)?;
+        Ok(Id::for_labeled(id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CustomLabeling, Entity, Label};
+    use bson::doc;
+    use claim::*;
+    use pretty_assertions::assert_eq;
+    use serde::{Deserialize, Serialize};
+
+    struct Order;
+
+    impl Label for Order {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("Order")
+        }
+    }
+
+    impl Entity for Order {
+        type IdGen = ObjectIdGenerator;
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct OrderDocument {
+        #[serde(rename = "_id")]
+        id: Id<Order, ObjectId>,
+        total: i64,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct OrderResponse {
+        #[serde(with = "crate::objectid::hex")]
+        id: Id<Order, ObjectId>,
+    }
+
+    #[test]
+    fn test_bson_roundtrip_as_native_object_id() {
+        let order = OrderDocument {
+            id: Order::next_id(),
+            total: 17,
+        };
+        let document = assert_ok!(bson::to_document(&order));
+        assert_eq!(document.get("_id"), Some(&Bson::ObjectId(order.id.id)));
+        assert_eq!(doc! { "_id": &order.id }, doc! { "_id": order.id.id });
+
+        let actual: OrderDocument = assert_ok!(bson::from_document(document));
+        assert_eq!(actual, order);
+        assert_eq!(actual.id.label, "Order");
+    }
+
+    #[test]
+    fn test_hex_serde_and_raw_id() {
+        let id: Id<Order, ObjectId> =
+            Id::for_labeled(ObjectId::parse_str("65f1c0ffee0123456789abcd").unwrap());
+        let response = OrderResponse { id: id.clone() };
+        let json = assert_ok!(serde_json::to_string(&response));
+        assert_eq!(json, r#"{"id":"65f1c0ffee0123456789abcd"}"#);
+        assert_eq!(
+            assert_ok!(serde_json::from_str::<OrderResponse>(&json)),
+            response
+        );
+
+        let raw = RawId::from(id.id);
+        assert_eq!(raw, RawId::Str("65f1c0ffee0123456789abcd".into()));
+        assert_eq!(ObjectId::try_from(raw), Ok(id.id));
+        assert_eq!(id.as_bytes().map(|bytes| bytes.len()), Some(12));
+    }
+}
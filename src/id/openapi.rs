@@ -0,0 +1,146 @@
+//! [`utoipa`] support, so typed ids can appear directly in axum handler signatures and request
+//! and response types, with the generated OpenAPI reflecting the underlying id format.
+
+use crate::{Id, Label, Labeling};
+use std::borrow::Cow;
+use utoipa::openapi::path::{Parameter, ParameterBuilder, ParameterIn};
+use utoipa::openapi::schema::Schema;
+#[cfg(any(feature = "snowflake", feature = "ksuid", feature = "ulid"))]
+use utoipa::openapi::schema::{ObjectBuilder, SchemaFormat, Type};
+use utoipa::openapi::{RefOr, Required};
+use utoipa::{IntoParams, PartialSchema, ToSchema};
+
+/// The schema of the bare `ID`, as `Id` serializes, with the label recorded as the title and
+/// description. utoipa does not implement `PartialSchema` for external id types such as `Uuid`;
+/// annotate those fields with `#[schema(value_type = String, format = Uuid)]` instead.
+impl<T: ?Sized + Label, ID: PartialSchema> PartialSchema for Id<T, ID> {
+    fn schema() -> RefOr<Schema> {
+        let mut schema = ID::schema();
+        let labeler = <T as Label>::labeler();
+        let label = labeler.label();
+        if let RefOr::T(Schema::Object(object)) = &mut schema {
+            if !label.is_empty() {
+                object.title = Some(format!("{label} id"));
+                object.description = Some(format!("Identifies a `{label}`."));
+            }
+        }
+        schema
+    }
+}
+
+/// Ids are named for their entity (e.g., `OrderId`), so each entity's id is a distinct component.
+impl<T: ?Sized + Label, ID: PartialSchema> ToSchema for Id<T, ID> {
+    fn name() -> Cow<'static, str> {
+        let labeler = <T as Label>::labeler();
+        Cow::Owned(format!("{}Id", labeler.label()))
+    }
+}
+
+/// Describes an id extracted as a single parameter named `id`, in the path unless the handler
+/// specifies otherwise; e.g., from `Path<Id<Order, u64>>` for a `/orders/{id}` route.
+impl<T: ?Sized + Label, ID: PartialSchema> IntoParams for Id<T, ID> {
+    fn into_params(parameter_in_provider: impl Fn() -> Option<ParameterIn>) -> Vec<Parameter> {
+        let parameter_in = parameter_in_provider().unwrap_or(ParameterIn::Path);
+        let required = if parameter_in == ParameterIn::Path {
+            Required::True
+        } else {
+            Required::False
+        };
+        let labeler = <T as Label>::labeler();
+        let description = Some(labeler.label())
+            .filter(|label| !label.is_empty())
+            .map(|label| format!("Identifies a `{label}`."));
+
+        vec![ParameterBuilder::new()
+            .name("id")
+            .parameter_in(parameter_in)
+            .required(required)
+            .description(description)
+            .schema(Some(Self::schema()))
+            .build()]
+    }
+}
+
+#[cfg(any(feature = "snowflake", feature = "ksuid", feature = "ulid"))]
+fn string_schema(format: Option<&str>) -> RefOr<Schema> {
+    ObjectBuilder::new()
+        .schema_type(Type::String)
+        .format(format.map(|format| SchemaFormat::Custom(format.to_string())))
+        .into()
+}
+
+#[cfg(feature = "snowflake")]
+impl PartialSchema for crate::snowflake::pretty::PrettySnowflakeId {
+    fn schema() -> RefOr<Schema> {
+        string_schema(None)
+    }
+}
+
+#[cfg(feature = "snowflake")]
+impl ToSchema for crate::snowflake::pretty::PrettySnowflakeId {}
+
+#[cfg(feature = "ksuid")]
+impl PartialSchema for crate::Ksuid {
+    fn schema() -> RefOr<Schema> {
+        string_schema(Some("ksuid"))
+    }
+}
+
+#[cfg(feature = "ksuid")]
+impl ToSchema for crate::Ksuid {}
+
+#[cfg(feature = "ulid")]
+impl PartialSchema for crate::Ulid {
+    fn schema() -> RefOr<Schema> {
+        string_schema(Some("ulid"))
+    }
+}
+
+#[cfg(feature = "ulid")]
+impl ToSchema for crate::Ulid {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CustomLabeling;
+    use pretty_assertions::assert_eq;
+
+    struct Order;
+
+    impl Label for Order {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("Order")
+        }
+    }
+
+    #[test]
+    fn test_id_schema_delegates_to_inner_id() {
+        assert_eq!(<Id<Order, u64> as ToSchema>::name(), "OrderId");
+
+        let schema = serde_json::to_value(<Id<Order, u64> as PartialSchema>::schema()).unwrap();
+        assert_eq!(schema["type"], "integer");
+        assert_eq!(schema["format"], "int64");
+        assert_eq!(schema["title"], "Order id");
+
+        let schema = serde_json::to_value(<Id<Order, String> as PartialSchema>::schema()).unwrap();
+        assert_eq!(schema["type"], "string");
+    }
+
+    #[test]
+    fn test_id_into_params() {
+        let params = Id::<Order, u64>::into_params(|| None);
+        assert_eq!(params.len(), 1);
+        let param = serde_json::to_value(&params[0]).unwrap();
+        assert_eq!(param["name"], "id");
+        assert_eq!(param["in"], "path");
+        assert_eq!(param["required"], true);
+        assert_eq!(param["schema"]["type"], "integer");
+
+        let params = Id::<Order, String>::into_params(|| Some(ParameterIn::Query));
+        let param = serde_json::to_value(&params[0]).unwrap();
+        assert_eq!(param["in"], "query");
+        assert_eq!(param["required"], false);
+    }
+}
@@ -0,0 +1,194 @@
+use crate::{accepts_label, Id, Label, Labeling};
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum IdParseError {
+    #[error("id is missing its `{expected}` label prefix")]
+    MissingLabel { expected: String },
+
+    #[error("id label `{actual}` does not match expected label `{expected}`")]
+    LabelMismatch { expected: String, actual: String },
+
+    #[error("failed to parse id: {0}")]
+    InvalidId(String),
+}
+
+impl<T, ID> Id<T, ID>
+where
+    T: ?Sized + Label,
+    ID: FromStr,
+    <ID as FromStr>::Err: fmt::Display,
+{
+    /// Parses either the labeled `Label::id` display form or a bare id, for inputs (e.g., URL path
+    /// segments) where the label prefix is optional. A label that is present must still match `T`.
+    pub fn parse_lenient(rep: &str) -> Result<Self, IdParseError> {
+        let labeler = <T as Label>::labeler();
+        match rep.split_once(T::DELIMITER) {
+            Some(_) if !labeler.label().is_empty() => Self::from_str(rep),
+            _ => Self::parse_id(labeler.label(), rep),
+        }
+    }
+
+    fn parse_id(label: &str, id: &str) -> Result<Self, IdParseError> {
+        let id = ID::from_str(id).map_err(|err| IdParseError::InvalidId(err.to_string()))?;
+        Ok(Self::direct_labeled(label, id))
+    }
+}
+
+/// Parses the `Label::id` form rendered by `Display`, verifying the label matches `T` or one of its
+/// aliases. Ids of unlabeled types parse from the bare id.
+impl<T, ID> FromStr for Id<T, ID>
+where
+    T: ?Sized + Label,
+    ID: FromStr,
+    <ID as FromStr>::Err: fmt::Display,
+{
+    type Err = IdParseError;
+
+    fn from_str(rep: &str) -> Result<Self, Self::Err> {
+        let labeler = <T as Label>::labeler();
+        if labeler.label().is_empty() {
+            return Self::parse_id(labeler.label(), rep);
+        }
+
+        let (label, id) =
+            rep.split_once(T::DELIMITER)
+                .ok_or_else(|| IdParseError::MissingLabel {
+                    expected: labeler.label().to_string(),
+                })?;
+
+        if !accepts_label::<T>(label) {
+            return Err(IdParseError::LabelMismatch {
+                expected: labeler.label().to_string(),
+                actual: label.to_string(),
+            });
+        }
+
+        Self::parse_id(labeler.label(), id)
+    }
+}
+
+impl<T, ID> TryFrom<&str> for Id<T, ID>
+where
+    T: ?Sized + Label,
+    ID: FromStr,
+    <ID as FromStr>::Err: fmt::Display,
+{
+    type Error = IdParseError;
+
+    fn try_from(rep: &str) -> Result<Self, Self::Error> {
+        Self::from_str(rep)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CustomLabeling, NoLabeling};
+    use claim::*;
+    use pretty_assertions::assert_eq;
+
+    struct User;
+
+    impl Label for User {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("User")
+        }
+
+        fn aliases() -> &'static [&'static str] {
+            &["Member"]
+        }
+    }
+
+    struct LegacyUser;
+
+    impl Label for LegacyUser {
+        type Labeler = CustomLabeling;
+        const DELIMITER: &'static str = "/";
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("user")
+        }
+    }
+
+    struct Unlabeled;
+
+    impl Label for Unlabeled {
+        type Labeler = NoLabeling;
+
+        fn labeler() -> Self::Labeler {
+            NoLabeling
+        }
+    }
+
+    #[test]
+    fn test_parse_display_roundtrip() {
+        let id: Id<User, u64> = Id::for_labeled(42);
+        let actual: Id<User, u64> = assert_ok!(id.to_string().parse());
+        assert_eq!(actual, id);
+        assert_eq!(actual.label, "User");
+
+        let actual: Id<User, String> = assert_ok!(Id::try_from("Member::a::b"));
+        assert_eq!(actual.label, "User");
+        assert_eq!(actual.id, "a::b");
+
+        let id: Id<Unlabeled, u64> = Id::for_labeled(17);
+        assert_eq!(assert_ok!(id.to_string().parse::<Id<Unlabeled, u64>>()), id);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            assert_err!("42".parse::<Id<User, u64>>()),
+            IdParseError::MissingLabel {
+                expected: "User".to_string()
+            }
+        );
+        assert_eq!(
+            assert_err!("Order::42".parse::<Id<User, u64>>()),
+            IdParseError::LabelMismatch {
+                expected: "User".to_string(),
+                actual: "Order".to_string(),
+            }
+        );
+        assert_matches!(
+            "User::forty-two".parse::<Id<User, u64>>(),
+            Err(IdParseError::InvalidId(_))
+        );
+    }
+
+    #[test]
+    fn test_parse_label_delimiter() {
+        let id: Id<LegacyUser, String> = Id::for_labeled("abc123".to_string());
+        assert_eq!(id.to_string(), "user/abc123");
+        assert_eq!(
+            assert_ok!(id.to_string().parse::<Id<LegacyUser, String>>()),
+            id
+        );
+        assert_eq!(
+            assert_ok!(Id::<LegacyUser, String>::parse_lenient("abc123")),
+            id
+        );
+        assert_eq!(
+            assert_err!("user::abc123".parse::<Id<LegacyUser, String>>()),
+            IdParseError::MissingLabel {
+                expected: "user".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_lenient() {
+        let id: Id<User, u64> = Id::for_labeled(42);
+        assert_eq!(assert_ok!(Id::<User, u64>::parse_lenient("42")), id);
+        assert_eq!(assert_ok!(Id::<User, u64>::parse_lenient("User::42")), id);
+        assert_matches!(
+            Id::<User, u64>::parse_lenient("Order::42"),
+            Err(IdParseError::LabelMismatch { .. })
+        );
+    }
+}
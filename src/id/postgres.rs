@@ -0,0 +1,150 @@
+//! Native `tokio-postgres` integration, mapping `Id<T, ID>` to the Postgres type of its inner `ID`
+//! so typed ids bind directly as prepared statement parameters and decode from rows.
+
+use crate::{Id, Label};
+use bytes::BytesMut;
+use postgres_types::{to_sql_checked, FromSql, IsNull, ToSql, Type};
+use std::error::Error;
+
+impl<T, ID> ToSql for Id<T, ID>
+where
+    T: ?Sized,
+    ID: ToSql,
+{
+    fn to_sql(
+        &self,
+        ty: &Type,
+        out: &mut BytesMut,
+    ) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        self.id.to_sql(ty, out)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        ID::accepts(ty)
+    }
+
+    to_sql_checked!();
+}
+
+/// Labels the decoded id for `T`, as deserialization does.
+impl<'a, T, ID> FromSql<'a> for Id<T, ID>
+where
+    T: ?Sized + Label,
+    ID: FromSql<'a>,
+{
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        ID::from_sql(ty, raw).map(Self::for_labeled)
+    }
+
+    fn from_sql_null(ty: &Type) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        ID::from_sql_null(ty).map(Self::for_labeled)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        ID::accepts(ty)
+    }
+}
+
+/// ULIDs share the 128-bit layout of UUIDs, so are stored in `uuid` columns.
+#[cfg(feature = "ulid")]
+impl ToSql for crate::Ulid {
+    fn to_sql(&self, _: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        out.extend_from_slice(self.as_bytes());
+        Ok(IsNull::No)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        *ty == Type::UUID
+    }
+
+    to_sql_checked!();
+}
+
+#[cfg(feature = "ulid")]
+impl<'a> FromSql<'a> for crate::Ulid {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        let bytes = raw
+            .try_into()
+            .map_err(|_| format!("expected 16 bytes for ULID but got {}", raw.len()))?;
+        Ok(Self::from_bytes(bytes))
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        *ty == Type::UUID
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CustomLabeling;
+    use claim::*;
+    use pretty_assertions::assert_eq;
+
+    struct Order;
+
+    impl Label for Order {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("Order")
+        }
+    }
+
+    fn roundtrip<ID>(id: &Id<Order, ID>, ty: &Type) -> Id<Order, ID>
+    where
+        ID: ToSql + for<'a> FromSql<'a>,
+    {
+        let mut buf = BytesMut::new();
+        let is_null = id.to_sql_checked(ty, &mut buf).unwrap();
+        assert!(matches!(is_null, IsNull::No));
+        assert_ok!(Id::from_sql(ty, &buf))
+    }
+
+    #[test]
+    fn test_postgres_roundtrip() {
+        let id: Id<Order, i64> = Id::for_labeled(17);
+        let actual = roundtrip(&id, &Type::INT8);
+        assert_eq!(actual, id);
+        assert_eq!(actual.to_string(), "Order::17");
+
+        let id: Id<Order, String> = Id::for_labeled("o-17".to_string());
+        assert_eq!(roundtrip(&id, &Type::TEXT), id);
+    }
+
+    #[test]
+    fn test_postgres_accepts_inner_type() {
+        assert!(<Id<Order, i64> as ToSql>::accepts(&Type::INT8));
+        assert!(!<Id<Order, i64> as ToSql>::accepts(&Type::TEXT));
+        assert!(<Id<Order, String> as FromSql>::accepts(&Type::VARCHAR));
+
+        let mut buf = BytesMut::new();
+        assert!(Id::<Order, i64>::for_labeled(17)
+            .to_sql_checked(&Type::TEXT, &mut buf)
+            .is_err());
+        assert_err!(<Id<Order, i64> as FromSql>::from_sql_null(&Type::INT8));
+        assert_eq!(
+            assert_ok!(<Option<Id<Order, i64>> as FromSql>::from_sql_nullable(
+                &Type::INT8,
+                None
+            )),
+            None
+        );
+    }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn test_postgres_uuid_roundtrip() {
+        let id: Id<Order, uuid::Uuid> = Id::for_labeled(uuid::Uuid::new_v4());
+        assert_eq!(roundtrip(&id, &Type::UUID), id);
+    }
+
+    #[cfg(feature = "ulid")]
+    #[test]
+    fn test_postgres_ulid_roundtrip() {
+        let id: Id<Order, crate::Ulid> = Id::for_labeled(crate::Ulid::new());
+        assert_eq!(roundtrip(&id, &Type::UUID), id);
+        assert!(!<crate::Ulid as ToSql>::accepts(&Type::TEXT));
+        assert!(crate::Ulid::from_sql(&Type::UUID, &[0; 3]).is_err());
+    }
+}
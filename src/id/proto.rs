@@ -0,0 +1,288 @@
+//! Conversions between typed ids and the `string` and `bytes` fields of [`prost`] generated
+//! messages, so gRPC services can map request fields into typed ids, checking labels, in one call.
+//!
+//! The `string` form is the labeled `Label::id` display form, while the `bytes` form carries the
+//! fixed-size binary form of the id (see [`ProtoBytes`]). Proto3 cannot distinguish an unset field
+//! from an empty one, so empty fields convert into [`IdProtoError::Missing`].
+
+use crate::{Id, IdParseError, Label, Labeling};
+use ::prost::bytes::Bytes;
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum IdProtoError {
+    #[error("{label} id is missing")]
+    Missing { label: String },
+
+    #[error(transparent)]
+    Parse(#[from] IdParseError),
+
+    #[error("invalid {label} id: expected {expected} bytes but got {actual}")]
+    InvalidLength {
+        label: String,
+        expected: usize,
+        actual: usize,
+    },
+}
+
+impl IdProtoError {
+    fn missing<T: ?Sized + Label>() -> Self {
+        Self::Missing {
+            label: T::labeler().label().to_string(),
+        }
+    }
+}
+
+/// Id representations with a fixed-size binary form, carried in `bytes` proto fields. Integers
+/// are big-endian, so their binary forms sort as the ids do.
+pub trait ProtoBytes: Sized {
+    /// The length of the binary form.
+    const LEN: usize;
+
+    fn to_proto_bytes(&self) -> Vec<u8>;
+
+    /// Decodes the binary form, returning `None` if `bytes` is not [`Self::LEN`] long.
+    fn from_proto_bytes(bytes: &[u8]) -> Option<Self>;
+}
+
+macro_rules! integer_proto_bytes {
+    ($i:ty) => {
+        impl ProtoBytes for $i {
+            const LEN: usize = std::mem::size_of::<$i>();
+
+            fn to_proto_bytes(&self) -> Vec<u8> {
+                self.to_be_bytes().to_vec()
+            }
+
+            fn from_proto_bytes(bytes: &[u8]) -> Option<Self> {
+                bytes.try_into().ok().map(<$i>::from_be_bytes)
+            }
+        }
+    };
+}
+
+integer_proto_bytes!(i64);
+integer_proto_bytes!(u64);
+integer_proto_bytes!(u128);
+
+#[cfg(feature = "uuid")]
+impl ProtoBytes for ::uuid::Uuid {
+    const LEN: usize = 16;
+
+    fn to_proto_bytes(&self) -> Vec<u8> {
+        self.as_bytes().to_vec()
+    }
+
+    fn from_proto_bytes(bytes: &[u8]) -> Option<Self> {
+        Self::from_slice(bytes).ok()
+    }
+}
+
+#[cfg(feature = "ksuid")]
+impl ProtoBytes for crate::id::Ksuid {
+    const LEN: usize = crate::id::ksuid::BYTES_LEN;
+
+    fn to_proto_bytes(&self) -> Vec<u8> {
+        self.as_bytes().to_vec()
+    }
+
+    fn from_proto_bytes(bytes: &[u8]) -> Option<Self> {
+        bytes.try_into().ok().map(Self::from_bytes)
+    }
+}
+
+#[cfg(feature = "ulid")]
+impl ProtoBytes for crate::Ulid {
+    const LEN: usize = crate::id::ulid::BYTES_LEN;
+
+    fn to_proto_bytes(&self) -> Vec<u8> {
+        self.as_bytes().to_vec()
+    }
+
+    fn from_proto_bytes(bytes: &[u8]) -> Option<Self> {
+        bytes.try_into().ok().map(Self::from_bytes)
+    }
+}
+
+#[cfg(feature = "objectid")]
+impl ProtoBytes for ::bson::oid::ObjectId {
+    const LEN: usize = 12;
+
+    fn to_proto_bytes(&self) -> Vec<u8> {
+        self.bytes().to_vec()
+    }
+
+    fn from_proto_bytes(bytes: &[u8]) -> Option<Self> {
+        bytes.try_into().ok().map(Self::from_bytes)
+    }
+}
+
+impl<T: ?Sized, ID: fmt::Display> From<Id<T, ID>> for String {
+    fn from(id: Id<T, ID>) -> Self {
+        id.to_string()
+    }
+}
+
+/// Parses the labeled `Label::id` form, verifying the label matches `T`.
+impl<T, ID> TryFrom<String> for Id<T, ID>
+where
+    T: ?Sized + Label,
+    ID: FromStr,
+    <ID as FromStr>::Err: fmt::Display,
+{
+    type Error = IdProtoError;
+
+    fn try_from(rep: String) -> Result<Self, Self::Error> {
+        if rep.is_empty() {
+            return Err(IdProtoError::missing::<T>());
+        }
+        Ok(Self::from_str(&rep)?)
+    }
+}
+
+impl<T: ?Sized, ID: ProtoBytes> From<Id<T, ID>> for Vec<u8> {
+    fn from(id: Id<T, ID>) -> Self {
+        id.id.to_proto_bytes()
+    }
+}
+
+impl<T: ?Sized, ID: ProtoBytes> From<Id<T, ID>> for Bytes {
+    fn from(id: Id<T, ID>) -> Self {
+        id.id.to_proto_bytes().into()
+    }
+}
+
+/// Labels the decoded id for `T`, as deserialization does.
+impl<T: ?Sized + Label, ID: ProtoBytes> TryFrom<&[u8]> for Id<T, ID> {
+    type Error = IdProtoError;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        if bytes.is_empty() {
+            return Err(IdProtoError::missing::<T>());
+        }
+
+        let id = ID::from_proto_bytes(bytes).ok_or_else(|| IdProtoError::InvalidLength {
+            label: T::labeler().label().to_string(),
+            expected: ID::LEN,
+            actual: bytes.len(),
+        })?;
+        Ok(Self::for_labeled(id))
+    }
+}
+
+impl<T: ?Sized + Label, ID: ProtoBytes> TryFrom<Vec<u8>> for Id<T, ID> {
+    type Error = IdProtoError;
+
+    fn try_from(bytes: Vec<u8>) -> Result<Self, Self::Error> {
+        Self::try_from(bytes.as_slice())
+    }
+}
+
+impl<T: ?Sized + Label, ID: ProtoBytes> TryFrom<Bytes> for Id<T, ID> {
+    type Error = IdProtoError;
+
+    fn try_from(bytes: Bytes) -> Result<Self, Self::Error> {
+        Self::try_from(bytes.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CustomLabeling;
+    use ::prost::Message;
+    use claim::*;
+    use pretty_assertions::assert_eq;
+
+    struct Order;
+
+    impl Label for Order {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("Order")
+        }
+    }
+
+    struct Customer;
+
+    impl Label for Customer {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("Customer")
+        }
+    }
+
+    #[derive(Clone, PartialEq, Message)]
+    struct GetOrderRequest {
+        #[prost(string, tag = "1")]
+        order_id: String,
+
+        #[prost(bytes = "vec", tag = "2")]
+        customer_id: Vec<u8>,
+
+        #[prost(bytes = "bytes", tag = "3")]
+        parent_id: Bytes,
+    }
+
+    #[test]
+    fn test_proto_roundtrip() {
+        let order_id: Id<Order, String> = Id::for_labeled("o-17".to_string());
+        let customer_id: Id<Customer, i64> = Id::for_labeled(17);
+        let parent_id: Id<Order, u128> = Id::for_labeled(u128::MAX - 1);
+
+        let request = GetOrderRequest {
+            order_id: order_id.clone().into(),
+            customer_id: customer_id.clone().into(),
+            parent_id: parent_id.clone().into(),
+        };
+        assert_eq!(request.order_id, "Order::o-17");
+        assert_eq!(request.customer_id, 17_i64.to_be_bytes());
+
+        let request = assert_ok!(GetOrderRequest::decode(request.encode_to_vec().as_slice()));
+        assert_eq!(order_id, assert_ok!(Id::try_from(request.order_id)));
+        assert_eq!(customer_id, assert_ok!(Id::try_from(request.customer_id)));
+        assert_eq!(parent_id, assert_ok!(Id::try_from(request.parent_id)));
+    }
+
+    #[test]
+    fn test_proto_errors() {
+        let request = GetOrderRequest::default();
+        assert_eq!(
+            assert_err!(Id::<Order, String>::try_from(request.order_id)),
+            IdProtoError::Missing {
+                label: "Order".to_string()
+            }
+        );
+        assert_eq!(
+            assert_err!(Id::<Customer, i64>::try_from(request.customer_id)).to_string(),
+            "Customer id is missing"
+        );
+
+        assert_matches!(
+            Id::<Order, String>::try_from("Customer::c-17".to_string()),
+            Err(IdProtoError::Parse(IdParseError::LabelMismatch { .. }))
+        );
+        assert_matches!(
+            Id::<Order, String>::try_from("o-17".to_string()),
+            Err(IdProtoError::Parse(IdParseError::MissingLabel { .. }))
+        );
+
+        assert_eq!(
+            assert_err!(Id::<Customer, i64>::try_from(vec![0, 17])).to_string(),
+            "invalid Customer id: expected 8 bytes but got 2"
+        );
+    }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn test_proto_uuid_bytes() {
+        let id: Id<Order, uuid::Uuid> = Id::for_labeled(uuid::Uuid::new_v4());
+        let bytes: Vec<u8> = id.clone().into();
+        assert_eq!(bytes, id.id.as_bytes());
+        assert_eq!(id, assert_ok!(Id::try_from(bytes)));
+    }
+}
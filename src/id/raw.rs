@@ -0,0 +1,478 @@
+use crate::Id;
+use serde::{Deserialize, Serialize};
+use smol_str::SmolStr;
+use std::borrow::Cow;
+use std::fmt;
+use thiserror::Error;
+
+/// Uniform access to the underlying representation of an id, so generic code (e.g., storage
+/// layers) can handle `Id<T, ID>` for any supported `ID` without bounding on each representation.
+///
+/// Every id has a string form; the numeric and binary accessors return `None` unless the
+/// representation naturally (and losslessly) fits.
+pub trait AsRawId {
+    fn as_str(&self) -> Cow<'_, str>;
+
+    fn as_i64(&self) -> Option<i64> {
+        None
+    }
+
+    fn as_u128(&self) -> Option<u128> {
+        None
+    }
+
+    /// The fixed-size binary form of the id, such as the 16 bytes of a UUID.
+    fn as_bytes(&self) -> Option<Cow<'_, [u8]>> {
+        None
+    }
+}
+
+impl<T: ?Sized, ID: AsRawId> AsRawId for Id<T, ID> {
+    fn as_str(&self) -> Cow<'_, str> {
+        self.id.as_str()
+    }
+
+    fn as_i64(&self) -> Option<i64> {
+        self.id.as_i64()
+    }
+
+    fn as_u128(&self) -> Option<u128> {
+        self.id.as_u128()
+    }
+
+    fn as_bytes(&self) -> Option<Cow<'_, [u8]>> {
+        self.id.as_bytes()
+    }
+}
+
+impl AsRawId for String {
+    fn as_str(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self.as_str())
+    }
+}
+
+impl AsRawId for SmolStr {
+    fn as_str(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self.as_str())
+    }
+}
+
+impl AsRawId for &str {
+    fn as_str(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self)
+    }
+}
+
+macro_rules! integer_raw_id {
+    ($i:ty) => {
+        impl AsRawId for $i {
+            fn as_str(&self) -> Cow<'_, str> {
+                Cow::Owned(self.to_string())
+            }
+
+            fn as_i64(&self) -> Option<i64> {
+                i64::try_from(*self).ok()
+            }
+
+            fn as_u128(&self) -> Option<u128> {
+                u128::try_from(*self).ok()
+            }
+        }
+    };
+}
+
+integer_raw_id!(i32);
+integer_raw_id!(i64);
+integer_raw_id!(u32);
+integer_raw_id!(u64);
+integer_raw_id!(u128);
+
+#[cfg(feature = "uuid")]
+impl AsRawId for ::uuid::Uuid {
+    fn as_str(&self) -> Cow<'_, str> {
+        Cow::Owned(self.to_string())
+    }
+
+    fn as_u128(&self) -> Option<u128> {
+        Some(self.as_u128())
+    }
+
+    fn as_bytes(&self) -> Option<Cow<'_, [u8]>> {
+        Some(Cow::Borrowed(self.as_bytes()))
+    }
+}
+
+#[cfg(feature = "snowflake")]
+impl AsRawId for crate::snowflake::pretty::PrettySnowflakeId {
+    fn as_str(&self) -> Cow<'_, str> {
+        Cow::Borrowed(self.as_ref())
+    }
+}
+
+/// A representation-agnostic id, so infrastructure code (e.g., outbox writers or generic
+/// repositories) can persist heterogeneous typed ids through a single code path.
+///
+/// Conversions exist from every supported id type; converting back is tolerant of ids that
+/// round-tripped through text (e.g., [`RawId::Str`] parses into numeric and UUID ids).
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RawId {
+    Str(SmolStr),
+    I64(i64),
+    U128(u128),
+    Bytes([u8; 16]),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("cannot convert raw id `{actual}` into {expected}")]
+pub struct RawIdError {
+    pub expected: &'static str,
+    pub actual: RawId,
+}
+
+impl RawIdError {
+    pub(crate) const fn new(expected: &'static str, actual: RawId) -> Self {
+        Self { expected, actual }
+    }
+}
+
+impl fmt::Display for RawId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Str(rep) => write!(f, "{rep}"),
+            Self::I64(rep) => write!(f, "{rep}"),
+            Self::U128(rep) => write!(f, "{rep}"),
+            Self::Bytes(rep) => rep.iter().try_for_each(|b| write!(f, "{b:02x}")),
+        }
+    }
+}
+
+impl AsRawId for RawId {
+    fn as_str(&self) -> Cow<'_, str> {
+        match self {
+            Self::Str(rep) => Cow::Borrowed(rep.as_str()),
+            other => Cow::Owned(other.to_string()),
+        }
+    }
+
+    fn as_i64(&self) -> Option<i64> {
+        match self {
+            Self::I64(rep) => Some(*rep),
+            Self::U128(rep) => i64::try_from(*rep).ok(),
+            _ => None,
+        }
+    }
+
+    fn as_u128(&self) -> Option<u128> {
+        match self {
+            Self::I64(rep) => u128::try_from(*rep).ok(),
+            Self::U128(rep) => Some(*rep),
+            Self::Bytes(rep) => Some(u128::from_be_bytes(*rep)),
+            Self::Str(_) => None,
+        }
+    }
+
+    fn as_bytes(&self) -> Option<Cow<'_, [u8]>> {
+        match self {
+            Self::Bytes(rep) => Some(Cow::Borrowed(rep.as_slice())),
+            _ => None,
+        }
+    }
+}
+
+impl<T: ?Sized, ID: Into<Self>> From<Id<T, ID>> for RawId {
+    fn from(id: Id<T, ID>) -> Self {
+        id.id.into()
+    }
+}
+
+impl From<String> for RawId {
+    fn from(rep: String) -> Self {
+        Self::Str(rep.into())
+    }
+}
+
+impl From<&str> for RawId {
+    fn from(rep: &str) -> Self {
+        Self::Str(rep.into())
+    }
+}
+
+impl From<SmolStr> for RawId {
+    fn from(rep: SmolStr) -> Self {
+        Self::Str(rep)
+    }
+}
+
+impl From<i64> for RawId {
+    fn from(rep: i64) -> Self {
+        Self::I64(rep)
+    }
+}
+
+impl From<u128> for RawId {
+    fn from(rep: u128) -> Self {
+        Self::U128(rep)
+    }
+}
+
+impl From<[u8; 16]> for RawId {
+    fn from(rep: [u8; 16]) -> Self {
+        Self::Bytes(rep)
+    }
+}
+
+impl TryFrom<RawId> for String {
+    type Error = RawIdError;
+
+    fn try_from(raw: RawId) -> Result<Self, Self::Error> {
+        match raw {
+            RawId::Str(rep) => Ok(rep.to_string()),
+            RawId::I64(rep) => Ok(rep.to_string()),
+            RawId::U128(rep) => Ok(rep.to_string()),
+            raw => Err(RawIdError::new("String", raw)),
+        }
+    }
+}
+
+impl TryFrom<RawId> for SmolStr {
+    type Error = RawIdError;
+
+    fn try_from(raw: RawId) -> Result<Self, Self::Error> {
+        String::try_from(raw).map(Self::from)
+    }
+}
+
+impl TryFrom<RawId> for i64 {
+    type Error = RawIdError;
+
+    fn try_from(raw: RawId) -> Result<Self, Self::Error> {
+        match &raw {
+            RawId::I64(rep) => Ok(*rep),
+            RawId::U128(rep) => Self::try_from(*rep).map_err(|_| RawIdError::new("i64", raw)),
+            RawId::Str(rep) => rep.parse().map_err(|_| RawIdError::new("i64", raw)),
+            RawId::Bytes(_) => Err(RawIdError::new("i64", raw)),
+        }
+    }
+}
+
+impl TryFrom<RawId> for u128 {
+    type Error = RawIdError;
+
+    fn try_from(raw: RawId) -> Result<Self, Self::Error> {
+        match &raw {
+            RawId::Str(rep) => rep.parse().map_err(|_| RawIdError::new("u128", raw)),
+            _ => raw.as_u128().ok_or_else(|| RawIdError::new("u128", raw)),
+        }
+    }
+}
+
+impl TryFrom<RawId> for [u8; 16] {
+    type Error = RawIdError;
+
+    fn try_from(raw: RawId) -> Result<Self, Self::Error> {
+        match raw {
+            RawId::Bytes(rep) => Ok(rep),
+            RawId::U128(rep) => Ok(rep.to_be_bytes()),
+            raw => Err(RawIdError::new("[u8; 16]", raw)),
+        }
+    }
+}
+
+impl<T: ?Sized + crate::Label, ID: TryFrom<RawId, Error = RawIdError>> TryFrom<RawId>
+    for Id<T, ID>
+{
+    type Error = RawIdError;
+
+    fn try_from(raw: RawId) -> Result<Self, Self::Error> {
+        ID::try_from(raw).map(Self::for_labeled)
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl From<::uuid::Uuid> for RawId {
+    fn from(rep: ::uuid::Uuid) -> Self {
+        Self::Bytes(rep.into_bytes())
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl TryFrom<RawId> for ::uuid::Uuid {
+    type Error = RawIdError;
+
+    fn try_from(raw: RawId) -> Result<Self, Self::Error> {
+        match &raw {
+            RawId::Bytes(rep) => Ok(Self::from_bytes(*rep)),
+            RawId::U128(rep) => Ok(Self::from_u128(*rep)),
+            RawId::Str(rep) => Self::parse_str(rep).map_err(|_| RawIdError::new("Uuid", raw)),
+            RawId::I64(_) => Err(RawIdError::new("Uuid", raw)),
+        }
+    }
+}
+
+#[cfg(feature = "snowflake")]
+impl From<crate::snowflake::pretty::PrettySnowflakeId> for RawId {
+    fn from(rep: crate::snowflake::pretty::PrettySnowflakeId) -> Self {
+        Self::Str(SmolStr::new(rep.as_ref()))
+    }
+}
+
+#[cfg(feature = "snowflake")]
+impl TryFrom<RawId> for crate::snowflake::pretty::PrettySnowflakeId {
+    type Error = RawIdError;
+
+    fn try_from(raw: RawId) -> Result<Self, Self::Error> {
+        match raw {
+            RawId::Str(rep) => Ok(Self::from_rep(rep)),
+            raw => Err(RawIdError::new("PrettySnowflakeId", raw)),
+        }
+    }
+}
+
+/// Raw ids are stored as text, via their `Display` form, and decode as [`RawId::Str`]; convert
+/// the decoded value into the typed id to recover the original representation.
+#[cfg(feature = "sqlx")]
+impl<'q, DB> sqlx::Encode<'q, DB> for RawId
+where
+    String: sqlx::Encode<'q, DB>,
+    DB: sqlx::Database,
+{
+    fn encode_by_ref(
+        &self,
+        buf: &mut <DB as sqlx::database::HasArguments<'q>>::ArgumentBuffer,
+    ) -> sqlx::encode::IsNull {
+        <String as sqlx::Encode<DB>>::encode(self.to_string(), buf)
+    }
+}
+
+#[cfg(feature = "sqlx")]
+impl<'r, DB> sqlx::Decode<'r, DB> for RawId
+where
+    String: sqlx::Decode<'r, DB>,
+    DB: sqlx::Database,
+{
+    fn decode(
+        value: <DB as sqlx::database::HasValueRef<'r>>::ValueRef,
+    ) -> Result<Self, sqlx::error::BoxDynError> {
+        let rep = <String as sqlx::Decode<DB>>::decode(value)?;
+        Ok(Self::Str(rep.into()))
+    }
+}
+
+#[cfg(feature = "sqlx")]
+impl<DB> sqlx::Type<DB> for RawId
+where
+    String: sqlx::Type<DB>,
+    DB: sqlx::Database,
+{
+    fn type_info() -> DB::TypeInfo {
+        <String as sqlx::Type<DB>>::type_info()
+    }
+
+    fn compatible(ty: &DB::TypeInfo) -> bool {
+        <String as sqlx::Type<DB>>::compatible(ty)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CustomLabeling;
+    use crate::Label;
+    use pretty_assertions::assert_eq;
+
+    struct Order;
+
+    impl Label for Order {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("Order")
+        }
+    }
+
+    fn describe<R: AsRawId>(raw: &R) -> (String, Option<i64>, Option<u128>, Option<usize>) {
+        (
+            raw.as_str().into_owned(),
+            raw.as_i64(),
+            raw.as_u128(),
+            raw.as_bytes().map(|b| b.len()),
+        )
+    }
+
+    #[test]
+    fn test_raw_id_accessors() {
+        let id: Id<Order, String> = Id::for_labeled("ig6wv6nezj0jg51lg53dztqy".to_string());
+        assert_eq!(
+            describe(&id),
+            ("ig6wv6nezj0jg51lg53dztqy".to_string(), None, None, None)
+        );
+        assert!(matches!(id.as_str(), Cow::Borrowed(_)));
+
+        let id: Id<Order, i64> = Id::for_labeled(-17);
+        assert_eq!(describe(&id), ("-17".to_string(), Some(-17), None, None));
+
+        let id: Id<Order, u128> = Id::for_labeled(u128::MAX);
+        assert_eq!(
+            describe(&id),
+            (u128::MAX.to_string(), None, Some(u128::MAX), None)
+        );
+    }
+
+    #[test]
+    fn test_raw_id_conversions() {
+        let id: Id<Order, String> = Id::for_labeled("ig6wv6nezj0jg51lg53dztqy".to_string());
+        let raw = RawId::from(id.clone());
+        assert_eq!(raw, RawId::Str("ig6wv6nezj0jg51lg53dztqy".into()));
+        assert_eq!(Id::<Order, String>::try_from(raw).unwrap(), id);
+
+        let raw = RawId::from(Id::<Order, i64>::for_labeled(42));
+        assert_eq!(raw, RawId::I64(42));
+        assert_eq!(raw.to_string(), "42");
+        assert_eq!(i64::try_from(RawId::Str("42".into())), Ok(42));
+        assert_eq!(u128::try_from(raw), Ok(42));
+        assert_eq!(
+            i64::try_from(RawId::U128(u128::MAX)),
+            Err(RawIdError {
+                expected: "i64",
+                actual: RawId::U128(u128::MAX),
+            })
+        );
+
+        let raw = RawId::Bytes([0xab; 16]);
+        assert_eq!(raw.to_string(), "ab".repeat(16));
+        assert!(String::try_from(raw).is_err());
+    }
+
+    #[test]
+    fn test_raw_id_serde() {
+        let raw = RawId::U128(u128::MAX);
+        let json = serde_json::to_string(&raw).unwrap();
+        assert_eq!(json, format!(r#"{{"u128":{}}}"#, u128::MAX));
+        assert_eq!(serde_json::from_str::<RawId>(&json).unwrap(), raw);
+
+        let raw = RawId::Str("abc".into());
+        let json = serde_json::to_string(&raw).unwrap();
+        assert_eq!(json, r#"{"str":"abc"}"#);
+        assert_eq!(serde_json::from_str::<RawId>(&json).unwrap(), raw);
+    }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn test_uuid_raw_id() {
+        let uuid = ::uuid::Uuid::new_v4();
+        let id: Id<Order, ::uuid::Uuid> = Id::for_labeled(uuid);
+        assert_eq!(
+            describe(&id),
+            (uuid.to_string(), None, Some(uuid.as_u128()), Some(16))
+        );
+
+        let raw = RawId::from(id);
+        assert_eq!(raw, RawId::Bytes(uuid.into_bytes()));
+        assert_eq!(::uuid::Uuid::try_from(raw.clone()), Ok(uuid));
+        assert_eq!(
+            ::uuid::Uuid::try_from(RawId::Str(raw.to_string().into())),
+            Ok(uuid)
+        );
+    }
+}
@@ -0,0 +1,369 @@
+use crate::{accepts_label, Id, Label, Labeling};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use smol_str::SmolStr;
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Leading scheme of every rendered resource name.
+pub const RESOURCE_NAME_SCHEME: &str = "srn";
+
+const COMPONENT_SEPARATOR: char = ':';
+const ID_SEPARATOR: char = '/';
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ResourceNameError {
+    #[error("resource name must start with `{RESOURCE_NAME_SCHEME}:`: {0}")]
+    MissingScheme(String),
+
+    #[error("resource name is missing its {0} component")]
+    MissingComponent(&'static str),
+
+    #[error("resource name label `{actual}` does not match expected label `{expected}`")]
+    LabelMismatch { expected: String, actual: String },
+
+    #[error("failed to parse resource name id: {0}")]
+    InvalidId(String),
+
+    #[error("resource name {component} `{value}` must not contain `{separator}`")]
+    InvalidComponent {
+        component: &'static str,
+        value: String,
+        separator: char,
+    },
+}
+
+/// An ARN-style, globally addressable resource identifier built from a typed [`Id`].
+///
+/// Renders as `srn:service:region:tenant:Label/id`. The region and tenant components may be left
+/// empty (e.g., `srn:billing:::Invoice/42`) for resources that are not partitioned along those
+/// axes. Components other than the id must not contain `:`, nor may the label contain `/`;
+/// [`ResourceNameBuilder::build`] rejects those that do.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ResourceName {
+    pub service: SmolStr,
+    pub region: SmolStr,
+    pub tenant: SmolStr,
+    pub label: SmolStr,
+    pub id: SmolStr,
+}
+
+impl ResourceName {
+    /// Start building resource names for the given service.
+    pub fn builder(service: impl AsRef<str>) -> ResourceNameBuilder {
+        ResourceNameBuilder::new(service)
+    }
+
+    /// Converts the resource name back into a typed [`Id`], verifying the label matches `T` or one of
+    /// its aliases.
+    pub fn to_id<T, ID>(&self) -> Result<Id<T, ID>, ResourceNameError>
+    where
+        T: ?Sized + Label,
+        ID: FromStr,
+        <ID as FromStr>::Err: fmt::Display,
+    {
+        let labeler = <T as Label>::labeler();
+        if !accepts_label::<T>(&self.label) {
+            return Err(ResourceNameError::LabelMismatch {
+                expected: labeler.label().to_string(),
+                actual: self.label.to_string(),
+            });
+        }
+
+        let id =
+            ID::from_str(&self.id).map_err(|err| ResourceNameError::InvalidId(err.to_string()))?;
+        Ok(Id::direct_labeled(labeler.label(), id))
+    }
+}
+
+impl fmt::Display for ResourceName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{RESOURCE_NAME_SCHEME}{COMPONENT_SEPARATOR}{}{COMPONENT_SEPARATOR}{}{COMPONENT_SEPARATOR}{}{COMPONENT_SEPARATOR}{}{ID_SEPARATOR}{}",
+            self.service, self.region, self.tenant, self.label, self.id
+        )
+    }
+}
+
+impl FromStr for ResourceName {
+    type Err = ResourceNameError;
+
+    fn from_str(rep: &str) -> Result<Self, Self::Err> {
+        let mut components = rep.splitn(5, COMPONENT_SEPARATOR);
+        if components.next() != Some(RESOURCE_NAME_SCHEME) {
+            return Err(ResourceNameError::MissingScheme(rep.to_string()));
+        }
+
+        let service = components
+            .next()
+            .filter(|s| !s.is_empty())
+            .ok_or(ResourceNameError::MissingComponent("service"))?;
+        let region = components
+            .next()
+            .ok_or(ResourceNameError::MissingComponent("region"))?;
+        let tenant = components
+            .next()
+            .ok_or(ResourceNameError::MissingComponent("tenant"))?;
+        let (label, id) = components
+            .next()
+            .and_then(|resource| resource.split_once(ID_SEPARATOR))
+            .ok_or(ResourceNameError::MissingComponent("resource"))?;
+        if id.is_empty() {
+            return Err(ResourceNameError::MissingComponent("id"));
+        }
+
+        Ok(Self {
+            service: SmolStr::new(service),
+            region: SmolStr::new(region),
+            tenant: SmolStr::new(tenant),
+            label: SmolStr::new(label),
+            id: SmolStr::new(id),
+        })
+    }
+}
+
+impl Serialize for ResourceName {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ResourceName {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let rep = String::deserialize(deserializer)?;
+        Self::from_str(&rep).map_err(de::Error::custom)
+    }
+}
+
+/// Captures the service, region and tenant context shared by resource names, so typed ids can be
+/// rendered into [`ResourceName`]s.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResourceNameBuilder {
+    service: SmolStr,
+    region: SmolStr,
+    tenant: SmolStr,
+}
+
+impl ResourceNameBuilder {
+    pub fn new(service: impl AsRef<str>) -> Self {
+        Self {
+            service: SmolStr::new(service),
+            region: SmolStr::default(),
+            tenant: SmolStr::default(),
+        }
+    }
+
+    pub fn region(self, region: impl AsRef<str>) -> Self {
+        Self {
+            region: SmolStr::new(region),
+            ..self
+        }
+    }
+
+    pub fn tenant(self, tenant: impl AsRef<str>) -> Self {
+        Self {
+            tenant: SmolStr::new(tenant),
+            ..self
+        }
+    }
+
+    /// Renders `id` as a resource name in the builder's context, rejecting a missing service and
+    /// components that would not survive parsing back from the rendered form.
+    pub fn build<T: ?Sized, ID: fmt::Display>(
+        &self,
+        id: &Id<T, ID>,
+    ) -> Result<ResourceName, ResourceNameError> {
+        if self.service.is_empty() {
+            return Err(ResourceNameError::MissingComponent("service"));
+        }
+        check_component("service", &self.service, COMPONENT_SEPARATOR)?;
+        check_component("region", &self.region, COMPONENT_SEPARATOR)?;
+        check_component("tenant", &self.tenant, COMPONENT_SEPARATOR)?;
+        check_component("label", &id.label, COMPONENT_SEPARATOR)?;
+        check_component("label", &id.label, ID_SEPARATOR)?;
+
+        let id_rep = id.id.to_string();
+        if id_rep.is_empty() {
+            return Err(ResourceNameError::MissingComponent("id"));
+        }
+
+        Ok(ResourceName {
+            service: self.service.clone(),
+            region: self.region.clone(),
+            tenant: self.tenant.clone(),
+            label: id.label.clone(),
+            id: SmolStr::new(id_rep),
+        })
+    }
+}
+
+fn check_component(
+    component: &'static str,
+    value: &str,
+    separator: char,
+) -> Result<(), ResourceNameError> {
+    if value.contains(separator) {
+        return Err(ResourceNameError::InvalidComponent {
+            component,
+            value: value.to_string(),
+            separator,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CustomLabeling, MakeLabeling};
+    use claim::*;
+    use pretty_assertions::assert_eq;
+
+    struct Invoice;
+
+    impl Label for Invoice {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("Invoice")
+        }
+    }
+
+    struct Order;
+
+    impl Label for Order {
+        type Labeler = MakeLabeling<Self>;
+
+        fn labeler() -> Self::Labeler {
+            MakeLabeling::default()
+        }
+    }
+
+    #[test]
+    fn test_resource_name_roundtrip() {
+        let id: Id<Invoice, u64> = Id::for_labeled(42);
+        let builder = ResourceName::builder("billing")
+            .region("us-east-1")
+            .tenant("acme");
+        let name = assert_ok!(builder.build(&id));
+        assert_eq!(name.to_string(), "srn:billing:us-east-1:acme:Invoice/42");
+
+        let parsed: ResourceName = assert_ok!(name.to_string().parse());
+        assert_eq!(parsed, name);
+
+        let actual: Id<Invoice, u64> = assert_ok!(parsed.to_id());
+        assert_eq!(actual, id);
+        assert_eq!(actual.label, id.label);
+    }
+
+    #[test]
+    fn test_resource_name_empty_context() {
+        let name: ResourceName = assert_ok!("srn:billing:::Invoice/a:b/c".parse());
+        assert_eq!(name.region, "");
+        assert_eq!(name.tenant, "");
+        assert_eq!(name.id, "a:b/c");
+        assert_eq!(name.to_string(), "srn:billing:::Invoice/a:b/c");
+    }
+
+    #[test]
+    fn test_resource_name_builder_rejects_unparseable_components() {
+        let id: Id<Invoice, u64> = Id::for_labeled(42);
+        assert_eq!(
+            assert_err!(ResourceName::builder("billing")
+                .region("us:east")
+                .build(&id)),
+            ResourceNameError::InvalidComponent {
+                component: "region",
+                value: "us:east".to_string(),
+                separator: ':',
+            }
+        );
+        assert_matches!(
+            ResourceName::builder("billing").tenant("a:b").build(&id),
+            Err(ResourceNameError::InvalidComponent {
+                component: "tenant",
+                ..
+            })
+        );
+        assert_matches!(
+            ResourceName::builder("bill:ing").build(&id),
+            Err(ResourceNameError::InvalidComponent {
+                component: "service",
+                ..
+            })
+        );
+        assert_eq!(
+            assert_err!(ResourceName::builder("").build(&id)),
+            ResourceNameError::MissingComponent("service")
+        );
+
+        let id: Id<Invoice, u64> = Id::direct_labeled("Invoice/v2", 42);
+        assert_eq!(
+            assert_err!(ResourceName::builder("billing").build(&id)),
+            ResourceNameError::InvalidComponent {
+                component: "label",
+                value: "Invoice/v2".to_string(),
+                separator: '/',
+            }
+        );
+
+        // ids may contain either separator, since they are the last component
+        let id: Id<Invoice, String> = Id::for_labeled("a:b/c".to_string());
+        let name = assert_ok!(ResourceName::builder("billing").build(&id));
+        assert_eq!(assert_ok!(name.to_string().parse::<ResourceName>()), name);
+    }
+
+    #[test]
+    fn test_resource_name_parse_errors() {
+        assert_eq!(
+            assert_err!("arn:billing:::Invoice/1".parse::<ResourceName>()),
+            ResourceNameError::MissingScheme("arn:billing:::Invoice/1".to_string())
+        );
+        assert_eq!(
+            assert_err!("srn::::Invoice/1".parse::<ResourceName>()),
+            ResourceNameError::MissingComponent("service")
+        );
+        assert_eq!(
+            assert_err!("srn:billing:".parse::<ResourceName>()),
+            ResourceNameError::MissingComponent("tenant")
+        );
+        assert_eq!(
+            assert_err!("srn:billing:::Invoice".parse::<ResourceName>()),
+            ResourceNameError::MissingComponent("resource")
+        );
+    }
+
+    #[test]
+    fn test_resource_name_label_mismatch() {
+        let name: ResourceName = assert_ok!("srn:billing:::Invoice/42".parse());
+        assert_eq!(
+            assert_err!(name.to_id::<Order, u64>()),
+            ResourceNameError::LabelMismatch {
+                expected: "Order".to_string(),
+                actual: "Invoice".to_string(),
+            }
+        );
+
+        let name: ResourceName = assert_ok!("srn:billing:::Invoice/forty-two".parse());
+        assert_matches!(
+            name.to_id::<Invoice, u64>(),
+            Err(ResourceNameError::InvalidId(_))
+        );
+    }
+
+    #[test]
+    fn test_resource_name_serde() {
+        let name: ResourceName = assert_ok!("srn:billing:eu:acme:Invoice/42".parse());
+        let json = assert_ok!(serde_json::to_string(&name));
+        assert_eq!(json, "\"srn:billing:eu:acme:Invoice/42\"");
+        let actual: ResourceName = assert_ok!(serde_json::from_str(&json));
+        assert_eq!(actual, name);
+    }
+}
@@ -0,0 +1,120 @@
+//! [`schemars`] support, so typed ids appear in generated JSON and OpenAPI schemas as their
+//! underlying id type, annotated with the entity label.
+
+use crate::{Id, Label, Labeling};
+use schemars::gen::SchemaGenerator;
+use schemars::schema::Schema;
+#[cfg(any(feature = "snowflake", feature = "ksuid", feature = "ulid"))]
+use schemars::schema::{InstanceType, SchemaObject};
+use schemars::JsonSchema;
+
+/// Delegates to the schema of the bare `ID`, as `Id` serializes, recording the label as the
+/// schema's title and description. Schemas are inlined rather than referenced, since ids of
+/// different entities share an `ID` type but not a label.
+impl<T: ?Sized + Label, ID: JsonSchema> JsonSchema for Id<T, ID> {
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn schema_name() -> String {
+        let labeler = <T as Label>::labeler();
+        format!("{}Id", labeler.label())
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        let mut schema = ID::json_schema(gen).into_object();
+        let labeler = <T as Label>::labeler();
+        let label = labeler.label();
+        if !label.is_empty() {
+            let metadata = schema.metadata();
+            metadata.title = Some(format!("{label} id"));
+            metadata.description = Some(format!("Identifies a `{label}`."));
+        }
+        Schema::Object(schema)
+    }
+}
+
+#[cfg(any(feature = "snowflake", feature = "ksuid", feature = "ulid"))]
+fn string_schema(format: Option<&str>) -> Schema {
+    Schema::Object(SchemaObject {
+        instance_type: Some(InstanceType::String.into()),
+        format: format.map(ToString::to_string),
+        ..SchemaObject::default()
+    })
+}
+
+#[cfg(feature = "snowflake")]
+impl JsonSchema for crate::snowflake::pretty::PrettySnowflakeId {
+    fn schema_name() -> String {
+        "PrettySnowflakeId".to_string()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        string_schema(None)
+    }
+}
+
+#[cfg(feature = "ksuid")]
+impl JsonSchema for crate::Ksuid {
+    fn schema_name() -> String {
+        "Ksuid".to_string()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        string_schema(Some("ksuid"))
+    }
+}
+
+#[cfg(feature = "ulid")]
+impl JsonSchema for crate::Ulid {
+    fn schema_name() -> String {
+        "Ulid".to_string()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        string_schema(Some("ulid"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CustomLabeling, Id, Label};
+    use pretty_assertions::assert_eq;
+    use schemars::{schema_for, JsonSchema};
+
+    struct Order;
+
+    impl Label for Order {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("Order")
+        }
+    }
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct Shipment {
+        order_id: Id<Order, u64>,
+        tracking: Id<Order, String>,
+    }
+
+    #[test]
+    fn test_id_schema_delegates_to_inner_id() {
+        let schema = serde_json::to_value(schema_for!(Shipment)).unwrap();
+        let properties = &schema["properties"];
+
+        assert_eq!(properties["order_id"]["type"], "integer");
+        assert_eq!(properties["order_id"]["format"], "uint64");
+        assert_eq!(properties["order_id"]["title"], "Order id");
+        assert_eq!(
+            properties["order_id"]["description"],
+            "Identifies a `Order`."
+        );
+        assert_eq!(properties["tracking"]["type"], "string");
+        assert!(schema.get("definitions").is_none());
+    }
+
+    #[cfg(feature = "uuid")]
+    static_assertions::assert_impl_all!(Id<Order, uuid::Uuid>: JsonSchema);
+}
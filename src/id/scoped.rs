@@ -0,0 +1,330 @@
+//! Ids of children that are only unique within their parent, such as the lines of an order.
+
+use crate::{Id, IdParseError, Label};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+/// Separates the parent and child ids in the rendered form of a [`ScopedId`].
+pub const SCOPE_SEPARATOR: char = '/';
+
+/// A child id scoped to its parent's id, rendered as `Order::123/Line::4` and stored (via serde
+/// and sqlx) in that form. Scoped ids compare and hash on both ids, parent first.
+///
+#[cfg_attr(feature = "derive", doc = "```rust")]
+#[cfg_attr(not(feature = "derive"), doc = "```ignore")]
+/// use tagid::{Id, Label, ScopedId};
+///
+/// #[derive(Label)]
+/// struct Order;
+///
+/// #[derive(Label)]
+/// #[label("Line")]
+/// struct OrderLine;
+///
+/// let order: Id<Order, u64> = Id::for_labeled(123);
+/// let line = Id::<OrderLine, u32>::for_labeled(4).within(order);
+/// assert_eq!(line.to_string(), "Order::123/Line::4");
+///
+/// let parsed: ScopedId<Order, OrderLine, u64, u32> = "Order::123/Line::4".parse().unwrap();
+/// assert_eq!(parsed, line);
+/// assert_ne!(parsed, Id::<OrderLine, u32>::for_labeled(4).within(Id::for_labeled(124)));
+/// ```
+pub struct ScopedId<P: ?Sized, C: ?Sized, PID, CID> {
+    pub parent: Id<P, PID>,
+    pub child: Id<C, CID>,
+}
+
+impl<P: ?Sized, C: ?Sized, PID, CID> ScopedId<P, C, PID, CID> {
+    pub const fn new(parent: Id<P, PID>, child: Id<C, CID>) -> Self {
+        Self { parent, child }
+    }
+}
+
+impl<C: ?Sized, CID> Id<C, CID> {
+    /// Scopes this id to its parent's.
+    pub const fn within<P: ?Sized, PID>(self, parent: Id<P, PID>) -> ScopedId<P, C, PID, CID> {
+        ScopedId::new(parent, self)
+    }
+}
+
+impl<P: ?Sized, C: ?Sized, PID: Clone, CID: Clone> Clone for ScopedId<P, C, PID, CID> {
+    fn clone(&self) -> Self {
+        Self::new(self.parent.clone(), self.child.clone())
+    }
+}
+
+impl<P, C, PID, CID> fmt::Debug for ScopedId<P, C, PID, CID>
+where
+    P: ?Sized + Label,
+    C: ?Sized + Label,
+    PID: fmt::Debug,
+    CID: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            f.debug_struct("ScopedId")
+                .field("parent", &self.parent)
+                .field("child", &self.child)
+                .finish()
+        } else {
+            write!(f, "{:?}{SCOPE_SEPARATOR}{:?}", self.parent, self.child)
+        }
+    }
+}
+
+impl<P, C, PID, CID> fmt::Display for ScopedId<P, C, PID, CID>
+where
+    P: ?Sized + Label,
+    C: ?Sized + Label,
+    PID: fmt::Display,
+    CID: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.parent.write_to(f)?;
+        write!(f, "{SCOPE_SEPARATOR}")?;
+        self.child.write_to(f)
+    }
+}
+
+/// Parses the `parent/child` form rendered by `Display`, verifying both labels. Since labeled ids
+/// may themselves contain the separator, each separator is tried in turn.
+impl<P, C, PID, CID> FromStr for ScopedId<P, C, PID, CID>
+where
+    P: ?Sized + Label,
+    C: ?Sized + Label,
+    PID: FromStr,
+    CID: FromStr,
+    <PID as FromStr>::Err: fmt::Display,
+    <CID as FromStr>::Err: fmt::Display,
+{
+    type Err = IdParseError;
+
+    fn from_str(rep: &str) -> Result<Self, Self::Err> {
+        let mut first_error = None;
+        for (pos, _) in rep.match_indices(SCOPE_SEPARATOR) {
+            let parsed = Id::from_str(&rep[..pos])
+                .and_then(|parent| Ok(Self::new(parent, Id::from_str(&rep[pos + 1..])?)));
+            match parsed {
+                Ok(scoped) => return Ok(scoped),
+                Err(error) => {
+                    first_error.get_or_insert(error);
+                }
+            }
+        }
+
+        Err(first_error.unwrap_or_else(|| {
+            IdParseError::InvalidId(format!(
+                "scoped id is missing its `{SCOPE_SEPARATOR}` separator: {rep}"
+            ))
+        }))
+    }
+}
+
+impl<P: ?Sized, C: ?Sized, PID: PartialEq, CID: PartialEq> PartialEq for ScopedId<P, C, PID, CID> {
+    fn eq(&self, other: &Self) -> bool {
+        self.parent == other.parent && self.child == other.child
+    }
+}
+
+impl<P: ?Sized, C: ?Sized, PID: Eq, CID: Eq> Eq for ScopedId<P, C, PID, CID> {}
+
+impl<P: ?Sized, C: ?Sized, PID: Ord, CID: Ord> Ord for ScopedId<P, C, PID, CID> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.parent
+            .cmp(&other.parent)
+            .then_with(|| self.child.cmp(&other.child))
+    }
+}
+
+impl<P: ?Sized, C: ?Sized, PID: PartialOrd, CID: PartialOrd> PartialOrd
+    for ScopedId<P, C, PID, CID>
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match self.parent.partial_cmp(&other.parent) {
+            Some(Ordering::Equal) => self.child.partial_cmp(&other.child),
+            ordering => ordering,
+        }
+    }
+}
+
+impl<P: ?Sized, C: ?Sized, PID: Hash, CID: Hash> Hash for ScopedId<P, C, PID, CID> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.parent.hash(state);
+        self.child.hash(state);
+    }
+}
+
+impl<P, C, PID, CID> Serialize for ScopedId<P, C, PID, CID>
+where
+    P: ?Sized + Label,
+    C: ?Sized + Label,
+    PID: fmt::Display,
+    CID: fmt::Display,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de, P, C, PID, CID> Deserialize<'de> for ScopedId<P, C, PID, CID>
+where
+    P: ?Sized + Label,
+    C: ?Sized + Label,
+    PID: FromStr,
+    CID: FromStr,
+    <PID as FromStr>::Err: fmt::Display,
+    <CID as FromStr>::Err: fmt::Display,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let rep = String::deserialize(deserializer)?;
+        Self::from_str(&rep).map_err(de::Error::custom)
+    }
+}
+
+#[cfg(feature = "sqlx")]
+impl<'q, P, C, PID, CID, DB> sqlx::Encode<'q, DB> for ScopedId<P, C, PID, CID>
+where
+    P: ?Sized + Label,
+    C: ?Sized + Label,
+    PID: fmt::Display,
+    CID: fmt::Display,
+    String: sqlx::Encode<'q, DB>,
+    DB: sqlx::Database,
+{
+    fn encode_by_ref(
+        &self,
+        buf: &mut <DB as sqlx::database::HasArguments<'q>>::ArgumentBuffer,
+    ) -> sqlx::encode::IsNull {
+        <String as sqlx::Encode<DB>>::encode(self.to_string(), buf)
+    }
+}
+
+#[cfg(feature = "sqlx")]
+impl<'r, P, C, PID, CID, DB> sqlx::Decode<'r, DB> for ScopedId<P, C, PID, CID>
+where
+    P: ?Sized + Label,
+    C: ?Sized + Label,
+    PID: FromStr,
+    CID: FromStr,
+    <PID as FromStr>::Err: fmt::Display,
+    <CID as FromStr>::Err: fmt::Display,
+    String: sqlx::Decode<'r, DB>,
+    DB: sqlx::Database,
+{
+    fn decode(
+        value: <DB as sqlx::database::HasValueRef<'r>>::ValueRef,
+    ) -> Result<Self, sqlx::error::BoxDynError> {
+        let rep = <String as sqlx::Decode<DB>>::decode(value)?;
+        Ok(Self::from_str(&rep)?)
+    }
+}
+
+#[cfg(feature = "sqlx")]
+impl<P: ?Sized, C: ?Sized, PID, CID, DB> sqlx::Type<DB> for ScopedId<P, C, PID, CID>
+where
+    String: sqlx::Type<DB>,
+    DB: sqlx::Database,
+{
+    fn type_info() -> DB::TypeInfo {
+        <String as sqlx::Type<DB>>::type_info()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CustomLabeling;
+    use claim::*;
+    use pretty_assertions::assert_eq;
+    use std::collections::HashSet;
+
+    struct Order;
+
+    impl Label for Order {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("Order")
+        }
+    }
+
+    struct Line;
+
+    impl Label for Line {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("Line")
+        }
+    }
+
+    struct Folder;
+
+    impl Label for Folder {
+        type Labeler = CustomLabeling;
+        const DELIMITER: &'static str = "/";
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("folder")
+        }
+    }
+
+    type LineId = ScopedId<Order, Line, u64, u32>;
+
+    fn line(order: u64, line: u32) -> LineId {
+        Id::for_labeled(line).within(Id::for_labeled(order))
+    }
+
+    #[test]
+    fn test_scoped_id() {
+        let id = line(123, 4);
+        assert_eq!(id.to_string(), "Order::123/Line::4");
+        assert_eq!(format!("{id:?}"), "Order::123/Line::4");
+        assert_eq!(assert_ok!(LineId::from_str("Order::123/Line::4")), id);
+
+        let ids: HashSet<_> = [line(1, 1), line(1, 2), line(2, 1), line(1, 1)].into();
+        assert_eq!(ids.len(), 3);
+        assert!(line(1, 2) < line(2, 1));
+        assert!(line(1, 1) < line(1, 2));
+
+        let json = assert_ok!(serde_json::to_string(&id));
+        assert_eq!(json, r#""Order::123/Line::4""#);
+        assert_eq!(assert_ok!(serde_json::from_str::<LineId>(&json)), id);
+
+        let nested: ScopedId<Folder, Line, String, u32> = assert_ok!("folder/docs/Line::7".parse());
+        assert_eq!(nested.parent.id, "docs");
+        assert_eq!(nested.child.id, 7);
+        assert_eq!(nested.to_string(), "folder/docs/Line::7");
+    }
+
+    #[test]
+    fn test_scoped_id_parse_errors() {
+        assert_matches!(
+            LineId::from_str("Order::123"),
+            Err(IdParseError::InvalidId(_))
+        );
+        assert_eq!(
+            assert_err!(LineId::from_str("Order::123/Item::4")),
+            IdParseError::LabelMismatch {
+                expected: "Line".to_string(),
+                actual: "Item".to_string(),
+            }
+        );
+        assert_eq!(
+            assert_err!(LineId::from_str("Invoice::123/Line::4")),
+            IdParseError::LabelMismatch {
+                expected: "Order".to_string(),
+                actual: "Invoice".to_string(),
+            }
+        );
+    }
+}
@@ -0,0 +1,117 @@
+use crate::Id;
+use std::fmt;
+use std::ops::Deref;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// Scrubs the id representation. The label identifies the type rather than the value and is left
+/// intact.
+impl<T: ?Sized, ID: Zeroize> Zeroize for Id<T, ID> {
+    fn zeroize(&mut self) {
+        self.id.zeroize();
+    }
+}
+
+/// An [`Id`] that doubles as a secret (e.g., a reset token or invite code), scrubbed from memory
+/// when dropped.
+///
+/// The id is redacted from `Debug` output; access it explicitly via [`SensitiveId::expose()`] or
+/// deref.
+pub struct SensitiveId<T: ?Sized, ID: Zeroize>(Id<T, ID>);
+
+impl<T: ?Sized, ID: Zeroize> SensitiveId<T, ID> {
+    pub const fn new(id: Id<T, ID>) -> Self {
+        Self(id)
+    }
+
+    pub const fn expose(&self) -> &Id<T, ID> {
+        &self.0
+    }
+}
+
+impl<T: ?Sized, ID: Zeroize> From<Id<T, ID>> for SensitiveId<T, ID> {
+    fn from(id: Id<T, ID>) -> Self {
+        Self::new(id)
+    }
+}
+
+impl<T: ?Sized, ID: Zeroize> Deref for SensitiveId<T, ID> {
+    type Target = Id<T, ID>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T: ?Sized, ID: Zeroize + Clone> Clone for SensitiveId<T, ID> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T: ?Sized, ID: Zeroize + PartialEq> PartialEq for SensitiveId<T, ID> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<T: ?Sized, ID: Zeroize + Eq> Eq for SensitiveId<T, ID> {}
+
+impl<T: ?Sized, ID: Zeroize> fmt::Debug for SensitiveId<T, ID> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SensitiveId({}::<redacted>)", self.0.label)
+    }
+}
+
+impl<T: ?Sized, ID: Zeroize> Zeroize for SensitiveId<T, ID> {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl<T: ?Sized, ID: Zeroize> Drop for SensitiveId<T, ID> {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl<T: ?Sized, ID: Zeroize> ZeroizeOnDrop for SensitiveId<T, ID> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CustomLabeling, Label};
+    use pretty_assertions::assert_eq;
+
+    struct ResetToken;
+
+    impl Label for ResetToken {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("ResetToken")
+        }
+    }
+
+    #[test]
+    fn test_zeroize_id() {
+        let mut id: Id<ResetToken, String> = Id::for_labeled("s3cr3t".to_string());
+        id.zeroize();
+        assert_eq!(id.id, "");
+        assert_eq!(id.label, "ResetToken");
+    }
+
+    #[test]
+    fn test_sensitive_id() {
+        let id: Id<ResetToken, String> = Id::for_labeled("s3cr3t".to_string());
+        let mut sensitive = SensitiveId::from(id.clone());
+        assert_eq!(sensitive.expose(), &id);
+        assert_eq!(sensitive.id, "s3cr3t");
+        assert_eq!(
+            format!("{sensitive:?}"),
+            "SensitiveId(ResetToken::<redacted>)"
+        );
+
+        sensitive.zeroize();
+        assert_eq!(sensitive.id, "");
+    }
+}
@@ -1,10 +1,17 @@
 use super::node::MachineNode;
-use crate::id::IdGenerator;
+use super::resolver::{default_resolver, MachineNodeResolver, ResolveError};
+use super::sequence::{self, Sequence};
+use crate::config::{ConfigError, SnowflakeConfig};
+use crate::id::{IdGenError, IdGenerator, TryIdGenerator};
+use iso8601_timestamp::Timestamp;
 use once_cell::sync::OnceCell;
-use snowflake::SnowflakeIdGenerator as SnowflakeGen;
-use std::sync::{Arc, Mutex};
-use std::time;
-use strum_macros::{Display, EnumString, EnumVariantNames, IntoStaticStr};
+use serde::{Deserialize, Serialize};
+use std::marker::PhantomData;
+use std::sync::Arc;
+use std::time::{self, Duration, SystemTime};
+use strum_macros::{Display, EnumString, IntoStaticStr, VariantNames};
+use thiserror::Error;
+use validator::Validate;
 
 static SNOWFLAKE_GENERATOR: OnceCell<SnowflakeGenerator> = OnceCell::new();
 
@@ -12,7 +19,9 @@ static SNOWFLAKE_GENERATOR: OnceCell<SnowflakeGenerator> = OnceCell::new();
 pub struct SnowflakeGenerator {
     strategy: GenerationStrategy,
     machine_node: MachineNode,
-    gen: Arc<Mutex<SnowflakeGen>>,
+    epoch: SystemTime,
+    drift_policy: ClockDriftPolicy,
+    sequence: Arc<Sequence>,
 }
 
 impl SnowflakeGenerator {
@@ -22,21 +31,147 @@ impl SnowflakeGenerator {
             .expect("SnowflakeGenerator is not initialized - initialize via single_node() or distributed().")
     }
 
+    /// The global generator, failing rather than panicking if it has not been initialized.
+    pub fn try_summon() -> Result<&'static Self, NotInitializedError> {
+        SNOWFLAKE_GENERATOR
+            .get()
+            .ok_or(NotInitializedError("SnowflakeGenerator"))
+    }
+
     pub fn single_node(strategy: GenerationStrategy) -> &'static Self {
         Self::distributed(MachineNode::default(), strategy)
     }
 
     pub fn distributed(machine_node: MachineNode, strategy: GenerationStrategy) -> &'static Self {
-        let gen = SnowflakeGen::with_epoch(
-            machine_node.machine_id,
-            machine_node.node_id,
-            time::UNIX_EPOCH,
-        );
-        SNOWFLAKE_GENERATOR.get_or_init(|| Self {
+        Self::distributed_with_epoch(machine_node, strategy, time::UNIX_EPOCH)
+    }
+
+    /// Initializes the global generator with a worker id derived at startup via the
+    /// [`default_resolver()`], for deployments (e.g., Kubernetes) where the machine node cannot be
+    /// configured statically.
+    pub fn distributed_auto(strategy: GenerationStrategy) -> Result<&'static Self, ResolveError> {
+        Self::distributed_with(&default_resolver(), strategy)
+    }
+
+    /// Initializes the global generator with the machine node derived by the `resolver`.
+    pub fn distributed_with(
+        resolver: &impl MachineNodeResolver,
+        strategy: GenerationStrategy,
+    ) -> Result<&'static Self, ResolveError> {
+        let machine_node = resolver.resolve()?;
+        Ok(Self::distributed(machine_node, strategy))
+    }
+
+    /// Initializes the global generator with a custom epoch, from which the timestamp portion of
+    /// generated ids is measured.
+    pub fn distributed_with_epoch(
+        machine_node: MachineNode,
+        strategy: GenerationStrategy,
+        epoch: SystemTime,
+    ) -> &'static Self {
+        SNOWFLAKE_GENERATOR.get_or_init(|| Self::with_epoch(machine_node, strategy, epoch))
+    }
+
+    /// Creates a standalone generator instance, independent of the global generator, so
+    /// different entity types or tenants can use distinct worker ids and strategies in the same
+    /// process. Bind entities to an instance via [`SnowflakeInstance`].
+    pub fn new(machine_node: MachineNode, strategy: GenerationStrategy) -> Self {
+        Self::with_epoch(machine_node, strategy, time::UNIX_EPOCH)
+    }
+
+    /// Creates a standalone generator instance with a custom epoch.
+    pub fn with_epoch(
+        machine_node: MachineNode,
+        strategy: GenerationStrategy,
+        epoch: SystemTime,
+    ) -> Self {
+        Self {
             machine_node,
             strategy,
-            gen: Arc::new(Mutex::new(gen)),
-        })
+            epoch,
+            drift_policy: ClockDriftPolicy::default(),
+            sequence: Arc::new(Sequence::new(machine_node, epoch)),
+        }
+    }
+
+    /// Sets how the generator responds to the system clock moving backwards.
+    #[must_use]
+    pub const fn with_drift_policy(mut self, drift_policy: ClockDriftPolicy) -> Self {
+        self.drift_policy = drift_policy;
+        self
+    }
+
+    /// Generates the next id from this generator instance. Generation is lock-free, so concurrent
+    /// callers do not contend on a mutex.
+    ///
+    /// # Panics
+    /// Panics if the clock moved backwards under the [`ClockDriftPolicy::Error`] policy; use
+    /// [`try_next_id()`](Self::try_next_id) to handle drift instead.
+    pub fn next_id(&self) -> i64 {
+        self.try_next_id()
+            .unwrap_or_else(|err| panic!("failed to generate snowflake id: {err}"))
+    }
+
+    /// Generates the next id, failing if the clock moved backwards under the
+    /// [`ClockDriftPolicy::Error`] policy.
+    pub fn try_next_id(&self) -> Result<i64, ClockDriftError> {
+        self.sequence.next_id(self.strategy, self.drift_policy)
+    }
+
+    /// Validates the configuration and initializes the global generator from it. Returns
+    /// [`ConfigError::AlreadyInitialized`] if the generator was previously initialized with a
+    /// different configuration.
+    pub fn try_init(config: &SnowflakeConfig) -> Result<&'static Self, ConfigError> {
+        config.validate()?;
+        let generator = SNOWFLAKE_GENERATOR.get_or_init(|| {
+            Self::with_epoch(config.machine_node(), config.strategy, config.epoch())
+                .with_drift_policy(config.drift_policy)
+        });
+        if !generator.matches(config) {
+            return Err(ConfigError::AlreadyInitialized("SnowflakeGenerator"));
+        }
+
+        Ok(generator)
+    }
+
+    /// Checks that [`try_init()`](Self::try_init) would succeed for the configuration, without
+    /// initializing the global generator.
+    pub fn check_init(config: &SnowflakeConfig) -> Result<(), ConfigError> {
+        config.validate()?;
+        match SNOWFLAKE_GENERATOR.get() {
+            Some(generator) if !generator.matches(config) => {
+                Err(ConfigError::AlreadyInitialized("SnowflakeGenerator"))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn matches(&self, config: &SnowflakeConfig) -> bool {
+        self.machine_node == config.machine_node()
+            && self.strategy == config.strategy
+            && self.epoch == config.epoch()
+            && self.drift_policy == config.drift_policy
+    }
+
+    pub const fn machine_node(&self) -> MachineNode {
+        self.machine_node
+    }
+
+    pub const fn strategy(&self) -> GenerationStrategy {
+        self.strategy
+    }
+
+    pub const fn epoch(&self) -> SystemTime {
+        self.epoch
+    }
+
+    pub const fn drift_policy(&self) -> ClockDriftPolicy {
+        self.drift_policy
+    }
+
+    /// When the snowflake `id` was generated by this generator, at millisecond precision.
+    pub fn created_at(&self, id: i64) -> Timestamp {
+        sequence::timestamp_of(id, self.epoch).into()
     }
 }
 
@@ -44,29 +179,229 @@ impl IdGenerator for SnowflakeGenerator {
     type IdType = i64;
 
     fn next_id_rep() -> Self::IdType {
-        let generator = Self::summon();
-        let mut gen = generator.gen.lock().unwrap();
-        match generator.strategy {
-            GenerationStrategy::RealTime => gen.real_time_generate(),
-            GenerationStrategy::Generate => gen.generate(),
-            GenerationStrategy::Lazy => gen.lazy_generate(),
-        }
+        Self::summon().next_id()
+    }
+
+    fn is_available() -> bool {
+        Self::try_summon().is_ok()
+    }
+}
+
+impl TryIdGenerator for SnowflakeGenerator {
+    fn try_next_id_rep() -> Result<Self::IdType, IdGenError> {
+        let generator = Self::try_summon()?;
+        Ok(generator.try_next_id()?)
+    }
+}
+
+/// Provides a specific [`SnowflakeGenerator`] instance, so entities can bind to it via
+/// [`SnowflakeInstanceGenerator`] rather than sharing the global generator.
+///
+/// ```rust
+/// use once_cell::sync::Lazy;
+/// use tagid::snowflake::{GenerationStrategy, SnowflakeInstance, SnowflakeInstanceGenerator};
+/// use tagid::{Entity, Label, MachineNode, SnowflakeGenerator};
+///
+/// struct TenantA;
+///
+/// impl SnowflakeInstance for TenantA {
+///     fn generator() -> &'static SnowflakeGenerator {
+///         static GENERATOR: Lazy<SnowflakeGenerator> = Lazy::new(|| {
+///             SnowflakeGenerator::new(MachineNode::new(2, 3).unwrap(), GenerationStrategy::Lazy)
+///         });
+///         &GENERATOR
+///     }
+/// }
+///
+/// #[derive(Label)]
+/// struct Order;
+///
+/// impl Entity for Order {
+///     type IdGen = SnowflakeInstanceGenerator<TenantA>;
+/// }
+///
+/// let id = Order::next_id();
+/// assert!(0 < id.id);
+/// ```
+pub trait SnowflakeInstance {
+    fn generator() -> &'static SnowflakeGenerator;
+}
+
+/// Generates snowflake ids from the generator instance provided by `I`.
+pub struct SnowflakeInstanceGenerator<I: ?Sized> {
+    marker: PhantomData<fn() -> I>,
+}
+
+impl<I: ?Sized + SnowflakeInstance> IdGenerator for SnowflakeInstanceGenerator<I> {
+    type IdType = i64;
+
+    fn next_id_rep() -> Self::IdType {
+        I::generator().next_id()
+    }
+}
+
+impl<I: ?Sized + SnowflakeInstance> TryIdGenerator for SnowflakeInstanceGenerator<I> {
+    fn try_next_id_rep() -> Result<Self::IdType, IdGenError> {
+        Ok(I::generator().try_next_id()?)
     }
 }
 
 impl PartialEq for SnowflakeGenerator {
     fn eq(&self, other: &Self) -> bool {
-        self.strategy == other.strategy && self.machine_node == other.machine_node
+        self.strategy == other.strategy
+            && self.machine_node == other.machine_node
+            && self.epoch == other.epoch
+            && self.drift_policy == other.drift_policy
     }
 }
 
 impl Eq for SnowflakeGenerator {}
 
 #[derive(
-    Debug, Copy, Clone, PartialEq, Eq, Hash, Display, IntoStaticStr, EnumString, EnumVariantNames,
+    Debug,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    Display,
+    IntoStaticStr,
+    EnumString,
+    VariantNames,
+    Serialize,
+    Deserialize,
 )]
 pub enum GenerationStrategy {
     RealTime,
     Generate,
     Lazy,
 }
+
+/// How a generator responds when the system clock moves backwards, e.g., after an NTP
+/// correction. The `Lazy` strategy never consults the clock, so is unaffected.
+#[derive(
+    Debug,
+    Default,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    Display,
+    IntoStaticStr,
+    EnumString,
+    VariantNames,
+    Serialize,
+    Deserialize,
+)]
+pub enum ClockDriftPolicy {
+    /// Block until the clock catches up to the last timestamp issued.
+    WaitForClock,
+
+    /// Fail generation with a [`ClockDriftError`].
+    Error,
+
+    /// Continue the sequence from the last timestamp issued, claiming subsequent milliseconds
+    /// ahead of the clock as the sequence is exhausted.
+    #[default]
+    BorrowSequence,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("system clock moved backwards by {}ms", .drift.as_millis())]
+pub struct ClockDriftError {
+    pub drift: Duration,
+}
+
+/// A global singleton, such as the [`SnowflakeGenerator`] or pretty id prettifier, was used
+/// before it was initialized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("{0} is not initialized")]
+pub struct NotInitializedError(pub &'static str);
+
+impl From<NotInitializedError> for IdGenError {
+    fn from(error: NotInitializedError) -> Self {
+        Self::Unavailable(error.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use once_cell::sync::Lazy;
+    use pretty_assertions::assert_eq;
+
+    /// Bits of a snowflake id holding the machine and node ids.
+    fn worker_bits(id: i64) -> (i64, i64) {
+        ((id >> 17) & 0x1f, (id >> 12) & 0x1f)
+    }
+
+    struct TenantA;
+
+    impl SnowflakeInstance for TenantA {
+        fn generator() -> &'static SnowflakeGenerator {
+            static GENERATOR: Lazy<SnowflakeGenerator> = Lazy::new(|| {
+                SnowflakeGenerator::new(MachineNode::new(2, 3).unwrap(), GenerationStrategy::Lazy)
+            });
+            &GENERATOR
+        }
+    }
+
+    struct TenantB;
+
+    impl SnowflakeInstance for TenantB {
+        fn generator() -> &'static SnowflakeGenerator {
+            static GENERATOR: Lazy<SnowflakeGenerator> = Lazy::new(|| {
+                SnowflakeGenerator::new(
+                    MachineNode::new(7, 11).unwrap(),
+                    GenerationStrategy::RealTime,
+                )
+            });
+            &GENERATOR
+        }
+    }
+
+    #[test]
+    fn test_instance_generators_are_independent() {
+        let a = SnowflakeInstanceGenerator::<TenantA>::next_id_rep();
+        let b = SnowflakeInstanceGenerator::<TenantB>::next_id_rep();
+        assert_eq!(worker_bits(a), (2, 3));
+        assert_eq!(worker_bits(b), (7, 11));
+
+        assert_eq!(TenantA::generator().strategy(), GenerationStrategy::Lazy);
+        assert_eq!(
+            TenantB::generator().strategy(),
+            GenerationStrategy::RealTime
+        );
+
+        let next = TenantA::generator().next_id();
+        assert!(a < next);
+    }
+
+    #[test]
+    fn test_created_at() {
+        struct At(SystemTime);
+
+        impl crate::clock::Clock for At {
+            fn now(&self) -> SystemTime {
+                self.0
+            }
+        }
+
+        let epoch = time::UNIX_EPOCH + Duration::from_secs(1_600_000_000);
+        let now = epoch + Duration::from_millis(69_779_798_068);
+        let _guard = crate::clock::install(At(now));
+        let generator = SnowflakeGenerator::with_epoch(
+            MachineNode::new(1, 1).unwrap(),
+            GenerationStrategy::RealTime,
+            epoch,
+        );
+        let id = generator.next_id();
+        assert_eq!(generator.created_at(id), Timestamp::from(now));
+        assert_eq!(
+            SnowflakeGenerator::new(MachineNode::default(), GenerationStrategy::RealTime)
+                .created_at(id),
+            Timestamp::from(time::UNIX_EPOCH + Duration::from_millis(69_779_798_068))
+        );
+    }
+}
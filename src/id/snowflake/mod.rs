@@ -1,6 +1,11 @@
 mod gen;
 mod node;
 pub mod pretty;
+pub mod resolver;
+mod sequence;
 
-pub use gen::{GenerationStrategy, SnowflakeGenerator};
+pub use gen::{
+    ClockDriftError, ClockDriftPolicy, GenerationStrategy, NotInitializedError, SnowflakeGenerator,
+    SnowflakeInstance, SnowflakeInstanceGenerator,
+};
 pub use node::MachineNode;
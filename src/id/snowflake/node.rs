@@ -5,7 +5,9 @@ use std::fmt;
 use validator::{Validate, ValidationErrors};
 
 /// Used to supplement the sectionalization attribute of the Snowflake algorithm in a distributed
-/// environment. The machine_id and node_id are combined to form a unique worker_id used by the
+/// environment.
+///
+/// The machine_id and node_id are combined to form a unique worker_id used by the
 /// Snowflake algorithm. This worker_id must be unique for a target identifier space (e.g.,
 /// identifier for a type of entity), otherwise identifier collisions can easily occur even in a
 /// light concurrent environment.
@@ -27,6 +29,19 @@ impl fmt::Display for MachineNode {
     }
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for MachineNode {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(
+            f,
+            "({=i32}{=str}{=i32})",
+            self.machine_id,
+            DELIMITER,
+            self.node_id
+        )
+    }
+}
+
 impl Default for MachineNode {
     fn default() -> Self {
         Self {
@@ -45,6 +60,21 @@ impl MachineNode {
         result.validate()?;
         Ok(result)
     }
+
+    /// The number of distinct worker ids, formed from 5 bits each of machine and node id.
+    pub const WORKER_ID_SPACE: u16 = 1 << 10;
+
+    /// Splits a 10-bit worker id into its machine and node ids. Higher bits are ignored.
+    pub const fn from_worker_id(worker_id: u16) -> Self {
+        Self {
+            machine_id: ((worker_id >> 5) & 0x1f) as i32,
+            node_id: (worker_id & 0x1f) as i32,
+        }
+    }
+
+    pub const fn worker_id(&self) -> u16 {
+        (((self.machine_id & 0x1f) << 5) | (self.node_id & 0x1f)) as u16
+    }
 }
 
 impl Ord for MachineNode {
@@ -1,15 +1,20 @@
 mod codec;
-mod damm;
 mod prettifier;
 
-pub use codec::{Alphabet, AlphabetCodec, Codec, BASE_23};
+pub use codec::{
+    Alphabet, AlphabetCodec, Base58Codec, Codec, CodecError, Proquint, BASE_23, BASE_58,
+};
 pub use prettifier::{ConversionError, IdPrettifier};
 
-use crate::id::IdGenerator;
+use crate::checksum::{Checksum, Damm};
+use crate::id::snowflake::NotInitializedError;
+use crate::id::{IdGenError, IdGenerator, TryIdGenerator};
 use crate::SnowflakeGenerator;
 use serde::{Deserialize, Serialize};
 use smol_str::SmolStr;
 use std::fmt;
+use std::marker::PhantomData;
+use std::str::FromStr;
 
 #[derive(Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 #[serde(transparent)]
@@ -18,8 +23,51 @@ pub struct PrettySnowflakeId(SmolStr);
 
 impl PrettySnowflakeId {
     pub fn from_snowflake(snowflake: i64) -> Self {
-        let pretty_id = encoder().prettify(snowflake);
-        Self(pretty_id.into())
+        Self::from_snowflake_with(snowflake, encoder())
+    }
+
+    /// Prettifies the snowflake with the global prettifier, failing if it is not initialized.
+    pub fn try_from_snowflake(snowflake: i64) -> Result<Self, NotInitializedError> {
+        let prettifier = IdPrettifier::<AlphabetCodec>::try_summon()?;
+        Ok(Self::from_snowflake_with(snowflake, prettifier))
+    }
+
+    /// Recovers the snowflake via the global prettifier, failing rather than panicking if the
+    /// prettifier is not initialized or the id is invalid.
+    pub fn try_to_snowflake(&self) -> Result<i64, ConversionError> {
+        self.to_snowflake_with(IdPrettifier::<AlphabetCodec>::try_summon()?)
+    }
+
+    /// Prettifies the snowflake with the given prettifier rather than the global one.
+    pub fn from_snowflake_with<C: Codec, K: Checksum>(
+        snowflake: i64,
+        prettifier: &IdPrettifier<C, K>,
+    ) -> Self {
+        Self(prettifier.prettify(snowflake).into())
+    }
+
+    /// Recovers the snowflake from an id prettified by the given prettifier.
+    pub fn to_snowflake_with<C: Codec, K: Checksum>(
+        &self,
+        prettifier: &IdPrettifier<C, K>,
+    ) -> Result<i64, ConversionError> {
+        prettifier.to_id_seed(&self.0)
+    }
+
+    /// Parses and validates a pretty id produced by the given prettifier, normalizing its letter
+    /// case and format.
+    pub fn parse_with<C: Codec, K: Checksum>(
+        rep: &str,
+        prettifier: &IdPrettifier<C, K>,
+    ) -> Result<Self, ConversionError> {
+        let normalized = prettifier.encoder.normalize(rep.trim());
+        let snowflake = prettifier.to_id_seed(&normalized)?;
+        Ok(Self::from_snowflake_with(snowflake, prettifier))
+    }
+
+    /// Wraps an already prettified representation, as deserialization does.
+    pub(crate) const fn from_rep(rep: SmolStr) -> Self {
+        Self(rep)
     }
 }
 
@@ -58,6 +106,23 @@ impl std::ops::Deref for PrettySnowflakeId {
     }
 }
 
+/// Parses a pretty id via the global prettifier, verifying its checksum.
+impl FromStr for PrettySnowflakeId {
+    type Err = ConversionError;
+
+    fn from_str(rep: &str) -> Result<Self, Self::Err> {
+        Self::parse_with(rep, IdPrettifier::<AlphabetCodec>::try_summon()?)
+    }
+}
+
+impl TryFrom<&str> for PrettySnowflakeId {
+    type Error = ConversionError;
+
+    fn try_from(rep: &str) -> Result<Self, Self::Error> {
+        Self::from_str(rep)
+    }
+}
+
 impl From<PrettySnowflakeId> for String {
     fn from(id: PrettySnowflakeId) -> Self {
         id.0.to_string()
@@ -72,14 +137,207 @@ impl From<PrettySnowflakeId> for i64 {
     }
 }
 
+/// Stores the underlying snowflake in a BIGINT column, converting via the global prettifier.
+///
+/// # Panics
+/// Encoding panics if the id cannot be converted into a snowflake, since sqlx encoding is
+/// infallible; ids parsed via [`FromStr`] or generated by the global format always convert.
+#[cfg(feature = "sqlx")]
+impl<'q, DB> sqlx::Encode<'q, DB> for PrettySnowflakeId
+where
+    i64: sqlx::Encode<'q, DB>,
+    DB: sqlx::Database,
+{
+    fn encode_by_ref(
+        &self,
+        buf: &mut <DB as sqlx::database::HasArguments<'q>>::ArgumentBuffer,
+    ) -> sqlx::encode::IsNull {
+        let snowflake = self.try_to_snowflake().unwrap_or_else(|err| {
+            panic!("failed to convert pretty id {self} into snowflake: {err}")
+        });
+        <i64 as sqlx::Encode<DB>>::encode(snowflake, buf)
+    }
+}
+
+#[cfg(feature = "sqlx")]
+impl<'r, DB> sqlx::Decode<'r, DB> for PrettySnowflakeId
+where
+    i64: sqlx::Decode<'r, DB>,
+    DB: sqlx::Database,
+{
+    fn decode(
+        value: <DB as sqlx::database::HasValueRef<'r>>::ValueRef,
+    ) -> Result<Self, sqlx::error::BoxDynError> {
+        let snowflake = <i64 as sqlx::Decode<DB>>::decode(value)?;
+        Ok(Self::try_from_snowflake(snowflake)?)
+    }
+}
+
+#[cfg(feature = "sqlx")]
+impl<DB> sqlx::Type<DB> for PrettySnowflakeId
+where
+    i64: sqlx::Type<DB>,
+    DB: sqlx::Database,
+{
+    fn type_info() -> DB::TypeInfo {
+        <i64 as sqlx::Type<DB>>::type_info()
+    }
+
+    fn compatible(ty: &DB::TypeInfo) -> bool {
+        <i64 as sqlx::Type<DB>>::compatible(ty)
+    }
+}
+
+/// The [`IdPrettifier`] a [`PrettySnowflakeGenerator`] formats its ids with, so entities in the
+/// same binary can use different pretty id formats.
+///
+/// ```rust
+/// use once_cell::sync::Lazy;
+/// use tagid::snowflake::pretty::{AlphabetCodec, IdPrettifier, PrettyFormat, PrettySnowflakeGenerator};
+/// use tagid::checksum::Damm;
+/// use tagid::snowflake::{GenerationStrategy, NotInitializedError};
+/// use tagid::{Entity, Label, SnowflakeGenerator};
+///
+/// struct InvoiceFormat;
+///
+/// impl PrettyFormat for InvoiceFormat {
+///     type Codec = AlphabetCodec;
+///     type Checksum = Damm;
+///
+///     fn try_prettifier() -> Result<&'static IdPrettifier<AlphabetCodec>, NotInitializedError> {
+///         static PRETTIFIER: Lazy<IdPrettifier<AlphabetCodec>> =
+///             Lazy::new(|| IdPrettifier::default().with_parts_size(8));
+///         Ok(&PRETTIFIER)
+///     }
+/// }
+///
+/// #[derive(Label)]
+/// struct Invoice;
+///
+/// impl Entity for Invoice {
+///     type IdGen = PrettySnowflakeGenerator<InvoiceFormat>;
+/// }
+///
+/// SnowflakeGenerator::single_node(GenerationStrategy::RealTime);
+/// let id = Invoice::next_id();
+/// assert_eq!(id.id.split('-').last().unwrap().len(), 8);
+/// let snowflake = id.id.to_snowflake_with(InvoiceFormat::prettifier()).unwrap();
+/// assert!(0 < snowflake);
+/// ```
+pub trait PrettyFormat {
+    type Codec: Codec + 'static;
+    type Checksum: Checksum + 'static;
+
+    fn try_prettifier(
+    ) -> Result<&'static IdPrettifier<Self::Codec, Self::Checksum>, NotInitializedError>;
+
+    fn prettifier() -> &'static IdPrettifier<Self::Codec, Self::Checksum> {
+        Self::try_prettifier().unwrap_or_else(|err| panic!("{err}"))
+    }
+}
+
+/// Formats ids with the global prettifier, initialized via [`IdPrettifier::global_initialize()`].
 #[derive(Debug, Clone, Copy)]
-pub struct PrettySnowflakeGenerator;
+pub struct GlobalPrettyFormat;
 
-impl IdGenerator for PrettySnowflakeGenerator {
+impl PrettyFormat for GlobalPrettyFormat {
+    type Codec = AlphabetCodec;
+    type Checksum = Damm;
+
+    fn try_prettifier() -> Result<&'static IdPrettifier<AlphabetCodec>, NotInitializedError> {
+        IdPrettifier::<AlphabetCodec>::try_summon()
+    }
+
+    fn prettifier() -> &'static IdPrettifier<AlphabetCodec> {
+        encoder()
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct PrettySnowflakeGenerator<F: PrettyFormat = GlobalPrettyFormat> {
+    marker: PhantomData<fn() -> F>,
+}
+
+impl<F: PrettyFormat> IdGenerator for PrettySnowflakeGenerator<F> {
     type IdType = PrettySnowflakeId;
 
     fn next_id_rep() -> Self::IdType {
         let snowflake = SnowflakeGenerator::next_id_rep();
-        PrettySnowflakeId::from_snowflake(snowflake)
+        PrettySnowflakeId::from_snowflake_with(snowflake, F::prettifier())
+    }
+
+    fn is_available() -> bool {
+        SnowflakeGenerator::is_available() && F::try_prettifier().is_ok()
+    }
+}
+
+impl<F: PrettyFormat> TryIdGenerator for PrettySnowflakeGenerator<F> {
+    fn try_next_id_rep() -> Result<Self::IdType, IdGenError> {
+        let prettifier = F::try_prettifier()?;
+        let snowflake = SnowflakeGenerator::try_next_id_rep()?;
+        Ok(PrettySnowflakeId::from_snowflake_with(
+            snowflake, prettifier,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::checksum::Luhn;
+    use claim::*;
+
+    struct UninitializedFormat;
+
+    impl PrettyFormat for UninitializedFormat {
+        type Codec = Base58Codec;
+        type Checksum = Luhn;
+
+        fn try_prettifier() -> Result<&'static IdPrettifier<Base58Codec, Luhn>, NotInitializedError>
+        {
+            Err(NotInitializedError("UninitializedFormat"))
+        }
+    }
+
+    #[test]
+    fn test_parse_pretty_snowflake_id() {
+        let prettifier = IdPrettifier::<AlphabetCodec>::default();
+        let id = PrettySnowflakeId::from_snowflake_with(824227036833910784, &prettifier);
+        assert_eq!(id.as_ref(), "ARPJ-27036-GVQS-07849");
+
+        for rep in [
+            "ARPJ-27036-GVQS-07849",
+            "arpj-27036-gvqs-07849",
+            " ArPj-27036-GVQS-07849\n",
+        ] {
+            let parsed = assert_ok!(PrettySnowflakeId::parse_with(rep, &prettifier));
+            assert_eq!(parsed, id);
+        }
+
+        assert_matches!(
+            PrettySnowflakeId::parse_with("ARPJ-27036-GVQS-07848", &prettifier),
+            Err(ConversionError::InvalidId(_))
+        );
+        assert_matches!(
+            PrettySnowflakeId::parse_with("AR0J-27036-GVQS-07849", &prettifier),
+            Err(ConversionError::Codec(_))
+        );
+        assert_err!(PrettySnowflakeId::parse_with("", &prettifier));
+
+        let base58 = IdPrettifier::<Base58Codec>::default();
+        let id = PrettySnowflakeId::from_snowflake_with(824227036833910784, &base58);
+        let lowercase = id.to_lowercase();
+        assert_eq!(assert_ok!(PrettySnowflakeId::parse_with(&id, &base58)), id);
+        assert_err!(PrettySnowflakeId::parse_with(&lowercase, &base58));
+    }
+
+    #[test]
+    fn test_uninitialized_format_fails_gracefully() {
+        type Generator = PrettySnowflakeGenerator<UninitializedFormat>;
+        assert!(!<Generator as IdGenerator>::is_available());
+        assert_matches!(
+            <Generator as TryIdGenerator>::try_next_id_rep(),
+            Err(IdGenError::Unavailable("UninitializedFormat"))
+        );
     }
 }
@@ -1,11 +1,43 @@
+use itertools::Itertools;
 use once_cell::sync::Lazy;
 use tailcall::tailcall;
+use thiserror::Error;
 
 pub static BASE_23: Lazy<Alphabet> = Lazy::new(|| Alphabet::new("ABCDEFGHJKLMNPQRSTUVXYZ"));
 
+/// The Bitcoin base58 alphabet, which omits the easily confused `0`, `O`, `I` and `l`.
+pub static BASE_58: Lazy<Alphabet> =
+    Lazy::new(|| Alphabet::new("123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz"));
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum CodecError {
+    #[error("cannot decode an empty representation")]
+    Empty,
+
+    #[error("character `{0}` is not in the codec alphabet")]
+    InvalidCharacter(char),
+
+    #[error("position {position} is out of range for an alphabet of {base} characters")]
+    OutOfRange { position: usize, base: usize },
+
+    #[error("`{0}` overflows an i64")]
+    Overflow(String),
+
+    #[error("`{0}` is not a proquint word")]
+    InvalidWord(String),
+}
+
 pub trait Codec {
+    /// Encodes a non-negative number.
     fn encode(&self, number: i64) -> String;
-    fn decode(&self, rep: &str) -> i64;
+
+    /// Decodes an encoded representation, which may come from untrusted input.
+    fn decode(&self, rep: &str) -> Result<i64, CodecError>;
+
+    /// Normalizes user input, such as its letter case, into the encoded form.
+    fn normalize(&self, rep: &str) -> String {
+        rep.to_string()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -21,46 +53,176 @@ impl AlphabetCodec {
     pub const fn new(alphabet: Alphabet) -> Self {
         Self(alphabet)
     }
+
+    pub const fn alphabet(&self) -> &Alphabet {
+        &self.0
+    }
 }
 
-#[derive(Debug, Default)]
-struct ResultWithIndex {
-    pub result: i64,
-    pub pos: usize,
+impl Codec for AlphabetCodec {
+    fn encode(&self, number: i64) -> String {
+        do_encode(&self.0, number, String::default())
+    }
+
+    fn decode(&self, rep: &str) -> Result<i64, CodecError> {
+        do_decode(&self.0, rep)
+    }
+
+    fn normalize(&self, rep: &str) -> String {
+        self.0.normalize_case(rep)
+    }
 }
 
-impl ResultWithIndex {
-    pub const fn increment_w_result(self, result: i64) -> Self {
-        Self {
-            result,
-            pos: self.pos + 1,
-        }
+/// Encodes with the Bitcoin [`BASE_58`] alphabet, producing shorter parts than the default
+/// [`BASE_23`] codec.
+///
+/// Encoded values of equal length sort in the same order as the numbers they represent; shorter
+/// values encode smaller numbers but may sort after longer ones, e.g., `"z"` (57) after `"21"`
+/// (58). Pad to a fixed width, as [`IdPrettifier`] does with leading zeros, to keep the order.
+///
+/// [`IdPrettifier`]: crate::snowflake::pretty::IdPrettifier
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Base58Codec;
+
+impl Base58Codec {
+    pub fn alphabet(&self) -> &'static Alphabet {
+        &BASE_58
     }
 }
 
-impl Codec for AlphabetCodec {
+impl Codec for Base58Codec {
+    /// # Panics
+    ///
+    /// Panics if `number` is negative.
     fn encode(&self, number: i64) -> String {
-        do_encode(&self.0, number, String::default())
+        assert!(
+            0 <= number,
+            "Base58Codec cannot encode negative number {number}"
+        );
+        do_encode(&BASE_58, number, String::default())
     }
 
-    fn decode(&self, rep: &str) -> i64 {
-        rep.chars()
-            .rev()
-            .fold(ResultWithIndex::default(), |acc, c| {
-                let encoded_part = self.0.index_of(c) as i64;
-                let base_placement = (self.0.base as i64).pow(acc.pos as u32);
-                let acc_inc = encoded_part + base_placement;
-                let new_acc = acc.result + acc_inc;
-                acc.increment_w_result(new_acc)
+    fn decode(&self, rep: &str) -> Result<i64, CodecError> {
+        do_decode(&BASE_58, rep)
+    }
+}
+
+const PROQUINT_CONSONANTS: &[u8; 16] = b"bdfghjklmnprstvz";
+const PROQUINT_VOWELS: &[u8; 4] = b"aiou";
+const PROQUINT_WORD_LEN: usize = 5;
+const PROQUINT_DELIMITER: char = '-';
+
+/// Encodes numbers as pronounceable [proquint](https://arxiv.org/html/0901.4016) words, each
+/// holding 16 bits; e.g., `lusab-babad`. Leading zero words are omitted.
+///
+/// Words are joined by `-` and encoded values vary in length, so in an [`IdPrettifier`] use a
+/// different delimiter and disable leading zeros.
+///
+/// ```rust
+/// use tagid::snowflake::pretty::{Codec, IdPrettifier, Proquint};
+///
+/// assert_eq!(Proquint.encode(0x7f00_0001), "lusab-babad");
+/// assert_eq!(Proquint.decode("lusab-babad").unwrap(), 0x7f00_0001);
+///
+/// let prettifier = IdPrettifier {
+///     delimiter: " ".to_string(),
+///     leading_zeros: false,
+///     ..IdPrettifier::<Proquint>::default()
+/// };
+/// let pretty_id = prettifier.prettify(824227036833910784);
+/// assert_eq!(prettifier.to_id_seed(&pretty_id).unwrap(), 824227036833910784);
+/// ```
+///
+/// [`IdPrettifier`]: crate::snowflake::pretty::IdPrettifier
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Proquint;
+
+impl Proquint {
+    fn encode_word(bits: u16) -> String {
+        let bits = usize::from(bits);
+        [
+            PROQUINT_CONSONANTS[(bits >> 12) & 0xf],
+            PROQUINT_VOWELS[(bits >> 10) & 0x3],
+            PROQUINT_CONSONANTS[(bits >> 6) & 0xf],
+            PROQUINT_VOWELS[(bits >> 4) & 0x3],
+            PROQUINT_CONSONANTS[bits & 0xf],
+        ]
+        .iter()
+        .map(|c| char::from(*c))
+        .collect()
+    }
+
+    fn decode_word(word: &str) -> Result<u64, CodecError> {
+        let invalid = || CodecError::InvalidWord(word.to_string());
+        if word.len() != PROQUINT_WORD_LEN {
+            return Err(invalid());
+        }
+
+        word.bytes()
+            .map(|c| c.to_ascii_lowercase())
+            .enumerate()
+            .try_fold(0_u64, |acc, (position, c)| {
+                let (letters, bits): (&[u8], _) = if position % 2 == 0 {
+                    (PROQUINT_CONSONANTS, 4)
+                } else {
+                    (PROQUINT_VOWELS, 2)
+                };
+                let value = letters.iter().position(|l| *l == c).ok_or_else(invalid)?;
+                Ok((acc << bits) | value as u64)
             })
-            .result
     }
 }
 
+impl Codec for Proquint {
+    fn encode(&self, number: i64) -> String {
+        let bits = number as u64;
+        let words = (bits.checked_ilog2().unwrap_or(0) / 16 + 1) as usize;
+        (0..words)
+            .rev()
+            .map(|word| Self::encode_word((bits >> (word * 16)) as u16))
+            .join(&PROQUINT_DELIMITER.to_string())
+    }
+
+    fn decode(&self, rep: &str) -> Result<i64, CodecError> {
+        if rep.is_empty() {
+            return Err(CodecError::Empty);
+        }
+
+        let words: Vec<&str> = rep.split(PROQUINT_DELIMITER).collect();
+        if 4 < words.len() {
+            return Err(CodecError::Overflow(rep.to_string()));
+        }
+
+        let bits = words.into_iter().try_fold(0_u64, |acc, word| {
+            Ok::<_, CodecError>((acc << 16) | Self::decode_word(word)?)
+        })?;
+        Ok(bits as i64)
+    }
+
+    fn normalize(&self, rep: &str) -> String {
+        rep.to_lowercase()
+    }
+}
+
+fn do_decode(alphabet: &Alphabet, rep: &str) -> Result<i64, CodecError> {
+    if rep.is_empty() {
+        return Err(CodecError::Empty);
+    }
+
+    rep.chars().try_fold(0_i64, |acc, c| {
+        let digit = alphabet.index_of(c)? as i64;
+        acc.checked_mul(alphabet.base as i64)
+            .and_then(|acc| acc.checked_add(digit))
+            .ok_or_else(|| CodecError::Overflow(rep.to_string()))
+    })
+}
+
 #[tailcall]
 fn do_encode(alphabet: &Alphabet, number: i64, mut acc: String) -> String {
     let modulo = (number % alphabet.base as i64) as usize;
-    let part = alphabet.value_of(modulo);
+    let part = alphabet
+        .value_of(modulo)
+        .expect("remainder of a non-negative number is within the alphabet");
     acc.insert(0, part);
     if number < alphabet.base as i64 {
         acc
@@ -82,15 +244,144 @@ impl Alphabet {
         Self { elements, base }
     }
 
-    pub fn value_of(&self, pos: usize) -> char {
+    pub fn value_of(&self, pos: usize) -> Result<char, CodecError> {
         self.elements
             .chars()
             .nth(pos)
-            .expect("failed on attempted pretty id codec out-of-bounds access.")
+            .ok_or(CodecError::OutOfRange {
+                position: pos,
+                base: self.base,
+            })
+    }
+
+    /// Converts `rep` to the letter case of the alphabet, unless the alphabet mixes cases.
+    pub fn normalize_case(&self, rep: &str) -> String {
+        let has_upper = self.elements.chars().any(char::is_uppercase);
+        let has_lower = self.elements.chars().any(char::is_lowercase);
+        match (has_upper, has_lower) {
+            (true, false) => rep.to_uppercase(),
+            (false, true) => rep.to_lowercase(),
+            _ => rep.to_string(),
+        }
+    }
+
+    pub fn index_of(&self, c: char) -> Result<usize, CodecError> {
+        self.elements
+            .chars()
+            .position(|a| a == c)
+            .ok_or(CodecError::InvalidCharacter(c))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use claim::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_alphabet_codec_roundtrip() {
+        let codec = AlphabetCodec::default();
+        assert_eq!(codec.encode(0), "A");
+        assert_eq!(codec.encode(23), "BA");
+        assert_eq!(assert_ok!(codec.decode("BA")), 23);
+        assert_eq!(assert_ok!(codec.decode("ARPJ")), 8242);
+
+        for number in [0, 1, 22, 23, 99_999, i64::MAX] {
+            assert_eq!(assert_ok!(codec.decode(&codec.encode(number))), number);
+        }
+    }
+
+    #[test]
+    fn test_proquint_codec() {
+        // examples from the proquint proposal, encoding IPv4 addresses
+        assert_eq!(Proquint.encode(0x7f00_0001), "lusab-babad");
+        assert_eq!(Proquint.encode(0x3f54_dcc1), "gutih-tugad");
+        assert_eq!(Proquint.encode(0), "babab");
+        assert_eq!(Proquint.encode(0xffff), "zuzuz");
+        assert_eq!(Proquint.encode(0x1_0000), "babad-babab");
+        assert_eq!(Proquint.encode(-1), "zuzuz-zuzuz-zuzuz-zuzuz");
+
+        for number in [
+            0,
+            1,
+            0xffff,
+            824_227_036_833_910_784,
+            i64::MAX,
+            i64::MIN,
+            -1,
+        ] {
+            assert_eq!(
+                assert_ok!(Proquint.decode(&Proquint.encode(number))),
+                number
+            );
+        }
+        assert_eq!(
+            assert_ok!(Proquint.decode("babab-lusab-babad")),
+            0x7f00_0001
+        );
+
+        assert_eq!(
+            assert_err!(Proquint.decode("lusab-baba")),
+            CodecError::InvalidWord("baba".to_string())
+        );
+        assert_eq!(
+            assert_err!(Proquint.decode("lusab-babed")),
+            CodecError::InvalidWord("babed".to_string())
+        );
+        assert_matches!(
+            Proquint.decode("babab-babab-babab-babab-babab"),
+            Err(CodecError::Overflow(_))
+        );
+        assert_eq!(Proquint.normalize("LUSAB-BABAD"), "lusab-babad");
+    }
+
+    #[test]
+    fn test_codec_decode_errors() {
+        let codec = AlphabetCodec::default();
+        assert_eq!(assert_err!(codec.decode("")), CodecError::Empty);
+        assert_eq!(
+            assert_err!(codec.decode("AB0")),
+            CodecError::InvalidCharacter('0')
+        );
+        assert_eq!(
+            assert_err!(Base58Codec.decode("NQm6nKp8qFD")),
+            CodecError::Overflow("NQm6nKp8qFD".to_string())
+        );
+        assert_eq!(
+            assert_err!(BASE_23.value_of(23)),
+            CodecError::OutOfRange {
+                position: 23,
+                base: 23
+            }
+        );
+    }
+
+    #[test]
+    fn test_base58_codec() {
+        let codec = Base58Codec;
+        assert_eq!(codec.alphabet().base, 58);
+        assert_eq!(codec.encode(0), "1");
+        assert_eq!(codec.encode(57), "z");
+        assert_eq!(codec.encode(58), "21");
+        assert_eq!(codec.encode(i64::MAX), "NQm6nKp8qFC");
+
+        for number in [0, 1, 57, 58, 3_364, 824_227_036_833_910_784, i64::MAX] {
+            assert_eq!(assert_ok!(codec.decode(&codec.encode(number))), number);
+        }
+
+        let mut encoded: Vec<String> = [3, 30, 58, 999, 1_000_000]
+            .into_iter()
+            .map(|number| codec.encode(number))
+            .collect();
+        let in_order = encoded.clone();
+        encoded.sort_by_key(|e| (e.len(), e.clone()));
+        assert_eq!(encoded, in_order);
     }
 
-    pub fn index_of(&self, c: char) -> usize {
-        let pos = self.elements.chars().position(|a| a == c);
-        pos.expect("failed to pretty id character in alphabet")
+    #[test]
+    #[should_panic(expected = "cannot encode negative number -1")]
+    fn test_base58_codec_rejects_negative_numbers() {
+        let _ = Base58Codec.encode(-1);
     }
 }
@@ -1,10 +1,13 @@
-use super::codec::Codec;
-use super::damm;
+use super::codec::{Codec, CodecError};
+use crate::checksum::{Checksum, Damm};
+use crate::config::{ConfigError, PrettifierConfig};
 use crate::id::snowflake::pretty::codec::{Alphabet, AlphabetCodec};
+use crate::id::snowflake::NotInitializedError;
 use itertools::Itertools;
 use once_cell::sync::OnceCell;
 use std::str::FromStr;
 use thiserror::Error;
+use validator::Validate;
 
 #[derive(Debug, Error)]
 pub enum ConversionError {
@@ -13,9 +16,16 @@ pub enum ConversionError {
 
     #[error("{0}")]
     ParseIntError(#[from] std::num::ParseIntError),
+
+    #[error("Not a valid ID: {0}")]
+    Codec(#[from] CodecError),
+
+    #[error("{0}")]
+    NotInitialized(#[from] NotInitializedError),
 }
 
 /// It makes Long ids more readable and user friendly, it also adds checksum.
+///
 /// Params:
 /// encoder – it the result needs to be monotonic, use monotonic Coded e.g. AlphabetCoded with
 ///     alphabet where char values are monotonic
@@ -23,9 +33,11 @@ pub enum ConversionError {
 ///     are encoded with codec)
 /// delimiter – sign between parts
 /// leadingZeros – prettifier will make id with constant length
+/// checksum – the check digit algorithm, [`Damm`] by default
 #[derive(Debug, Clone)]
-pub struct IdPrettifier<C: Codec> {
+pub struct IdPrettifier<C: Codec, K: Checksum = Damm> {
     pub encoder: C,
+    pub checksum: K,
     pub parts_size: usize,
     pub delimiter: String,
     pub leading_zeros: bool,
@@ -33,6 +45,10 @@ pub struct IdPrettifier<C: Codec> {
     pub max_encoder_length: usize,
 }
 
+/// The number of digits in the largest id, plus its check digit.
+const I64_DIGITS: usize = 20;
+const U128_DIGITS: usize = 40;
+
 static PRETTIFIER: OnceCell<IdPrettifier<AlphabetCodec>> = OnceCell::new();
 
 impl IdPrettifier<AlphabetCodec> {
@@ -43,11 +59,65 @@ impl IdPrettifier<AlphabetCodec> {
             .expect("Alphabetic prettifier is not initialized - initialize via IdPrettifier::<AlphabetCodec>::global_initialize()")
     }
 
+    /// The global prettifier, failing rather than panicking if it has not been initialized.
+    pub fn try_summon() -> Result<&'static Self, NotInitializedError> {
+        PRETTIFIER.get().ok_or(NotInitializedError("IdPrettifier"))
+    }
+
     #[allow(dead_code)]
     pub fn global_initialize(alphabet: Alphabet) -> &'static Self {
         PRETTIFIER.get_or_init(|| Self::from_alphabet(alphabet))
     }
 
+    /// Initializes the global prettifier with a fully configured instance. As with
+    /// `global_initialize()`, the first initialization wins.
+    pub fn global_initialize_with(prettifier: Self) -> &'static Self {
+        PRETTIFIER.get_or_init(|| prettifier)
+    }
+
+    /// Validates the configuration and initializes the global prettifier from it. Returns
+    /// [`ConfigError::AlreadyInitialized`] if the prettifier was previously initialized with a
+    /// different configuration.
+    pub fn try_init(config: &PrettifierConfig) -> Result<&'static Self, ConfigError> {
+        config.validate()?;
+        let prettifier = Self::global_initialize_with(Self::from_config(config));
+        if !prettifier.matches(config) {
+            return Err(ConfigError::AlreadyInitialized("IdPrettifier"));
+        }
+
+        Ok(prettifier)
+    }
+
+    /// Checks that [`try_init()`](Self::try_init) would succeed for the configuration, without
+    /// initializing the global prettifier.
+    pub fn check_init(config: &PrettifierConfig) -> Result<(), ConfigError> {
+        config.validate()?;
+        match PRETTIFIER.get() {
+            Some(prettifier) if !prettifier.matches(config) => {
+                Err(ConfigError::AlreadyInitialized("IdPrettifier"))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn matches(&self, config: &PrettifierConfig) -> bool {
+        self.encoder.alphabet().elements == config.alphabet
+            && self.parts_size == config.parts_size
+            && self.delimiter == config.delimiter
+            && self.leading_zeros == config.leading_zeros
+    }
+
+    /// Creates a prettifier from the configuration, which is expected to be valid.
+    pub fn from_config(config: &PrettifierConfig) -> Self {
+        let prettifier = Self::from_alphabet(Alphabet::new(config.alphabet.as_str()))
+            .with_parts_size(config.parts_size);
+        Self {
+            delimiter: config.delimiter.clone(),
+            leading_zeros: config.leading_zeros,
+            ..prettifier
+        }
+    }
+
     #[allow(dead_code)]
     pub fn from_alphabet(alphabet: Alphabet) -> Self {
         let encoder = AlphabetCodec::new(alphabet);
@@ -61,6 +131,7 @@ impl IdPrettifier<AlphabetCodec> {
 
         Self {
             encoder,
+            checksum: Damm,
             parts_size,
             delimiter: '-'.to_string(),
             leading_zeros: true,
@@ -70,7 +141,7 @@ impl IdPrettifier<AlphabetCodec> {
     }
 }
 
-impl<C: Codec + Default> Default for IdPrettifier<C> {
+impl<C: Codec + Default, K: Checksum + Default> Default for IdPrettifier<C, K> {
     fn default() -> Self {
         let encoder = C::default();
         let parts_size: usize = 5;
@@ -82,6 +153,7 @@ impl<C: Codec + Default> Default for IdPrettifier<C> {
         let max_encoder_length = encoder.encode(10_i64.pow(parts_size as u32) - 1_i64).len();
         Self {
             encoder,
+            checksum: K::default(),
             parts_size,
             delimiter: '-'.to_string(),
             leading_zeros: true,
@@ -91,24 +163,68 @@ impl<C: Codec + Default> Default for IdPrettifier<C> {
     }
 }
 
-impl<C: Codec> IdPrettifier<C> {
+impl<C: Codec, K: Checksum> IdPrettifier<C, K> {
+    /// Changes the check digit algorithm.
+    pub fn with_checksum<K2: Checksum>(self, checksum: K2) -> IdPrettifier<C, K2> {
+        IdPrettifier {
+            encoder: self.encoder,
+            checksum,
+            parts_size: self.parts_size,
+            delimiter: self.delimiter,
+            leading_zeros: self.leading_zeros,
+            zero_char: self.zero_char,
+            max_encoder_length: self.max_encoder_length,
+        }
+    }
+
+    /// Changes the length of the parts the id is chopped into, recalculating the encoded part
+    /// length accordingly.
+    pub fn with_parts_size(self, parts_size: usize) -> Self {
+        let max_encoder_length = self
+            .encoder
+            .encode(10_i64.pow(parts_size as u32) - 1_i64)
+            .len();
+        Self {
+            parts_size,
+            max_encoder_length,
+            ..self
+        }
+    }
+
     pub fn prettify(&self, id_seed: i64) -> String {
-        let id_rep = id_seed.to_string();
-        let parts = self.divide(damm::encode(id_rep.as_str()));
-        let parts_to_convert =
-            self.convert_with_leading_zeros(parts, |item| self.add_leading_zeros_parts(item));
+        self.prettify_rep(&id_seed.to_string(), I64_DIGITS)
+    }
+
+    /// Prettifies a 128-bit id, such as a UUID or ULID (via `as_u128()` or `to_u128()`), into
+    /// the same grouped, check-digit-protected form as snowflakes.
+    pub fn prettify_u128(&self, id_seed: u128) -> String {
+        self.prettify_rep(&id_seed.to_string(), U128_DIGITS)
+    }
+
+    fn prettify_rep(&self, id_rep: &str, max_digits: usize) -> String {
+        let parts = self.divide(self.checksum.encode(id_rep));
+        let parts_to_convert = self.convert_with_leading_zeros(parts, |item| {
+            self.add_leading_zeros_parts(item, max_digits)
+        });
         self.convert_parts(parts_to_convert)
     }
 
     #[allow(dead_code)]
     pub fn is_valid(&self, id: &str) -> bool {
-        damm::is_valid(self.decode_seed_with_check_digit(id).as_str())
+        self.decode_seed_with_check_digit(id)
+            .is_ok_and(|decoded| self.checksum.is_valid(&decoded))
     }
 
     pub fn to_id_seed(&self, id: &str) -> Result<i64, ConversionError> {
         self.convert_to_id(id)
     }
 
+    /// Recovers the 128-bit id from its pretty form, as produced by
+    /// [`prettify_u128()`](Self::prettify_u128).
+    pub fn to_u128_seed(&self, id: &str) -> Result<u128, ConversionError> {
+        self.convert_to_id(id)
+    }
+
     fn divide(&self, rep: String) -> Vec<String> {
         let mut parts = Vec::with_capacity(rep.len() / self.parts_size + 1);
 
@@ -122,8 +238,8 @@ impl<C: Codec> IdPrettifier<C> {
         parts.into_iter().rev().collect()
     }
 
-    fn add_leading_zeros_parts(&self, mut parts: Vec<String>) -> Vec<String> {
-        let max_parts = (20_f64 / self.parts_size as f64).ceil() as usize;
+    fn add_leading_zeros_parts(&self, mut parts: Vec<String>, max_digits: usize) -> Vec<String> {
+        let max_parts = max_digits.div_ceil(self.parts_size);
         parts.reverse();
         parts
             .into_iter()
@@ -132,13 +248,18 @@ impl<C: Codec> IdPrettifier<C> {
             .collect()
     }
 
-    fn convert_to_id(&self, rep: &str) -> Result<i64, ConversionError> {
-        let decoded_with_check_digit = self.decode_seed_with_check_digit(rep);
-        if damm::is_valid(&decoded_with_check_digit) {
+    fn convert_to_id<N>(&self, rep: &str) -> Result<N, ConversionError>
+    where
+        N: FromStr<Err = std::num::ParseIntError>,
+    {
+        let decoded_with_check_digit = self.decode_seed_with_check_digit(rep)?;
+        if self.checksum.is_valid(&decoded_with_check_digit) {
+            let seed_len = decoded_with_check_digit.len().saturating_sub(1);
             decoded_with_check_digit
-                .get(..(decoded_with_check_digit.len() - 1))
+                .get(..seed_len)
+                .filter(|decoded| !decoded.is_empty())
                 .ok_or_else(|| ConversionError::InvalidId(rep.to_string()))
-                .and_then(|decoded| i64::from_str(decoded).map_err(|err| err.into()))
+                .and_then(|decoded| N::from_str(decoded).map_err(|err| err.into()))
         } else {
             Err(ConversionError::InvalidId(rep.to_string()))
         }
@@ -156,7 +277,7 @@ impl<C: Codec> IdPrettifier<C> {
     }
 
     fn convert_parts(&self, parts: Vec<String>) -> String {
-        let encode_odd = parts.len() % 2 == 0;
+        let encode_odd = parts.len().is_multiple_of(2);
         let padded_converted_parts =
             parts
                 .into_iter()
@@ -201,52 +322,54 @@ impl<C: Codec> IdPrettifier<C> {
         lead_padded
     }
 
-    fn decode_seed_with_check_digit(&self, rep: impl AsRef<str>) -> String {
+    fn decode_seed_with_check_digit(&self, rep: impl AsRef<str>) -> Result<String, CodecError> {
         let parts: Vec<&str> = rep.as_ref().split(&self.delimiter).collect();
-        let decode_even = parts.len() % 2 != 0;
+        let decode_even = !parts.len().is_multiple_of(2);
         let decoded_with_check_digit =
             parts
                 .into_iter()
-                .fold(Vec::<String>::new(), |mut acc, part| {
+                .try_fold(Vec::<String>::new(), |mut acc, part| {
                     let is_even = acc.len() % 2 == 0;
                     let decode_part = if decode_even { is_even } else { !is_even };
                     if decode_part {
                         acc.push(part.to_string());
                     } else {
-                        let encoded_part = format!("{}", self.encoder.decode(part));
+                        let encoded_part = format!("{}", self.encoder.decode(part)?);
                         let decoded = Self::add_leading_zeros(encoded_part, '0', self.parts_size);
                         acc.push(decoded);
                     }
-                    acc
-                });
+                    Ok(acc)
+                })?;
 
         let formatted = decoded_with_check_digit
             .into_iter()
             .format_with("", |ps, f| f(&ps));
-        formatted.to_string()
+        Ok(formatted.to_string())
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use claim::*;
     use pretty_assertions::assert_eq;
 
     use super::*;
-    use crate::id::snowflake::pretty::codec::AlphabetCodec;
+    use crate::checksum::Luhn;
+    use crate::id::snowflake::pretty::codec::{AlphabetCodec, Base58Codec};
 
     const EXAMPLE_ID: i64 = 824227036833910784;
-    const EXAMPLE_REP: &'static str = "824227036833910784";
+    const EXAMPLE_REP: &str = "824227036833910784";
 
     #[test]
     fn test_divide() {
         let prettifier = IdPrettifier::<AlphabetCodec>::default();
 
-        let damm_encoded = damm::encode("100");
+        let damm_encoded = prettifier.checksum.encode("100");
         assert_eq!(&damm_encoded, "1007");
         let actual = prettifier.divide(damm_encoded);
         assert_eq!(actual, vec!["1007".to_string()]);
 
-        let damm_encoded = damm::encode(EXAMPLE_REP);
+        let damm_encoded = prettifier.checksum.encode(EXAMPLE_REP);
         assert_eq!(damm_encoded, format!("{}9", EXAMPLE_REP));
         let actual = prettifier.divide(damm_encoded);
         assert_eq!(
@@ -264,7 +387,7 @@ mod tests {
     fn test_add_leading_zeros_parts() {
         let prettifier = IdPrettifier::<AlphabetCodec>::default();
 
-        let actual = prettifier.add_leading_zeros_parts(vec!["1007".to_string()]);
+        let actual = prettifier.add_leading_zeros_parts(vec!["1007".to_string()], I64_DIGITS);
         assert_eq!(
             actual,
             vec![
@@ -275,12 +398,15 @@ mod tests {
             ]
         );
 
-        let actual = prettifier.add_leading_zeros_parts(vec![
-            "8242".to_string(),
-            "27036".to_string(),
-            "83391".to_string(),
-            "07849".to_string(),
-        ]);
+        let actual = prettifier.add_leading_zeros_parts(
+            vec![
+                "8242".to_string(),
+                "27036".to_string(),
+                "83391".to_string(),
+                "07849".to_string(),
+            ],
+            I64_DIGITS,
+        );
         assert_eq!(
             actual,
             vec![
@@ -308,10 +434,7 @@ mod tests {
             "27036".to_string(),
             "83391".to_string(),
             "07849".to_string(),
-        ]
-        .into_iter()
-        .map(|s| s.to_string())
-        .collect();
+        ];
         let actual = prettifier.convert_parts(parts);
         assert_eq!(actual, "ARPJ-27036-GVQS-07849".to_string());
     }
@@ -342,4 +465,83 @@ mod tests {
             "00009223-FTYTHN-47758077"
         );
     }
+
+    #[test]
+    fn test_base58_pretty_ids() {
+        let prettifier = IdPrettifier::<Base58Codec>::default();
+        assert_eq!(prettifier.max_encoder_length, 3);
+
+        let example_pretty_id = prettifier.prettify(EXAMPLE_ID);
+        assert_eq!(&example_pretty_id, "3T7-27036-Rnn-07849");
+        assert_eq!(
+            prettifier.to_id_seed(&example_pretty_id).unwrap(),
+            EXAMPLE_ID
+        );
+
+        let max_pretty_id = prettifier.prettify(i64::MAX);
+        assert_eq!(prettifier.to_id_seed(&max_pretty_id).unwrap(), i64::MAX);
+    }
+
+    #[test]
+    fn test_to_id_seed() {
+        let prettifier = IdPrettifier::<AlphabetCodec>::default();
+        for id in [0, 1, EXAMPLE_ID, i64::MAX] {
+            let pretty_id = prettifier.prettify(id);
+            assert!(prettifier.is_valid(&pretty_id));
+            assert_eq!(assert_ok!(prettifier.to_id_seed(&pretty_id)), id);
+        }
+
+        assert_matches!(
+            prettifier.to_id_seed("AR0J-27036-GVQS-07849"),
+            Err(ConversionError::Codec(CodecError::InvalidCharacter('0')))
+        );
+        assert_matches!(
+            prettifier.to_id_seed("-27036-GVQS-07849"),
+            Err(ConversionError::Codec(CodecError::Empty))
+        );
+        assert_matches!(
+            prettifier.to_id_seed("ARPJ-27036-GVQS-07848"),
+            Err(ConversionError::InvalidId(_))
+        );
+        assert!(!prettifier.is_valid("AR0J-27036-GVQS-07849"));
+    }
+
+    #[test]
+    fn test_luhn_pretty_ids() {
+        let prettifier = IdPrettifier::<AlphabetCodec>::default().with_checksum(Luhn);
+        let pretty_id = prettifier.prettify(EXAMPLE_ID);
+        assert_eq!(&pretty_id, "ARPJ-27036-GVQS-07843");
+        assert!(prettifier.is_valid(&pretty_id));
+        assert_eq!(assert_ok!(prettifier.to_id_seed(&pretty_id)), EXAMPLE_ID);
+        assert!(!prettifier.is_valid("ARPJ-27036-GVQS-07849"));
+    }
+
+    #[test]
+    fn test_u128_pretty_ids() {
+        let prettifier = IdPrettifier::<AlphabetCodec>::default();
+        for id in [0, 1, EXAMPLE_ID as u128, u128::MAX] {
+            let pretty_id = prettifier.prettify_u128(id);
+            assert_eq!(pretty_id.split('-').count(), 8);
+            assert!(prettifier.is_valid(&pretty_id));
+            assert_eq!(assert_ok!(prettifier.to_u128_seed(&pretty_id)), id);
+        }
+        assert_eq!(
+            prettifier.prettify_u128(u128::MAX),
+            "CUHM-23669-BSPJ-46346-CTUF-07431-GHFB-14559"
+        );
+        assert_matches!(
+            prettifier.to_id_seed(&prettifier.prettify_u128(u128::MAX)),
+            Err(ConversionError::ParseIntError(_))
+        );
+    }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn test_uuid_pretty_ids() {
+        let prettifier = IdPrettifier::<AlphabetCodec>::default();
+        let uuid = uuid::Uuid::new_v4();
+        let pretty_id = prettifier.prettify_u128(uuid.as_u128());
+        let decoded = assert_ok!(prettifier.to_u128_seed(&pretty_id));
+        assert_eq!(uuid::Uuid::from_u128(decoded), uuid);
+    }
 }
@@ -0,0 +1,309 @@
+//! Resolution of a unique [`MachineNode`] at startup, for environments (e.g., Kubernetes) where
+//! worker ids cannot be statically configured.
+//!
+//! Resolvers are tried in order by [`SnowflakeGenerator::distributed_with()`]; the
+//! [`default_resolver()`] consults environment variables, then the pod IP, then a hash of the
+//! hostname. External coordinators (e.g., etcd or redis leases) plug in via
+//! [`WorkerIdCoordinator`].
+//!
+//! [`SnowflakeGenerator::distributed_with()`]: crate::SnowflakeGenerator::distributed_with
+
+use super::node::MachineNode;
+use std::env;
+use std::error::Error;
+use std::net::IpAddr;
+use thiserror::Error;
+
+pub const MACHINE_ID_VAR: &str = "TAGID_MACHINE_ID";
+pub const NODE_ID_VAR: &str = "TAGID_NODE_ID";
+pub const POD_IP_VAR: &str = "POD_IP";
+pub const HOSTNAME_VAR: &str = "HOSTNAME";
+
+#[derive(Debug, Error)]
+pub enum ResolveError {
+    #[error("{0} is not available")]
+    Unavailable(String),
+
+    #[error("invalid worker id source: {0}")]
+    Invalid(String),
+
+    #[error("worker id coordinator failed: {0}")]
+    Coordinator(#[source] Box<dyn Error + Send + Sync>),
+
+    #[error("no resolver produced a machine node: [{}]", .0.iter().map(ToString::to_string).collect::<Vec<_>>().join("; "))]
+    Exhausted(Vec<Self>),
+}
+
+/// A strategy for deriving this process's [`MachineNode`].
+pub trait MachineNodeResolver {
+    fn resolve(&self) -> Result<MachineNode, ResolveError>;
+}
+
+impl<R: MachineNodeResolver + ?Sized> MachineNodeResolver for Box<R> {
+    fn resolve(&self) -> Result<MachineNode, ResolveError> {
+        (**self).resolve()
+    }
+}
+
+/// Reads the machine and node ids from environment variables, by default `TAGID_MACHINE_ID` and
+/// `TAGID_NODE_ID`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvResolver {
+    machine_var: String,
+    node_var: String,
+}
+
+impl Default for EnvResolver {
+    fn default() -> Self {
+        Self::new(MACHINE_ID_VAR, NODE_ID_VAR)
+    }
+}
+
+impl EnvResolver {
+    pub fn new(machine_var: impl Into<String>, node_var: impl Into<String>) -> Self {
+        Self {
+            machine_var: machine_var.into(),
+            node_var: node_var.into(),
+        }
+    }
+
+    fn read_var(var: &str) -> Result<i32, ResolveError> {
+        let value = env::var(var).map_err(|_| ResolveError::Unavailable(var.to_string()))?;
+        value
+            .trim()
+            .parse()
+            .map_err(|err| ResolveError::Invalid(format!("{var}={value}: {err}")))
+    }
+}
+
+impl MachineNodeResolver for EnvResolver {
+    fn resolve(&self) -> Result<MachineNode, ResolveError> {
+        let machine_id = Self::read_var(&self.machine_var)?;
+        let node_id = Self::read_var(&self.node_var)?;
+        MachineNode::new(machine_id, node_id).map_err(|err| ResolveError::Invalid(err.to_string()))
+    }
+}
+
+/// Derives the worker id from the low 10 bits of the pod IP, read from an environment variable
+/// (`POD_IP` by default, as commonly exposed via the Kubernetes downward API).
+///
+/// This is unique as long as pods sharing an id space are allocated from a /22 (or smaller)
+/// subnet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PodIpResolver {
+    var: String,
+}
+
+impl Default for PodIpResolver {
+    fn default() -> Self {
+        Self::new(POD_IP_VAR)
+    }
+}
+
+impl PodIpResolver {
+    pub fn new(var: impl Into<String>) -> Self {
+        Self { var: var.into() }
+    }
+
+    pub fn from_ip(ip: IpAddr) -> MachineNode {
+        let low_bits = match ip {
+            IpAddr::V4(ip) => u32::from(ip) as u16,
+            IpAddr::V6(ip) => u128::from(ip) as u16,
+        };
+        MachineNode::from_worker_id(low_bits % MachineNode::WORKER_ID_SPACE)
+    }
+}
+
+impl MachineNodeResolver for PodIpResolver {
+    fn resolve(&self) -> Result<MachineNode, ResolveError> {
+        let value = env::var(&self.var).map_err(|_| ResolveError::Unavailable(self.var.clone()))?;
+        let ip: IpAddr = value
+            .trim()
+            .parse()
+            .map_err(|err| ResolveError::Invalid(format!("{}={value}: {err}", self.var)))?;
+        Ok(Self::from_ip(ip))
+    }
+}
+
+/// Derives the worker id from a stable hash of the hostname, read from the `HOSTNAME` environment
+/// variable or `/etc/hostname`.
+///
+/// Collisions are possible between distinct hostnames, so prefer an explicit or coordinated
+/// source where the id space is crowded.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HostnameHashResolver {
+    hostname: Option<String>,
+}
+
+impl HostnameHashResolver {
+    /// Resolves from the given hostname rather than the environment.
+    pub fn with_hostname(hostname: impl Into<String>) -> Self {
+        Self {
+            hostname: Some(hostname.into()),
+        }
+    }
+
+    fn hostname(&self) -> Result<String, ResolveError> {
+        if let Some(hostname) = &self.hostname {
+            return Ok(hostname.clone());
+        }
+
+        env::var(HOSTNAME_VAR)
+            .ok()
+            .or_else(|| std::fs::read_to_string("/etc/hostname").ok())
+            .map(|hostname| hostname.trim().to_string())
+            .filter(|hostname| !hostname.is_empty())
+            .ok_or_else(|| ResolveError::Unavailable("hostname".to_string()))
+    }
+}
+
+impl MachineNodeResolver for HostnameHashResolver {
+    fn resolve(&self) -> Result<MachineNode, ResolveError> {
+        let hash = fnv1a(self.hostname()?.as_bytes());
+        let worker_id = (hash % u64::from(MachineNode::WORKER_ID_SPACE)) as u16;
+        Ok(MachineNode::from_worker_id(worker_id))
+    }
+}
+
+/// FNV-1a, chosen because its output is stable across processes and releases (unlike std's
+/// `DefaultHasher`).
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// An external service (e.g., etcd or redis) that leases worker ids unique across the cluster.
+pub trait WorkerIdCoordinator {
+    /// Acquires a 10-bit worker id for this process.
+    fn acquire_worker_id(&self) -> Result<u16, Box<dyn Error + Send + Sync>>;
+}
+
+/// Resolves the machine node from a worker id leased by a [`WorkerIdCoordinator`].
+#[derive(Debug, Clone)]
+pub struct CoordinatedResolver<C>(pub C);
+
+impl<C: WorkerIdCoordinator> MachineNodeResolver for CoordinatedResolver<C> {
+    fn resolve(&self) -> Result<MachineNode, ResolveError> {
+        let worker_id = self
+            .0
+            .acquire_worker_id()
+            .map_err(ResolveError::Coordinator)?;
+        if MachineNode::WORKER_ID_SPACE <= worker_id {
+            return Err(ResolveError::Invalid(format!(
+                "coordinator leased worker id {worker_id} outside of 0..{}",
+                MachineNode::WORKER_ID_SPACE
+            )));
+        }
+        Ok(MachineNode::from_worker_id(worker_id))
+    }
+}
+
+/// Tries each resolver in order, returning the first machine node resolved.
+pub struct FirstResolved(Vec<Box<dyn MachineNodeResolver + Send + Sync>>);
+
+impl FirstResolved {
+    pub fn new(resolvers: Vec<Box<dyn MachineNodeResolver + Send + Sync>>) -> Self {
+        Self(resolvers)
+    }
+}
+
+impl MachineNodeResolver for FirstResolved {
+    fn resolve(&self) -> Result<MachineNode, ResolveError> {
+        let mut errors = Vec::with_capacity(self.0.len());
+        for resolver in &self.0 {
+            match resolver.resolve() {
+                Ok(machine_node) => return Ok(machine_node),
+                Err(err) => errors.push(err),
+            }
+        }
+        Err(ResolveError::Exhausted(errors))
+    }
+}
+
+/// Environment variables, then the pod IP, then the hostname hash.
+pub fn default_resolver() -> FirstResolved {
+    FirstResolved::new(vec![
+        Box::new(EnvResolver::default()),
+        Box::new(PodIpResolver::default()),
+        Box::new(HostnameHashResolver::default()),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use claim::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_env_resolver() {
+        env::set_var("TEST_RESOLVER_MACHINE_ID", "3");
+        env::set_var("TEST_RESOLVER_NODE_ID", " 17 ");
+        let resolver = EnvResolver::new("TEST_RESOLVER_MACHINE_ID", "TEST_RESOLVER_NODE_ID");
+        assert_eq!(
+            assert_ok!(resolver.resolve()),
+            MachineNode::new(3, 17).unwrap()
+        );
+
+        env::set_var("TEST_RESOLVER_NODE_ID", "32");
+        assert_matches!(resolver.resolve(), Err(ResolveError::Invalid(_)));
+
+        let resolver = EnvResolver::new("TEST_RESOLVER_MISSING", "TEST_RESOLVER_NODE_ID");
+        assert_matches!(resolver.resolve(), Err(ResolveError::Unavailable(_)));
+    }
+
+    #[test]
+    fn test_pod_ip_resolver() {
+        let machine_node = PodIpResolver::from_ip("10.4.2.37".parse().unwrap());
+        assert_eq!(machine_node.worker_id(), 0x225);
+        assert_eq!(machine_node, MachineNode::from_worker_id(549));
+
+        env::set_var("TEST_RESOLVER_POD_IP", "fd00::13ff");
+        let resolver = PodIpResolver::new("TEST_RESOLVER_POD_IP");
+        assert_eq!(
+            assert_ok!(resolver.resolve()),
+            MachineNode::from_worker_id(0x3ff)
+        );
+    }
+
+    #[test]
+    fn test_hostname_hash_resolver_is_stable() {
+        let a = assert_ok!(HostnameHashResolver::with_hostname("ingest-7c9f8-abcde").resolve());
+        let b = assert_ok!(HostnameHashResolver::with_hostname("ingest-7c9f8-abcde").resolve());
+        assert_eq!(a, b);
+        assert_ok!(MachineNode::new(a.machine_id, a.node_id));
+    }
+
+    struct Lease(Result<u16, &'static str>);
+
+    impl WorkerIdCoordinator for Lease {
+        fn acquire_worker_id(&self) -> Result<u16, Box<dyn Error + Send + Sync>> {
+            self.0.map_err(Into::into)
+        }
+    }
+
+    #[test]
+    fn test_first_resolved() {
+        let resolver = FirstResolved::new(vec![
+            Box::new(CoordinatedResolver(Lease(Err("lease unavailable")))),
+            Box::new(EnvResolver::new(
+                "TEST_RESOLVER_MISSING",
+                "TEST_RESOLVER_MISSING",
+            )),
+            Box::new(CoordinatedResolver(Lease(Ok(1023)))),
+        ]);
+        assert_eq!(
+            assert_ok!(resolver.resolve()),
+            MachineNode::new(31, 31).unwrap()
+        );
+
+        let resolver = FirstResolved::new(vec![
+            Box::new(CoordinatedResolver(Lease(Ok(1024)))),
+            Box::new(CoordinatedResolver(Lease(Err("lease unavailable")))),
+        ]);
+        let err = assert_err!(resolver.resolve());
+        assert_matches!(&err, ResolveError::Exhausted(errors) if errors.len() == 2);
+        assert!(err.to_string().contains("lease unavailable"));
+    }
+}
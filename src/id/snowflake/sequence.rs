@@ -0,0 +1,284 @@
+use super::gen::{ClockDriftError, ClockDriftPolicy, GenerationStrategy};
+use super::node::MachineNode;
+use crate::clock;
+use std::hint::spin_loop;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::time::{Duration, SystemTime};
+
+const SEQUENCE_BITS: u32 = 12;
+const SEQUENCE_MASK: i64 = (1 << SEQUENCE_BITS) - 1;
+const NODE_ID_SHIFT: u32 = SEQUENCE_BITS;
+const MACHINE_ID_SHIFT: u32 = NODE_ID_SHIFT + 5;
+const TIMESTAMP_SHIFT: u32 = MACHINE_ID_SHIFT + 5;
+
+/// Lock-free snowflake id state.
+///
+/// The last timestamp (in milliseconds since the epoch) and the sequence within that millisecond
+/// are packed into a single atomic word, so concurrent generation advances it with a
+/// compare-and-swap rather than a lock. The `Lazy` strategy never consults the clock, so its
+/// sequence overflows directly into the timestamp with a single `fetch_add`.
+///
+/// If the clock moves backwards, the `ClockDriftPolicy` decides whether to wait for it to catch
+/// up, fail, or keep counting from the last timestamp issued. In every case ids remain unique and
+/// increasing.
+///
+/// Ids share the bit layout of the `rs-snowflake` crate: 41 bits of timestamp, 5 bits each of
+/// machine and node id, and 12 bits of sequence.
+#[derive(Debug)]
+pub(super) struct Sequence {
+    state: AtomicI64,
+    worker_bits: i64,
+    epoch: SystemTime,
+}
+
+impl Sequence {
+    pub fn new(machine_node: MachineNode, epoch: SystemTime) -> Self {
+        let worker_bits = (i64::from(machine_node.machine_id) << MACHINE_ID_SHIFT)
+            | (i64::from(machine_node.node_id) << NODE_ID_SHIFT);
+        Self {
+            state: AtomicI64::new(millis_since(epoch) << SEQUENCE_BITS),
+            worker_bits,
+            epoch,
+        }
+    }
+
+    pub fn next_id(
+        &self,
+        strategy: GenerationStrategy,
+        drift_policy: ClockDriftPolicy,
+    ) -> Result<i64, ClockDriftError> {
+        let state = match strategy {
+            GenerationStrategy::Lazy => self.state.fetch_add(1, Ordering::AcqRel) + 1,
+            strategy => self.advance(strategy, drift_policy)?,
+        };
+
+        let timestamp = state >> SEQUENCE_BITS;
+        let sequence = state & SEQUENCE_MASK;
+        Ok((timestamp << TIMESTAMP_SHIFT) | self.worker_bits | sequence)
+    }
+
+    fn advance(
+        &self,
+        strategy: GenerationStrategy,
+        drift_policy: ClockDriftPolicy,
+    ) -> Result<i64, ClockDriftError> {
+        let mut current = self.state.load(Ordering::Acquire);
+        loop {
+            let last_millis = current >> SEQUENCE_BITS;
+            let sequence = current & SEQUENCE_MASK;
+
+            let next = match strategy {
+                GenerationStrategy::RealTime => {
+                    let now = millis_since(self.epoch);
+                    if last_millis < now {
+                        now << SEQUENCE_BITS
+                    } else if now < last_millis && drift_policy != ClockDriftPolicy::BorrowSequence
+                    {
+                        Self::check_drift(drift_policy, last_millis, now)?;
+                        self.wait_until(last_millis);
+                        current = self.state.load(Ordering::Acquire);
+                        continue;
+                    } else if sequence < SEQUENCE_MASK {
+                        current + 1
+                    } else {
+                        self.next_millis(last_millis, drift_policy)? << SEQUENCE_BITS
+                    }
+                }
+                _ if sequence < SEQUENCE_MASK => current + 1,
+                _ => self.next_millis(last_millis, drift_policy)? << SEQUENCE_BITS,
+            };
+
+            match self.state.compare_exchange_weak(
+                current,
+                next,
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => return Ok(next),
+                Err(actual) => current = actual,
+            }
+        }
+    }
+
+    /// The millisecond following an exhausted sequence at `last_millis`. If the clock is behind
+    /// `last_millis`, the `BorrowSequence` policy claims the next millisecond ahead of the clock
+    /// rather than waiting for it.
+    fn next_millis(
+        &self,
+        last_millis: i64,
+        drift_policy: ClockDriftPolicy,
+    ) -> Result<i64, ClockDriftError> {
+        let now = millis_since(self.epoch);
+        if now < last_millis {
+            if drift_policy == ClockDriftPolicy::BorrowSequence {
+                return Ok(last_millis + 1);
+            }
+            Self::check_drift(drift_policy, last_millis, now)?;
+        }
+        Ok(self.wait_until(last_millis + 1))
+    }
+
+    fn check_drift(
+        drift_policy: ClockDriftPolicy,
+        last_millis: i64,
+        now: i64,
+    ) -> Result<(), ClockDriftError> {
+        if drift_policy == ClockDriftPolicy::Error {
+            return Err(ClockDriftError {
+                drift: Duration::from_millis((last_millis - now).unsigned_abs()),
+            });
+        }
+        Ok(())
+    }
+
+    fn wait_until(&self, millis: i64) -> i64 {
+        loop {
+            let now = millis_since(self.epoch);
+            if millis <= now {
+                return now;
+            }
+            spin_loop();
+        }
+    }
+}
+
+/// When the snowflake `id` was generated, measured from the generator `epoch`.
+pub(super) fn timestamp_of(id: i64, epoch: SystemTime) -> SystemTime {
+    let millis = u64::try_from(id >> TIMESTAMP_SHIFT).unwrap_or_default();
+    epoch + Duration::from_millis(millis)
+}
+
+fn millis_since(epoch: SystemTime) -> i64 {
+    clock::now()
+        .duration_since(epoch)
+        .map_or(0, |elapsed| elapsed.as_millis() as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::collections::HashSet;
+    use std::sync::Arc;
+    use std::time::UNIX_EPOCH;
+
+    #[test]
+    fn test_sequence_layout() {
+        let sequence = Sequence::new(MachineNode::new(2, 3).unwrap(), UNIX_EPOCH);
+        let id = sequence
+            .next_id(GenerationStrategy::RealTime, ClockDriftPolicy::default())
+            .unwrap();
+        assert_eq!((id >> MACHINE_ID_SHIFT) & 0x1f, 2);
+        assert_eq!((id >> NODE_ID_SHIFT) & 0x1f, 3);
+
+        let millis = id >> TIMESTAMP_SHIFT;
+        assert!((millis - millis_since(UNIX_EPOCH)).abs() < 1_000);
+    }
+
+    #[test]
+    fn test_lazy_sequence_overflows_into_timestamp() {
+        let sequence = Sequence::new(MachineNode::default(), UNIX_EPOCH);
+        let first = sequence
+            .next_id(GenerationStrategy::Lazy, ClockDriftPolicy::default())
+            .unwrap();
+        let ids: Vec<_> = (0..2 * (SEQUENCE_MASK + 1))
+            .map(|_| {
+                sequence
+                    .next_id(GenerationStrategy::Lazy, ClockDriftPolicy::default())
+                    .unwrap()
+            })
+            .collect();
+        assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(
+            (ids[ids.len() - 1] >> TIMESTAMP_SHIFT) - (first >> TIMESTAMP_SHIFT),
+            2
+        );
+    }
+
+    /// Simulates the clock having moved backwards by `drift` since the last id was generated.
+    fn drifted_sequence(drift: Duration) -> Sequence {
+        let sequence = Sequence::new(MachineNode::default(), UNIX_EPOCH);
+        let ahead = millis_since(UNIX_EPOCH) + drift.as_millis() as i64;
+        sequence
+            .state
+            .store(ahead << SEQUENCE_BITS, Ordering::Release);
+        sequence
+    }
+
+    #[test]
+    fn test_clock_drift_error() {
+        let sequence = drifted_sequence(Duration::from_secs(60));
+        let err = sequence
+            .next_id(GenerationStrategy::RealTime, ClockDriftPolicy::Error)
+            .unwrap_err();
+        assert!(Duration::from_secs(59) < err.drift && err.drift <= Duration::from_secs(60));
+
+        // the lazy strategy never consults the clock
+        assert!(sequence
+            .next_id(GenerationStrategy::Lazy, ClockDriftPolicy::Error)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_clock_drift_wait_for_clock() {
+        let sequence = drifted_sequence(Duration::from_millis(20));
+        let ahead = sequence.state.load(Ordering::Acquire) >> SEQUENCE_BITS;
+        let id = sequence
+            .next_id(GenerationStrategy::RealTime, ClockDriftPolicy::WaitForClock)
+            .unwrap();
+        assert!(ahead <= id >> TIMESTAMP_SHIFT);
+        assert!(ahead <= millis_since(UNIX_EPOCH));
+    }
+
+    #[test]
+    fn test_clock_drift_borrow_sequence() {
+        let sequence = drifted_sequence(Duration::from_secs(60));
+        let ahead = sequence.state.load(Ordering::Acquire) >> SEQUENCE_BITS;
+        let ids: Vec<_> = (0..=SEQUENCE_MASK + 1)
+            .map(|_| {
+                sequence
+                    .next_id(
+                        GenerationStrategy::RealTime,
+                        ClockDriftPolicy::BorrowSequence,
+                    )
+                    .unwrap()
+            })
+            .collect();
+        assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(ids[0] >> TIMESTAMP_SHIFT, ahead);
+        assert_eq!(ids[ids.len() - 1] >> TIMESTAMP_SHIFT, ahead + 1);
+    }
+
+    #[test]
+    fn test_concurrent_generation_is_unique() {
+        for strategy in [
+            GenerationStrategy::RealTime,
+            GenerationStrategy::Generate,
+            GenerationStrategy::Lazy,
+        ] {
+            let sequence = Arc::new(Sequence::new(MachineNode::default(), UNIX_EPOCH));
+            let handles: Vec<_> = (0..4)
+                .map(|_| {
+                    let sequence = Arc::clone(&sequence);
+                    std::thread::spawn(move || {
+                        (0..5_000)
+                            .map(|_| {
+                                sequence
+                                    .next_id(strategy, ClockDriftPolicy::default())
+                                    .unwrap()
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+
+            let mut ids = HashSet::new();
+            for handle in handles {
+                let generated = handle.join().unwrap();
+                assert!(generated.windows(2).all(|pair| pair[0] < pair[1]));
+                ids.extend(generated);
+            }
+            assert_eq!(ids.len(), 20_000, "duplicate ids with {strategy} strategy");
+        }
+    }
+}
@@ -0,0 +1,371 @@
+//! Obfuscated string forms of sequential numeric ids, so database sequence or snowflake ids can be
+//! exposed publicly without revealing volumes or inviting enumeration.
+//!
+//! The encoding follows the [Sqids](https://sqids.org) algorithm, and an empty salt produces
+//! Sqids-compatible ids for single numbers. A salt shuffles the alphabet beforehand, so
+//! deployments with different salts produce unrelated ids.
+//!
+//! ```rust
+//! use once_cell::sync::Lazy;
+//! use tagid::{Entity, IdGenerator, Label, ObfuscatedIdGenerator, Obfuscation, Obfuscator};
+//!
+//! struct OrderSequence;
+//!
+//! impl IdGenerator for OrderSequence {
+//!     type IdType = u64;
+//!
+//!     fn next_id_rep() -> u64 {
+//!         // e.g., nextval('order_seq')
+//!         1_017
+//!     }
+//! }
+//!
+//! struct PublicIds;
+//!
+//! impl Obfuscation for PublicIds {
+//!     fn obfuscator() -> &'static Obfuscator {
+//!         static OBFUSCATOR: Lazy<Obfuscator> = Lazy::new(|| {
+//!             Obfuscator::new(Obfuscator::DEFAULT_ALPHABET, "pepper")
+//!                 .unwrap()
+//!                 .with_min_length(6)
+//!         });
+//!         &OBFUSCATOR
+//!     }
+//! }
+//!
+//! #[derive(Label)]
+//! struct Order;
+//!
+//! impl Entity for Order {
+//!     type IdGen = ObfuscatedIdGenerator<OrderSequence, PublicIds>;
+//! }
+//!
+//! let id = Order::next_id();
+//! assert_eq!(id.id.len(), 6);
+//! assert_eq!(ObfuscatedIdGenerator::<OrderSequence, PublicIds>::decode(&id.id).unwrap(), 1_017);
+//! ```
+
+use crate::id::{IdGenError, IdGenerator, TryIdGenerator};
+use smol_str::SmolStr;
+use std::collections::HashSet;
+use std::marker::PhantomData;
+use thiserror::Error;
+
+const MIN_ALPHABET_LEN: usize = 3;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ObfuscationError {
+    #[error("obfuscation alphabet must have at least {MIN_ALPHABET_LEN} characters")]
+    AlphabetTooShort,
+
+    #[error("obfuscation alphabet must be ASCII")]
+    NonAsciiAlphabet,
+
+    #[error("obfuscation alphabet repeats character `{0}`")]
+    RepeatedCharacter(char),
+
+    #[error("`{0}` is not a valid obfuscated id")]
+    InvalidId(String),
+
+    #[error("id {0} is out of range for obfuscation")]
+    OutOfRange(String),
+}
+
+/// Encodes numeric ids into short, non-sequential strings and decodes them back.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Obfuscator {
+    alphabet: Vec<u8>,
+    min_length: usize,
+}
+
+impl Default for Obfuscator {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_ALPHABET, "").expect("default obfuscation alphabet is valid")
+    }
+}
+
+impl Obfuscator {
+    pub const DEFAULT_ALPHABET: &'static str =
+        "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+    /// Creates an obfuscator from the alphabet, shuffled by the salt.
+    pub fn new(alphabet: &str, salt: &str) -> Result<Self, ObfuscationError> {
+        if !alphabet.is_ascii() {
+            return Err(ObfuscationError::NonAsciiAlphabet);
+        }
+        if alphabet.len() < MIN_ALPHABET_LEN {
+            return Err(ObfuscationError::AlphabetTooShort);
+        }
+        let mut seen = HashSet::with_capacity(alphabet.len());
+        if let Some(repeated) = alphabet.bytes().find(|c| !seen.insert(*c)) {
+            return Err(ObfuscationError::RepeatedCharacter(char::from(repeated)));
+        }
+
+        let mut alphabet = alphabet.as_bytes().to_vec();
+        salt_shuffle(&mut alphabet, salt.as_bytes());
+        shuffle(&mut alphabet);
+        Ok(Self {
+            alphabet,
+            min_length: 0,
+        })
+    }
+
+    /// Pads encoded ids to at least `min_length` characters.
+    #[must_use]
+    pub const fn with_min_length(mut self, min_length: usize) -> Self {
+        self.min_length = min_length;
+        self
+    }
+
+    pub fn encode(&self, id: u64) -> String {
+        let len = self.alphabet.len();
+        let offset = (usize::from(self.alphabet[(id % len as u64) as usize]) + 1) % len;
+        let mut alphabet = self.alphabet.clone();
+        alphabet.rotate_left(offset);
+        let prefix = alphabet[0];
+        alphabet.reverse();
+
+        let mut encoded = vec![prefix];
+        encoded.extend(to_digits(id, &alphabet[1..]));
+
+        if encoded.len() < self.min_length {
+            encoded.push(alphabet[0]);
+            while encoded.len() < self.min_length {
+                shuffle(&mut alphabet);
+                let padding = (self.min_length - encoded.len()).min(len);
+                encoded.extend_from_slice(&alphabet[..padding]);
+            }
+        }
+
+        encoded.into_iter().map(char::from).collect()
+    }
+
+    /// Decodes an obfuscated id, rejecting any representation other than the one
+    /// [`encode()`](Self::encode) produces.
+    pub fn decode(&self, rep: &str) -> Result<u64, ObfuscationError> {
+        let invalid = || ObfuscationError::InvalidId(rep.to_string());
+        let (&prefix, rest) = rep.as_bytes().split_first().ok_or_else(invalid)?;
+        let offset = self
+            .alphabet
+            .iter()
+            .position(|c| *c == prefix)
+            .ok_or_else(invalid)?;
+        let mut alphabet = self.alphabet.clone();
+        alphabet.rotate_left(offset);
+        alphabet.reverse();
+
+        let digits = rest.split(|c| *c == alphabet[0]).next().unwrap_or_default();
+        let id = from_digits(digits, &alphabet[1..]).ok_or_else(invalid)?;
+        if self.encode(id) != rep {
+            return Err(invalid());
+        }
+        Ok(id)
+    }
+}
+
+/// Shuffles the alphabet deterministically by the salt.
+fn salt_shuffle(alphabet: &mut [u8], salt: &[u8]) {
+    if salt.is_empty() {
+        return;
+    }
+
+    let mut position = 0;
+    let mut sum = 0;
+    for i in (1..alphabet.len()).rev() {
+        position %= salt.len();
+        let n = usize::from(salt[position]);
+        sum += n;
+        alphabet.swap(i, (n + position + sum) % i);
+        position += 1;
+    }
+}
+
+fn shuffle(alphabet: &mut [u8]) {
+    let len = alphabet.len();
+    let (mut i, mut j) = (0, len - 1);
+    while 0 < j {
+        let r = (i * j + usize::from(alphabet[i]) + usize::from(alphabet[j])) % len;
+        alphabet.swap(i, r);
+        i += 1;
+        j -= 1;
+    }
+}
+
+fn to_digits(mut id: u64, alphabet: &[u8]) -> Vec<u8> {
+    let base = alphabet.len() as u64;
+    let mut digits = Vec::new();
+    loop {
+        digits.push(alphabet[(id % base) as usize]);
+        id /= base;
+        if id == 0 {
+            break;
+        }
+    }
+    digits.reverse();
+    digits
+}
+
+fn from_digits(digits: &[u8], alphabet: &[u8]) -> Option<u64> {
+    if digits.is_empty() {
+        return None;
+    }
+
+    digits.iter().try_fold(0_u64, |acc, c| {
+        let digit = alphabet.iter().position(|a| a == c)? as u64;
+        acc.checked_mul(alphabet.len() as u64)?.checked_add(digit)
+    })
+}
+
+/// Provides the [`Obfuscator`] an [`ObfuscatedIdGenerator`] encodes its ids with.
+pub trait Obfuscation {
+    fn obfuscator() -> &'static Obfuscator;
+}
+
+/// Generates ids via the numeric generator `G`, such as a database sequence or the
+/// `SnowflakeGenerator`, rendered in the obfuscated form of `O`.
+pub struct ObfuscatedIdGenerator<G, O> {
+    marker: PhantomData<fn() -> (G, O)>,
+}
+
+impl<G, O> ObfuscatedIdGenerator<G, O>
+where
+    G: IdGenerator,
+    G::IdType: TryFrom<u64>,
+    O: Obfuscation,
+{
+    /// Recovers the id generated by `G` from its obfuscated form.
+    pub fn decode(rep: &str) -> Result<G::IdType, ObfuscationError> {
+        let id = O::obfuscator().decode(rep)?;
+        G::IdType::try_from(id).map_err(|_| ObfuscationError::InvalidId(rep.to_string()))
+    }
+}
+
+fn obfuscate<O: Obfuscation>(id: impl TryInto<u64> + ToString) -> Result<SmolStr, IdGenError> {
+    let rep = id.to_string();
+    let id = id
+        .try_into()
+        .map_err(|_| IdGenError::Source(Box::new(ObfuscationError::OutOfRange(rep))))?;
+    Ok(O::obfuscator().encode(id).into())
+}
+
+impl<G, O> IdGenerator for ObfuscatedIdGenerator<G, O>
+where
+    G: IdGenerator,
+    G::IdType: TryInto<u64> + ToString,
+    O: Obfuscation,
+{
+    type IdType = SmolStr;
+
+    fn next_id_rep() -> Self::IdType {
+        obfuscate::<O>(G::next_id_rep()).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    fn is_available() -> bool {
+        G::is_available()
+    }
+}
+
+impl<G, O> TryIdGenerator for ObfuscatedIdGenerator<G, O>
+where
+    G: TryIdGenerator,
+    G::IdType: TryInto<u64> + ToString,
+    O: Obfuscation,
+{
+    fn try_next_id_rep() -> Result<Self::IdType, IdGenError> {
+        obfuscate::<O>(G::try_next_id_rep()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use claim::*;
+    use once_cell::sync::Lazy;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_sqids_compatible_encoding() {
+        let obfuscator = Obfuscator::default();
+        let expected = ["bM", "Uk", "gb", "Ef", "Vq", "uw", "OI", "AX", "p6", "nJ"];
+        for (id, rep) in expected.into_iter().enumerate() {
+            assert_eq!(obfuscator.encode(id as u64), rep);
+            assert_eq!(assert_ok!(obfuscator.decode(rep)), id as u64);
+        }
+    }
+
+    #[test]
+    fn test_obfuscator_roundtrip() {
+        let salted = assert_ok!(Obfuscator::new(Obfuscator::DEFAULT_ALPHABET, "pepper"));
+        let padded = salted.clone().with_min_length(10);
+        for id in [0, 1, 2, 999, 1_000, 824_227_036_833_910_784, u64::MAX] {
+            assert_eq!(assert_ok!(salted.decode(&salted.encode(id))), id);
+            let rep = padded.encode(id);
+            assert!(10 <= rep.len());
+            assert_eq!(assert_ok!(padded.decode(&rep)), id);
+        }
+
+        assert_ne!(salted.encode(1), Obfuscator::default().encode(1));
+        assert_ne!(padded.encode(1), padded.encode(2));
+    }
+
+    #[test]
+    fn test_obfuscator_errors() {
+        assert_eq!(
+            assert_err!(Obfuscator::new("ab", "")),
+            ObfuscationError::AlphabetTooShort
+        );
+        assert_eq!(
+            assert_err!(Obfuscator::new("abca", "")),
+            ObfuscationError::RepeatedCharacter('a')
+        );
+        assert_eq!(
+            assert_err!(Obfuscator::new("abcé", "")),
+            ObfuscationError::NonAsciiAlphabet
+        );
+
+        let obfuscator = Obfuscator::default();
+        for rep in ["", "U", "U!", "Ukk"] {
+            assert_eq!(
+                assert_err!(obfuscator.decode(rep)),
+                ObfuscationError::InvalidId(rep.to_string())
+            );
+        }
+    }
+
+    struct Sequence;
+
+    impl IdGenerator for Sequence {
+        type IdType = i64;
+
+        fn next_id_rep() -> Self::IdType {
+            42
+        }
+    }
+
+    impl TryIdGenerator for Sequence {
+        fn try_next_id_rep() -> Result<Self::IdType, IdGenError> {
+            Ok(-1)
+        }
+    }
+
+    struct Salted;
+
+    impl Obfuscation for Salted {
+        fn obfuscator() -> &'static Obfuscator {
+            static OBFUSCATOR: Lazy<Obfuscator> =
+                Lazy::new(|| Obfuscator::new(Obfuscator::DEFAULT_ALPHABET, "salt").unwrap());
+            &OBFUSCATOR
+        }
+    }
+
+    #[test]
+    fn test_obfuscated_id_generator() {
+        type Generator = ObfuscatedIdGenerator<Sequence, Salted>;
+        let id = <Generator as IdGenerator>::next_id_rep();
+        assert_eq!(assert_ok!(Generator::decode(&id)), 42);
+        assert_matches!(
+            <Generator as TryIdGenerator>::try_next_id_rep(),
+            Err(IdGenError::Source(_))
+        );
+    }
+}
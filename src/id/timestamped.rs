@@ -0,0 +1,104 @@
+//! Creation times recovered from time-ordered ids, so "when was this entity created" needs no
+//! separate column.
+
+use crate::Id;
+use iso8601_timestamp::Timestamp;
+
+/// An id representation that always embeds the time it was generated, such as a ULID or KSUID.
+///
+/// UUIDs are not `TimestampedId`s, since only version 7 UUIDs carry a timestamp. Snowflake ids
+/// are measured from their generator's epoch, so `Id::created_at_by` takes the generator.
+pub trait TimestampedId {
+    /// When the id was generated, at the precision of its representation.
+    fn created_at(&self) -> Timestamp;
+}
+
+impl<T: ?Sized, ID: TimestampedId> Id<T, ID> {
+    /// When the id was generated, as embedded in its representation.
+    pub fn created_at(&self) -> Timestamp {
+        self.id.created_at()
+    }
+}
+
+#[cfg(feature = "ulid")]
+impl TimestampedId for crate::Ulid {
+    fn created_at(&self) -> Timestamp {
+        self.timestamp().into()
+    }
+}
+
+#[cfg(feature = "ksuid")]
+impl TimestampedId for crate::Ksuid {
+    fn created_at(&self) -> Timestamp {
+        self.timestamp().into()
+    }
+}
+
+#[cfg(feature = "snowflake")]
+impl<T: ?Sized> Id<T, i64> {
+    /// When the snowflake id was generated by `generator`, measured from its epoch.
+    pub fn created_at_by(&self, generator: &crate::SnowflakeGenerator) -> Timestamp {
+        generator.created_at(self.id)
+    }
+}
+
+#[cfg(all(test, any(feature = "ulid", feature = "ksuid", feature = "snowflake")))]
+mod tests {
+    use super::*;
+    use crate::{CustomLabeling, Label};
+    use pretty_assertions::assert_eq;
+    use std::time::{Duration, SystemTime};
+
+    struct Order;
+
+    impl Label for Order {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("Order")
+        }
+    }
+
+    fn at() -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(1_669_779_798)
+    }
+
+    #[cfg(feature = "ulid")]
+    #[test]
+    fn test_ulid_created_at() {
+        let id: Id<Order, crate::Ulid> = Id::for_labeled(crate::Ulid::from_datetime(at()));
+        assert_eq!(id.created_at(), Timestamp::from(at()));
+    }
+
+    #[cfg(feature = "ksuid")]
+    #[test]
+    fn test_ksuid_created_at() {
+        let id: Id<Order, crate::Ksuid> = Id::for_labeled(crate::Ksuid::from_parts(at(), [7; 16]));
+        assert_eq!(id.created_at(), Timestamp::from(at()));
+    }
+
+    #[cfg(feature = "snowflake")]
+    #[test]
+    fn test_snowflake_created_at_by() {
+        use crate::id::snowflake::GenerationStrategy;
+        use crate::{MachineNode, SnowflakeGenerator};
+
+        let epoch = at() - Duration::from_secs(86_400);
+        let custom = SnowflakeGenerator::with_epoch(
+            MachineNode::default(),
+            GenerationStrategy::RealTime,
+            epoch,
+        );
+        let unix = SnowflakeGenerator::new(MachineNode::default(), GenerationStrategy::RealTime);
+
+        let id: Id<Order, i64> = Id::for_labeled(1_000 << 22);
+        assert_eq!(
+            id.created_at_by(&custom),
+            Timestamp::from(epoch + Duration::from_secs(1))
+        );
+        assert_eq!(
+            id.created_at_by(&unix),
+            Timestamp::from(SystemTime::UNIX_EPOCH + Duration::from_secs(1))
+        );
+    }
+}
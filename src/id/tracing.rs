@@ -0,0 +1,72 @@
+use crate::Id;
+use ::tracing::field::DisplayValue;
+use std::fmt;
+
+impl<T: ?Sized, ID: fmt::Display> Id<T, ID> {
+    /// Records the id in its labeled display form as a [`tracing`] field value, e.g.,
+    /// `info_span!("order", order_id = id.as_tracing_value())`.
+    pub fn as_tracing_value(&self) -> DisplayValue<&Self> {
+        ::tracing::field::display(self)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::{CustomLabeling, Label};
+    use pretty_assertions::assert_eq;
+    use std::io;
+    use std::sync::{Arc, Mutex};
+
+    /// Runs `f` under a subscriber that formats events, with their spans, into the returned log.
+    pub fn capture_log(f: impl FnOnce()) -> String {
+        #[derive(Clone, Default)]
+        struct Log(Arc<Mutex<Vec<u8>>>);
+
+        impl io::Write for Log {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                self.0.lock().unwrap().write(buf)
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let log = Log::default();
+        let writer = log.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_ansi(false)
+            .without_time()
+            .with_writer(move || writer.clone())
+            .finish();
+        tracing::subscriber::with_default(subscriber, f);
+
+        let bytes = log.0.lock().unwrap().clone();
+        String::from_utf8(bytes).unwrap()
+    }
+
+    struct Order;
+
+    impl Label for Order {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("Order")
+        }
+    }
+
+    #[test]
+    fn test_id_as_tracing_value() {
+        let id: Id<Order, u64> = Id::for_labeled(17);
+        let log = capture_log(|| {
+            let span = tracing::info_span!("order", order_id = id.as_tracing_value());
+            let _entered = span.enter();
+            tracing::info!("placed");
+        });
+        assert_eq!(
+            log.trim(),
+            "INFO order{order_id=Order::17}: tagid::id::tracing::tests: placed"
+        );
+    }
+}
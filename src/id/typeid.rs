@@ -0,0 +1,292 @@
+//! [TypeID](https://github.com/jetify-com/typeid) compatibility, rendering `Id<T, Uuid>` in the
+//! type-prefixed `prefix_01h455vb4pex5vsknk084sn02q` form.
+//!
+//! The prefix is derived from the entity label (e.g., `OrderLine` becomes `order_line`) and the
+//! suffix is the UUID encoded in 26 lowercase Crockford base32 characters.
+//!
+//! Ids are generated as version 7 UUIDs by [`TypeIdGenerator`], so TypeIDs sort in generation
+//! order. Serialize ids in TypeID form via `#[serde(with = "tagid::typeid")]`.
+//!
+//! ```rust
+//! use tagid::{Entity, Id, Label, TypeIdGenerator};
+//! use uuid::Uuid;
+//!
+//! #[derive(Label)]
+//! struct OrderLine;
+//!
+//! impl Entity for OrderLine {
+//!     type IdGen = TypeIdGenerator;
+//! }
+//!
+//! let id = OrderLine::next_id();
+//! let type_id = id.to_type_id();
+//! assert!(type_id.starts_with("order_line_01"));
+//! assert_eq!(Id::<OrderLine, Uuid>::parse_type_id(&type_id).unwrap(), id);
+//! ```
+
+use crate::id::{IdGenerator, TimeOrderedIdGenerator, TryIdGenerator};
+use crate::{Id, Label, Labeling};
+use serde::{de, Deserialize, Deserializer, Serializer};
+use std::borrow::Cow;
+use std::time::SystemTime;
+use thiserror::Error;
+use uuid::Uuid;
+
+pub const TYPE_ID_DELIMITER: char = '_';
+const MAX_PREFIX_LEN: usize = 63;
+const SUFFIX_LEN: usize = 26;
+const CROCKFORD: &[u8; 32] = b"0123456789abcdefghjkmnpqrstvwxyz";
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum TypeIdError {
+    #[error("TypeID prefix `{actual}` does not match expected prefix `{expected}`")]
+    PrefixMismatch { expected: String, actual: String },
+
+    #[error("invalid TypeID suffix `{0}`: expected {SUFFIX_LEN} lowercase base32 characters")]
+    InvalidSuffix(String),
+}
+
+/// Generates version 7 UUIDs, rendered in TypeID form via [`Id::to_type_id()`].
+pub struct TypeIdGenerator;
+
+impl IdGenerator for TypeIdGenerator {
+    type IdType = Uuid;
+
+    fn next_id_rep() -> Self::IdType {
+        Uuid::now_v7()
+    }
+}
+
+impl TryIdGenerator for TypeIdGenerator {}
+
+impl TimeOrderedIdGenerator for TypeIdGenerator {
+    fn next_id_rep_at(timestamp: SystemTime) -> Self::IdType {
+        crate::UuidV7Generator::next_id_rep_at(timestamp)
+    }
+}
+
+/// The TypeID prefix for a label: the label in snake case, restricted to `a-z` and `_`, with
+/// runs of other characters replaced by `_` and at most 63 characters.
+pub fn type_id_prefix(label: &str) -> String {
+    let chars: Vec<char> = label.chars().collect();
+    let mut prefix = String::with_capacity(label.len() + 4);
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_ascii_uppercase() {
+            let previous = i.checked_sub(1).map(|p| chars[p]);
+            let next = chars.get(i + 1);
+            let word_start = previous.is_some_and(|p| p.is_ascii_lowercase() || p.is_ascii_digit())
+                || (previous.is_some_and(|p| p.is_ascii_uppercase())
+                    && next.is_some_and(char::is_ascii_lowercase));
+            if word_start && !prefix.ends_with(TYPE_ID_DELIMITER) {
+                prefix.push(TYPE_ID_DELIMITER);
+            }
+            prefix.push(c.to_ascii_lowercase());
+        } else if c.is_ascii_lowercase() {
+            prefix.push(*c);
+        } else if !prefix.ends_with(TYPE_ID_DELIMITER) {
+            prefix.push(TYPE_ID_DELIMITER);
+        }
+    }
+
+    prefix.truncate(MAX_PREFIX_LEN);
+    prefix.trim_matches(TYPE_ID_DELIMITER).to_string()
+}
+
+fn encode_suffix(uuid: &Uuid) -> String {
+    let mut value = uuid.as_u128();
+    let mut encoded = [CROCKFORD[0]; SUFFIX_LEN];
+    for digit in encoded.iter_mut().rev() {
+        *digit = CROCKFORD[(value & 0x1f) as usize];
+        value >>= 5;
+    }
+    encoded.iter().map(|d| char::from(*d)).collect()
+}
+
+fn decode_suffix(suffix: &str) -> Result<Uuid, TypeIdError> {
+    let invalid = || TypeIdError::InvalidSuffix(suffix.to_string());
+    if suffix.len() != SUFFIX_LEN {
+        return Err(invalid());
+    }
+
+    let mut value = 0_u128;
+    for (position, c) in suffix.bytes().enumerate() {
+        let digit = CROCKFORD.iter().position(|d| *d == c).ok_or_else(invalid)?;
+
+        // 26 characters hold 130 bits, so the leading character is limited to 3 bits
+        if position == 0 && 7 < digit {
+            return Err(invalid());
+        }
+        value = (value << 5) | digit as u128;
+    }
+
+    Ok(Uuid::from_u128(value))
+}
+
+impl<T: ?Sized> Id<T, Uuid> {
+    /// Renders the id in TypeID form, prefixed by its label. Ids of unlabeled types render as the
+    /// bare suffix.
+    pub fn to_type_id(&self) -> String {
+        let prefix = type_id_prefix(&self.label);
+        let suffix = encode_suffix(&self.id);
+        if prefix.is_empty() {
+            suffix
+        } else {
+            format!("{prefix}{TYPE_ID_DELIMITER}{suffix}")
+        }
+    }
+}
+
+impl<T: ?Sized + Label> Id<T, Uuid> {
+    /// Parses the TypeID form, verifying the prefix is derived from the label of `T` or one of
+    /// its aliases.
+    pub fn parse_type_id(rep: &str) -> Result<Self, TypeIdError> {
+        let (prefix, suffix) = rep.rsplit_once(TYPE_ID_DELIMITER).unwrap_or(("", rep));
+
+        let labeler = <T as Label>::labeler();
+        let expected = type_id_prefix(labeler.label());
+        let accepted = prefix == expected
+            || T::aliases()
+                .iter()
+                .any(|alias| prefix == type_id_prefix(alias));
+        if !accepted {
+            return Err(TypeIdError::PrefixMismatch {
+                expected,
+                actual: prefix.to_string(),
+            });
+        }
+
+        decode_suffix(suffix).map(Self::for_labeled)
+    }
+}
+
+pub fn serialize<T, S>(id: &Id<T, Uuid>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: ?Sized,
+    S: Serializer,
+{
+    serializer.serialize_str(&id.to_type_id())
+}
+
+pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Id<T, Uuid>, D::Error>
+where
+    T: ?Sized + Label,
+    D: Deserializer<'de>,
+{
+    let rep = Cow::<'de, str>::deserialize(deserializer)?;
+    Id::parse_type_id(&rep).map_err(de::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CustomLabeling, NoLabeling};
+    use claim::*;
+    use pretty_assertions::assert_eq;
+    use serde::{Deserialize, Serialize};
+
+    struct Prefix;
+
+    impl Label for Prefix {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("Prefix")
+        }
+
+        fn aliases() -> &'static [&'static str] {
+            &["LegacyPrefix"]
+        }
+    }
+
+    struct Unlabeled;
+
+    impl Label for Unlabeled {
+        type Labeler = NoLabeling;
+
+        fn labeler() -> Self::Labeler {
+            NoLabeling
+        }
+    }
+
+    #[test]
+    fn test_type_id_prefix() {
+        assert_eq!(type_id_prefix("Order"), "order");
+        assert_eq!(type_id_prefix("OrderLine"), "order_line");
+        assert_eq!(type_id_prefix("HTTPRequest"), "http_request");
+        assert_eq!(type_id_prefix("api-key"), "api_key");
+        assert_eq!(type_id_prefix("User2Fa"), "user_fa");
+        assert_eq!(type_id_prefix(""), "");
+        assert_eq!(type_id_prefix(&"a".repeat(70)).len(), 63);
+    }
+
+    #[test]
+    fn test_type_id_spec_examples() {
+        let uuid = Uuid::parse_str("01890a5d-ac96-774b-bcce-b302099a8057").unwrap();
+        let id: Id<Prefix, Uuid> = Id::for_labeled(uuid);
+        assert_eq!(id.to_type_id(), "prefix_01h455vb4pex5vsknk084sn02q");
+        assert_eq!(
+            assert_ok!(Id::<Prefix, Uuid>::parse_type_id(
+                "prefix_01h455vb4pex5vsknk084sn02q"
+            )),
+            id
+        );
+
+        let nil: Id<Unlabeled, Uuid> = Id::for_labeled(Uuid::nil());
+        assert_eq!(nil.to_type_id(), "00000000000000000000000000");
+        let max: Id<Unlabeled, Uuid> = Id::for_labeled(Uuid::max());
+        assert_eq!(max.to_type_id(), "7zzzzzzzzzzzzzzzzzzzzzzzzz");
+        assert_eq!(
+            assert_ok!(Id::<Unlabeled, Uuid>::parse_type_id(
+                "7zzzzzzzzzzzzzzzzzzzzzzzzz"
+            )),
+            max
+        );
+    }
+
+    #[test]
+    fn test_type_id_parse_errors() {
+        let suffix = "01h455vb4pex5vsknk084sn02q";
+        assert_ok!(Id::<Prefix, Uuid>::parse_type_id(&format!(
+            "legacy_prefix_{suffix}"
+        )));
+        assert_eq!(
+            assert_err!(Id::<Prefix, Uuid>::parse_type_id(&format!("user_{suffix}"))),
+            TypeIdError::PrefixMismatch {
+                expected: "prefix".to_string(),
+                actual: "user".to_string(),
+            }
+        );
+        assert_matches!(
+            Id::<Prefix, Uuid>::parse_type_id(suffix),
+            Err(TypeIdError::PrefixMismatch { .. })
+        );
+        for suffix in [
+            "01H455VB4PEX5VSKNK084SN02Q",
+            "01h455vb4pex5vsknk084sn02",
+            "81h455vb4pex5vsknk084sn02q",
+            "01h455vb4pex5vsknk084sn0uq",
+        ] {
+            assert_matches!(
+                Id::<Prefix, Uuid>::parse_type_id(&format!("prefix_{suffix}")),
+                Err(TypeIdError::InvalidSuffix(_))
+            );
+        }
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Record {
+        #[serde(with = "crate::typeid")]
+        id: Id<Prefix, Uuid>,
+    }
+
+    #[test]
+    fn test_type_id_serde() {
+        let record = Record {
+            id: Id::for_labeled(TypeIdGenerator::next_id_rep()),
+        };
+        let json = assert_ok!(serde_json::to_string(&record));
+        assert_eq!(json, format!(r#"{{"id":"{}"}}"#, record.id.to_type_id()));
+        assert_eq!(assert_ok!(serde_json::from_str::<Record>(&json)), record);
+        assert_eq!(record.id.id.get_version_num(), 7);
+    }
+}
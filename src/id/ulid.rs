@@ -0,0 +1,596 @@
+use crate::clock;
+#[cfg(feature = "sqlx")]
+use crate::config::ConfigError;
+use crate::id::{AsRawId, IdGenerator, RawId, RawIdError, TimeOrderedIdGenerator, TryIdGenerator};
+#[cfg(feature = "sqlx")]
+use once_cell::sync::OnceCell;
+use rand::RngCore;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::borrow::Cow;
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
+#[cfg(feature = "sqlx")]
+use strum_macros::{Display, EnumString, IntoStaticStr, VariantNames};
+use thiserror::Error;
+
+const TIMESTAMP_LEN: usize = 6;
+const RANDOM_LEN: usize = 10;
+pub const BYTES_LEN: usize = TIMESTAMP_LEN + RANDOM_LEN;
+const ENCODED_LEN: usize = 26;
+const CROCKFORD: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+const MAX_TIMESTAMP_MILLIS: u64 = (1 << 48) - 1;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum UlidError {
+    #[error("ULID must be {ENCODED_LEN} characters, but was {0}")]
+    InvalidLength(usize),
+
+    #[error("invalid ULID character: {0:?}")]
+    InvalidCharacter(char),
+
+    #[error("ULID value exceeds 128 bits")]
+    Overflow,
+}
+
+/// A Universally Unique Lexicographically Sortable IDentifier: a 48-bit millisecond timestamp
+/// followed by 80 random bits, rendered as 26 Crockford base32 characters in generation order.
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "diesel",
+    derive(::diesel::expression::AsExpression, ::diesel::deserialize::FromSqlRow),
+    diesel(sql_type = ::diesel::sql_types::Binary)
+)]
+pub struct Ulid([u8; BYTES_LEN]);
+
+impl Ulid {
+    /// Creates a ULID for the current time with random bits.
+    pub fn new() -> Self {
+        Self::from_datetime(clock::now())
+    }
+
+    /// Creates a ULID dated at `timestamp` with random bits, so ids minted for historical records
+    /// sort among ids generated at that time.
+    pub fn from_datetime(timestamp: SystemTime) -> Self {
+        let mut random = [0; RANDOM_LEN];
+        rand::thread_rng().fill_bytes(&mut random);
+        Self::from_parts(timestamp, random)
+    }
+
+    /// Creates a ULID from its timestamp and random bits. Timestamps outside the ULID range
+    /// saturate to the nearest representable millisecond.
+    pub fn from_parts(timestamp: SystemTime, random: [u8; RANDOM_LEN]) -> Self {
+        let millis = timestamp
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |since| {
+                u64::try_from(since.as_millis()).unwrap_or(u64::MAX)
+            })
+            .min(MAX_TIMESTAMP_MILLIS);
+
+        let mut bytes = [0; BYTES_LEN];
+        bytes[..TIMESTAMP_LEN].copy_from_slice(&millis.to_be_bytes()[8 - TIMESTAMP_LEN..]);
+        bytes[TIMESTAMP_LEN..].copy_from_slice(&random);
+        Self(bytes)
+    }
+
+    /// The all-zero ULID, which no generator produces after the Unix epoch.
+    pub const fn nil() -> Self {
+        Self([0; BYTES_LEN])
+    }
+
+    pub const fn from_bytes(bytes: [u8; BYTES_LEN]) -> Self {
+        Self(bytes)
+    }
+
+    pub const fn as_bytes(&self) -> &[u8; BYTES_LEN] {
+        &self.0
+    }
+
+    pub const fn from_u128(value: u128) -> Self {
+        Self(value.to_be_bytes())
+    }
+
+    pub const fn to_u128(&self) -> u128 {
+        u128::from_be_bytes(self.0)
+    }
+
+    pub fn timestamp(&self) -> SystemTime {
+        let mut millis = [0; 8];
+        millis[8 - TIMESTAMP_LEN..].copy_from_slice(&self.0[..TIMESTAMP_LEN]);
+        SystemTime::UNIX_EPOCH + Duration::from_millis(u64::from_be_bytes(millis))
+    }
+
+    pub fn random(&self) -> &[u8] {
+        &self.0[TIMESTAMP_LEN..]
+    }
+}
+
+impl Default for Ulid {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for Ulid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut value = self.to_u128();
+        let mut encoded = [CROCKFORD[0]; ENCODED_LEN];
+        for digit in encoded.iter_mut().rev() {
+            *digit = CROCKFORD[(value & 0x1f) as usize];
+            value >>= 5;
+        }
+
+        let encoded = std::str::from_utf8(&encoded).map_err(|_| fmt::Error)?;
+        f.write_str(encoded)
+    }
+}
+
+impl fmt::Debug for Ulid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Ulid({self})")
+    }
+}
+
+/// Parses the Crockford base32 form case-insensitively.
+impl FromStr for Ulid {
+    type Err = UlidError;
+
+    fn from_str(rep: &str) -> Result<Self, Self::Err> {
+        if rep.len() != ENCODED_LEN {
+            return Err(UlidError::InvalidLength(rep.chars().count()));
+        }
+
+        let mut value = 0_u128;
+        for (position, c) in rep.chars().enumerate() {
+            let digit = CROCKFORD
+                .iter()
+                .position(|d| char::from(*d) == c.to_ascii_uppercase())
+                .ok_or(UlidError::InvalidCharacter(c))?;
+
+            // 26 characters hold 130 bits, so the leading character is limited to 3 bits
+            if position == 0 && 7 < digit {
+                return Err(UlidError::Overflow);
+            }
+            value = (value << 5) | digit as u128;
+        }
+
+        Ok(Self::from_u128(value))
+    }
+}
+
+/// Serializes as the encoded string in human-readable formats and as the 16 raw bytes in compact
+/// formats (e.g., bincode or MessagePack), as `Uuid` does.
+impl Serialize for Ulid {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            serializer.serialize_bytes(&self.0)
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Ulid {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            let rep = Cow::<'de, str>::deserialize(deserializer)?;
+            Self::from_str(&rep).map_err(de::Error::custom)
+        } else {
+            deserializer.deserialize_bytes(UlidBytesVisitor)
+        }
+    }
+}
+
+struct UlidBytesVisitor;
+
+impl<'de> de::Visitor<'de> for UlidBytesVisitor {
+    type Value = Ulid;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{BYTES_LEN} ULID bytes")
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        <[u8; BYTES_LEN]>::try_from(v)
+            .map(Ulid::from_bytes)
+            .map_err(|_| E::invalid_length(v.len(), &self))
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bytes = [0; BYTES_LEN];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(i, &self))?;
+        }
+        Ok(Ulid::from_bytes(bytes))
+    }
+}
+
+impl AsRawId for Ulid {
+    fn as_str(&self) -> Cow<'_, str> {
+        Cow::Owned(self.to_string())
+    }
+
+    fn as_u128(&self) -> Option<u128> {
+        Some(self.to_u128())
+    }
+
+    fn as_bytes(&self) -> Option<Cow<'_, [u8]>> {
+        Some(Cow::Borrowed(self.0.as_slice()))
+    }
+}
+
+impl From<Ulid> for RawId {
+    fn from(id: Ulid) -> Self {
+        Self::Bytes(id.0)
+    }
+}
+
+impl TryFrom<RawId> for Ulid {
+    type Error = RawIdError;
+
+    fn try_from(raw: RawId) -> Result<Self, Self::Error> {
+        match &raw {
+            RawId::Bytes(rep) => Ok(Self(*rep)),
+            RawId::U128(rep) => Ok(Self::from_u128(*rep)),
+            RawId::Str(rep) => Self::from_str(rep).map_err(|_| RawIdError::new("Ulid", raw)),
+            RawId::I64(_) => Err(RawIdError::new("Ulid", raw)),
+        }
+    }
+}
+
+/// ULIDs and UUIDs share a 128-bit layout, so convert losslessly, e.g., to store ULIDs in `uuid`
+/// columns.
+#[cfg(feature = "uuid")]
+impl From<Ulid> for ::uuid::Uuid {
+    fn from(id: Ulid) -> Self {
+        Self::from_bytes(id.0)
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl From<::uuid::Uuid> for Ulid {
+    fn from(id: ::uuid::Uuid) -> Self {
+        Self(id.into_bytes())
+    }
+}
+
+#[cfg(feature = "sqlx")]
+static ULID_STORAGE: OnceCell<UlidStorage> = OnceCell::new();
+
+/// How ULIDs are stored via sqlx, selected once per application via [`UlidStorage::try_init()`]
+/// (or the `ulid_storage` configuration setting). Both encoding and decoding honor the selection.
+#[cfg(feature = "sqlx")]
+#[derive(
+    Debug,
+    Default,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Hash,
+    Display,
+    IntoStaticStr,
+    EnumString,
+    VariantNames,
+    Serialize,
+    Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum UlidStorage {
+    /// The 26 character Crockford base32 text form.
+    #[default]
+    Text,
+
+    /// The 16 byte form, in a UUID column.
+    #[cfg(feature = "uuid")]
+    Uuid,
+}
+
+#[cfg(feature = "sqlx")]
+impl UlidStorage {
+    /// Selects the storage format, returning [`ConfigError::AlreadyInitialized`] if a different
+    /// format was previously selected or already used.
+    pub fn try_init(storage: Self) -> Result<(), ConfigError> {
+        if *ULID_STORAGE.get_or_init(|| storage) != storage {
+            return Err(ConfigError::AlreadyInitialized("Ulid storage"));
+        }
+        Ok(())
+    }
+
+    /// Checks that [`try_init()`](Self::try_init) would succeed for the storage format, without
+    /// selecting it.
+    pub fn check_init(storage: Self) -> Result<(), ConfigError> {
+        match ULID_STORAGE.get() {
+            Some(current) if *current != storage => {
+                Err(ConfigError::AlreadyInitialized("Ulid storage"))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// The selected storage format. The format is fixed on first use, defaulting to
+    /// [`UlidStorage::Text`], so values are never encoded and decoded inconsistently.
+    pub fn current() -> Self {
+        *ULID_STORAGE.get_or_init(Self::default)
+    }
+}
+
+#[cfg(all(feature = "sqlx", not(feature = "uuid")))]
+impl<'q, DB> sqlx::Encode<'q, DB> for Ulid
+where
+    String: sqlx::Encode<'q, DB>,
+    DB: sqlx::Database,
+{
+    fn encode_by_ref(
+        &self,
+        buf: &mut <DB as sqlx::database::HasArguments<'q>>::ArgumentBuffer,
+    ) -> sqlx::encode::IsNull {
+        <String as sqlx::Encode<DB>>::encode(self.to_string(), buf)
+    }
+}
+
+#[cfg(all(feature = "sqlx", not(feature = "uuid")))]
+impl<'r, DB> sqlx::Decode<'r, DB> for Ulid
+where
+    String: sqlx::Decode<'r, DB>,
+    DB: sqlx::Database,
+{
+    fn decode(
+        value: <DB as sqlx::database::HasValueRef<'r>>::ValueRef,
+    ) -> Result<Self, sqlx::error::BoxDynError> {
+        let rep = <String as sqlx::Decode<DB>>::decode(value)?;
+        Ok(Self::from_str(&rep)?)
+    }
+}
+
+#[cfg(all(feature = "sqlx", not(feature = "uuid")))]
+impl<DB> sqlx::Type<DB> for Ulid
+where
+    String: sqlx::Type<DB>,
+    DB: sqlx::Database,
+{
+    fn type_info() -> DB::TypeInfo {
+        <String as sqlx::Type<DB>>::type_info()
+    }
+
+    fn compatible(ty: &DB::TypeInfo) -> bool {
+        <String as sqlx::Type<DB>>::compatible(ty)
+    }
+}
+
+#[cfg(all(feature = "sqlx", feature = "uuid"))]
+impl<'q, DB> sqlx::Encode<'q, DB> for Ulid
+where
+    String: sqlx::Encode<'q, DB>,
+    ::uuid::Uuid: sqlx::Encode<'q, DB>,
+    DB: sqlx::Database,
+{
+    fn encode_by_ref(
+        &self,
+        buf: &mut <DB as sqlx::database::HasArguments<'q>>::ArgumentBuffer,
+    ) -> sqlx::encode::IsNull {
+        match UlidStorage::current() {
+            UlidStorage::Text => <String as sqlx::Encode<DB>>::encode(self.to_string(), buf),
+            UlidStorage::Uuid => <::uuid::Uuid as sqlx::Encode<DB>>::encode((*self).into(), buf),
+        }
+    }
+}
+
+#[cfg(all(feature = "sqlx", feature = "uuid"))]
+impl<'r, DB> sqlx::Decode<'r, DB> for Ulid
+where
+    String: sqlx::Decode<'r, DB>,
+    ::uuid::Uuid: sqlx::Decode<'r, DB>,
+    DB: sqlx::Database,
+{
+    fn decode(
+        value: <DB as sqlx::database::HasValueRef<'r>>::ValueRef,
+    ) -> Result<Self, sqlx::error::BoxDynError> {
+        match UlidStorage::current() {
+            UlidStorage::Text => {
+                let rep = <String as sqlx::Decode<DB>>::decode(value)?;
+                Ok(Self::from_str(&rep)?)
+            }
+            UlidStorage::Uuid => <::uuid::Uuid as sqlx::Decode<DB>>::decode(value).map(Self::from),
+        }
+    }
+}
+
+#[cfg(all(feature = "sqlx", feature = "uuid"))]
+impl<DB> sqlx::Type<DB> for Ulid
+where
+    String: sqlx::Type<DB>,
+    ::uuid::Uuid: sqlx::Type<DB>,
+    DB: sqlx::Database,
+{
+    fn type_info() -> DB::TypeInfo {
+        match UlidStorage::current() {
+            UlidStorage::Text => <String as sqlx::Type<DB>>::type_info(),
+            UlidStorage::Uuid => <::uuid::Uuid as sqlx::Type<DB>>::type_info(),
+        }
+    }
+
+    fn compatible(ty: &DB::TypeInfo) -> bool {
+        match UlidStorage::current() {
+            UlidStorage::Text => <String as sqlx::Type<DB>>::compatible(ty),
+            UlidStorage::Uuid => <::uuid::Uuid as sqlx::Type<DB>>::compatible(ty),
+        }
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for Ulid {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(f, "Ulid({=u128:#x})", self.to_u128())
+    }
+}
+
+pub struct UlidGenerator;
+
+impl UlidGenerator {
+    /// Generates a ULID dated at `timestamp`; e.g., when backfilling records, so their ids keep
+    /// the time order of the original events.
+    pub fn next_id_at(timestamp: SystemTime) -> Ulid {
+        Ulid::from_datetime(timestamp)
+    }
+}
+
+impl IdGenerator for UlidGenerator {
+    type IdType = Ulid;
+
+    fn next_id_rep() -> Self::IdType {
+        Ulid::new()
+    }
+}
+
+impl TryIdGenerator for UlidGenerator {}
+
+impl TimeOrderedIdGenerator for UlidGenerator {
+    fn next_id_rep_at(timestamp: SystemTime) -> Self::IdType {
+        Self::next_id_at(timestamp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use claim::*;
+    use pretty_assertions::assert_eq;
+
+    const EXAMPLE_REP: &str = "01ARZ3NDEKTSV4RRFFQ69G5FAV";
+
+    #[test]
+    fn test_ulid_encoding() {
+        let ulid: Ulid = assert_ok!(EXAMPLE_REP.parse());
+        assert_eq!(ulid.to_string(), EXAMPLE_REP);
+        assert_eq!(
+            ulid.timestamp(),
+            SystemTime::UNIX_EPOCH + Duration::from_millis(1_469_922_850_259)
+        );
+
+        let lower: Ulid = assert_ok!(EXAMPLE_REP.to_lowercase().parse());
+        assert_eq!(lower, ulid);
+
+        let rebuilt = Ulid::from_parts(ulid.timestamp(), ulid.random().try_into().unwrap());
+        assert_eq!(rebuilt, ulid);
+
+        assert_eq!(Ulid::from_u128(0).to_string(), "00000000000000000000000000");
+        assert_eq!(
+            Ulid::from_u128(u128::MAX).to_string(),
+            "7ZZZZZZZZZZZZZZZZZZZZZZZZZ"
+        );
+    }
+
+    #[test]
+    fn test_ulid_serde_tokens() {
+        use serde_test::{assert_de_tokens_error, assert_tokens, Configure, Token};
+
+        const BYTES: [u8; BYTES_LEN] = [
+            1, 86, 62, 58, 181, 211, 214, 118, 76, 97, 239, 185, 147, 2, 189, 91,
+        ];
+        let ulid: Ulid = assert_ok!(EXAMPLE_REP.parse());
+        assert_eq!(ulid.as_bytes(), &BYTES);
+        assert_tokens(&ulid.readable(), &[Token::Str(EXAMPLE_REP)]);
+        assert_tokens(&ulid.compact(), &[Token::Bytes(&BYTES)]);
+        assert_de_tokens_error::<serde_test::Compact<Ulid>>(
+            &[Token::Bytes(&[0; 4])],
+            "invalid length 4, expected 16 ULID bytes",
+        );
+    }
+
+    #[test]
+    fn test_ulid_parse_errors() {
+        assert_eq!(
+            assert_err!("01ARZ3NDEKTSV4RRFFQ69G5FA".parse::<Ulid>()),
+            UlidError::InvalidLength(25)
+        );
+        assert_eq!(
+            assert_err!("01ARZ3NDEKTSV4RRFFQ69G5FAU".parse::<Ulid>()),
+            UlidError::InvalidCharacter('U')
+        );
+        assert_eq!(
+            assert_err!("80000000000000000000000000".parse::<Ulid>()),
+            UlidError::Overflow
+        );
+    }
+
+    #[test]
+    fn test_ulid_generator_at_timestamp() {
+        let timestamp = SystemTime::UNIX_EPOCH + Duration::from_millis(1_469_922_850_259);
+        let backfilled = UlidGenerator::next_id_at(timestamp);
+        assert_eq!(backfilled.timestamp(), timestamp);
+
+        let later = UlidGenerator::next_id_at(timestamp + Duration::from_millis(1));
+        assert!(backfilled < later);
+        assert!(backfilled.to_string() < later.to_string());
+
+        let generated = <UlidGenerator as IdGenerator>::next_id_rep();
+        assert!(later < generated);
+    }
+
+    #[test]
+    fn test_ulid_raw_id() {
+        let ulid: Ulid = assert_ok!(EXAMPLE_REP.parse());
+        let raw = RawId::from(ulid);
+        assert_matches!(raw, RawId::Bytes(_));
+        assert_eq!(assert_ok!(Ulid::try_from(raw)), ulid);
+        assert_eq!(assert_ok!(Ulid::try_from(RawId::from(EXAMPLE_REP))), ulid);
+        assert_err!(Ulid::try_from(RawId::I64(17)));
+    }
+
+    struct Import;
+
+    impl crate::Label for Import {
+        type Labeler = crate::CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            crate::CustomLabeling::new("Import")
+        }
+    }
+
+    impl crate::Entity for Import {
+        type IdGen = UlidGenerator;
+    }
+
+    #[test]
+    fn test_entity_next_id_at() {
+        use crate::Entity;
+
+        let timestamp = SystemTime::UNIX_EPOCH + Duration::from_millis(1_469_922_850_259);
+        let id = Import::next_id_at(timestamp);
+        assert_eq!(id.label, "Import");
+        assert_eq!(id.id.timestamp(), timestamp);
+        assert!(id < Import::next_id());
+    }
+
+    #[cfg(feature = "sqlx")]
+    #[test]
+    fn test_ulid_storage_is_fixed_on_first_use() {
+        let storage = UlidStorage::current();
+        assert_ok!(UlidStorage::try_init(storage));
+        assert_eq!(UlidStorage::current(), storage);
+        assert_eq!(assert_ok!("text".parse::<UlidStorage>()), UlidStorage::Text);
+
+        #[cfg(feature = "uuid")]
+        {
+            let other = match storage {
+                UlidStorage::Text => UlidStorage::Uuid,
+                UlidStorage::Uuid => UlidStorage::Text,
+            };
+            assert_matches!(
+                UlidStorage::try_init(other),
+                Err(ConfigError::AlreadyInitialized(_))
+            );
+            assert_eq!(assert_ok!("uuid".parse::<UlidStorage>()), UlidStorage::Uuid);
+        }
+    }
+}
@@ -0,0 +1,205 @@
+use crate::{accepts_label, Id, Label, Labeling};
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Leading scheme of every rendered URN.
+pub const URN_SCHEME: &str = "urn";
+
+const URN_SEPARATOR: char = ':';
+const MAX_NAMESPACE_LEN: usize = 32;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum UrnError {
+    #[error("URN must start with `{URN_SCHEME}:`: {0}")]
+    MissingScheme(String),
+
+    #[error("URN namespace `{0}` is not a valid namespace identifier")]
+    InvalidNamespace(String),
+
+    #[error("URN is missing its {0} component")]
+    MissingComponent(&'static str),
+
+    #[error("URN label `{actual}` does not match expected label `{expected}`")]
+    LabelMismatch { expected: String, actual: String },
+
+    #[error("failed to parse URN id: {0}")]
+    InvalidId(String),
+}
+
+impl<T: ?Sized, ID: fmt::Display> Id<T, ID> {
+    /// Renders the id as `urn:<namespace>:<label>:<id>`.
+    ///
+    /// The namespace should be a valid RFC 8141 namespace identifier (2 to 32 ASCII alphanumeric
+    /// or `-` characters, not starting or ending with `-`); otherwise [`Id::from_urn`] will reject
+    /// the rendered URN.
+    pub fn to_urn(&self, namespace: &str) -> String {
+        format!(
+            "{URN_SCHEME}{URN_SEPARATOR}{namespace}{URN_SEPARATOR}{}{URN_SEPARATOR}{}",
+            self.label, self.id
+        )
+    }
+}
+
+impl<T, ID> Id<T, ID>
+where
+    T: ?Sized + Label,
+    ID: FromStr,
+    <ID as FromStr>::Err: fmt::Display,
+{
+    /// Parses a `urn:<namespace>:<label>:<id>` URN, verifying the label matches `T` or one of its
+    /// aliases. The parsed id carries `T`'s current label.
+    pub fn from_urn(urn: &str) -> Result<Self, UrnError> {
+        let mut components = urn.splitn(3, URN_SEPARATOR);
+        if !components
+            .next()
+            .is_some_and(|scheme| scheme.eq_ignore_ascii_case(URN_SCHEME))
+        {
+            return Err(UrnError::MissingScheme(urn.to_string()));
+        }
+
+        let namespace = components
+            .next()
+            .ok_or(UrnError::MissingComponent("namespace"))?;
+        if !is_valid_namespace(namespace) {
+            return Err(UrnError::InvalidNamespace(namespace.to_string()));
+        }
+
+        let (label, id) = components
+            .next()
+            .and_then(|nss| nss.split_once(URN_SEPARATOR))
+            .ok_or(UrnError::MissingComponent("label"))?;
+
+        let labeler = <T as Label>::labeler();
+        if !accepts_label::<T>(label) {
+            return Err(UrnError::LabelMismatch {
+                expected: labeler.label().to_string(),
+                actual: label.to_string(),
+            });
+        }
+
+        if id.is_empty() {
+            return Err(UrnError::MissingComponent("id"));
+        }
+        let id = ID::from_str(id).map_err(|err| UrnError::InvalidId(err.to_string()))?;
+        Ok(Self::direct_labeled(labeler.label(), id))
+    }
+}
+
+fn is_valid_namespace(namespace: &str) -> bool {
+    (2..=MAX_NAMESPACE_LEN).contains(&namespace.len())
+        && !namespace.starts_with('-')
+        && !namespace.ends_with('-')
+        && namespace
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CustomLabeling, NoLabeling};
+    use claim::*;
+    use pretty_assertions::assert_eq;
+
+    struct User;
+
+    impl Label for User {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("User")
+        }
+
+        fn aliases() -> &'static [&'static str] {
+            &["Member"]
+        }
+    }
+
+    struct Account;
+
+    impl Label for Account {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("Account")
+        }
+    }
+
+    struct Unlabeled;
+
+    impl Label for Unlabeled {
+        type Labeler = NoLabeling;
+
+        fn labeler() -> Self::Labeler {
+            NoLabeling
+        }
+    }
+
+    #[test]
+    fn test_urn_roundtrip() {
+        let id: Id<User, String> = Id::for_labeled("ig6wv6nezj0jg51lg53dztqy".to_string());
+        let urn = id.to_urn("acme");
+        assert_eq!(urn, "urn:acme:User:ig6wv6nezj0jg51lg53dztqy");
+
+        let actual: Id<User, String> = assert_ok!(Id::from_urn(&urn));
+        assert_eq!(actual, id);
+        assert_eq!(actual.label, id.label);
+
+        let actual: Id<User, String> = assert_ok!(Id::from_urn("URN:acme:User:a:b"));
+        assert_eq!(actual.id, "a:b");
+
+        let id: Id<Unlabeled, u64> = Id::for_labeled(17);
+        assert_eq!(id.to_urn("acme"), "urn:acme::17");
+        assert_eq!(
+            assert_ok!(Id::<Unlabeled, u64>::from_urn("urn:acme::17")),
+            id
+        );
+    }
+
+    #[test]
+    fn test_urn_legacy_label_alias() {
+        let actual: Id<User, u64> = assert_ok!(Id::from_urn("urn:acme:Member:7"));
+        assert_eq!(actual.label, "User");
+        assert_eq!(actual.id, 7);
+        assert_matches!(
+            Id::<Account, u64>::from_urn("urn:acme:Member:7"),
+            Err(UrnError::LabelMismatch { .. })
+        );
+    }
+
+    #[test]
+    fn test_urn_parse_errors() {
+        assert_eq!(
+            assert_err!(Id::<User, u64>::from_urn("uri:acme:User:1")),
+            UrnError::MissingScheme("uri:acme:User:1".to_string())
+        );
+        assert_eq!(
+            assert_err!(Id::<User, u64>::from_urn("urn:-acme:User:1")),
+            UrnError::InvalidNamespace("-acme".to_string())
+        );
+        assert_eq!(
+            assert_err!(Id::<User, u64>::from_urn("urn")),
+            UrnError::MissingComponent("namespace")
+        );
+        assert_eq!(
+            assert_err!(Id::<User, u64>::from_urn("urn:acme:User")),
+            UrnError::MissingComponent("label")
+        );
+        assert_eq!(
+            assert_err!(Id::<User, u64>::from_urn("urn:acme:User:")),
+            UrnError::MissingComponent("id")
+        );
+        assert_eq!(
+            assert_err!(Id::<Account, u64>::from_urn("urn:acme:User:1")),
+            UrnError::LabelMismatch {
+                expected: "Account".to_string(),
+                actual: "User".to_string(),
+            }
+        );
+        assert_matches!(
+            Id::<User, u64>::from_urn("urn:acme:User:one"),
+            Err(UrnError::InvalidId(_))
+        );
+    }
+}
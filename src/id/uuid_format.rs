@@ -0,0 +1,164 @@
+//! Alternative textual forms of UUID ids, for interop with APIs that expect, e.g., the 32
+//! character simple form rather than the default hyphenated one.
+
+use crate::{Id, Label};
+use std::fmt;
+use std::str::FromStr;
+use uuid::Uuid;
+
+use super::IdParseError;
+
+/// The textual form of a UUID; see [`Id::display_as()`] and [`Id::parse_as()`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UuidFormat {
+    /// `67e55044-10b1-426f-9247-bb680e5fe0c8`
+    #[default]
+    Hyphenated,
+
+    /// `67e5504410b1426f9247bb680e5fe0c8`
+    Simple,
+
+    /// `{67e55044-10b1-426f-9247-bb680e5fe0c8}`
+    Braced,
+
+    /// `urn:uuid:67e55044-10b1-426f-9247-bb680e5fe0c8`
+    Urn,
+}
+
+impl UuidFormat {
+    fn write(self, uuid: &Uuid, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Hyphenated => write!(f, "{}", uuid.hyphenated()),
+            Self::Simple => write!(f, "{}", uuid.simple()),
+            Self::Braced => write!(f, "{}", uuid.braced()),
+            Self::Urn => write!(f, "{}", uuid.urn()),
+        }
+    }
+
+    const fn len(self) -> usize {
+        match self {
+            Self::Hyphenated => uuid::fmt::Hyphenated::LENGTH,
+            Self::Simple => uuid::fmt::Simple::LENGTH,
+            Self::Braced => uuid::fmt::Braced::LENGTH,
+            Self::Urn => uuid::fmt::Urn::LENGTH,
+        }
+    }
+}
+
+struct UuidDisplay<'a, T: ?Sized> {
+    id: &'a Id<T, Uuid>,
+    format: UuidFormat,
+}
+
+impl<T: ?Sized + Label> fmt::Display for UuidDisplay<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if !f.alternate() && !self.id.label.is_empty() {
+            f.write_str(&self.id.label)?;
+            f.write_str(T::DELIMITER)?;
+        }
+        self.format.write(&self.id.id, f)
+    }
+}
+
+impl<T: ?Sized + Label> Id<T, Uuid> {
+    /// Displays the id as [`Display`](fmt::Display) does, but with the UUID in the given form.
+    ///
+    /// ```rust
+    /// use tagid::{Id, Label, UuidFormat};
+    ///
+    /// #[derive(Label)]
+    /// struct Order;
+    ///
+    /// let rep = uuid::Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+    /// let id: Id<Order, _> = Id::for_labeled(rep);
+    /// let simple = id.display_as(UuidFormat::Simple).to_string();
+    /// assert_eq!(simple, "Order::67e5504410b1426f9247bb680e5fe0c8");
+    /// assert_eq!(Id::<Order, _>::parse_as(&simple, UuidFormat::Simple).unwrap(), id);
+    /// ```
+    pub fn display_as(&self, format: UuidFormat) -> impl fmt::Display + '_ {
+        UuidDisplay { id: self, format }
+    }
+
+    /// Parses the labeled form rendered by [`Id::display_as()`], requiring the UUID in exactly the
+    /// given form, in either case.
+    pub fn parse_as(rep: &str, format: UuidFormat) -> Result<Self, IdParseError> {
+        let labeled = Id::<T, String>::from_str(rep)?;
+        let uuid = Uuid::try_parse(&labeled.id)
+            .ok()
+            // each form has a distinct length, so the length pins the form `try_parse` accepted
+            .filter(|_| labeled.id.len() == format.len())
+            .ok_or_else(|| {
+                IdParseError::InvalidId(format!("`{}` is not a {format:?} UUID", labeled.id))
+            })?;
+        Ok(Self::direct_labeled(labeled.label, uuid))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CustomLabeling;
+    use claim::*;
+    use pretty_assertions::assert_eq;
+
+    struct Order;
+
+    impl Label for Order {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("Order")
+        }
+    }
+
+    #[test]
+    fn test_uuid_formats() {
+        let rep = Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+        let id: Id<Order, Uuid> = Id::for_labeled(rep);
+        let cases = [
+            (
+                UuidFormat::Hyphenated,
+                "67e55044-10b1-426f-9247-bb680e5fe0c8",
+            ),
+            (UuidFormat::Simple, "67e5504410b1426f9247bb680e5fe0c8"),
+            (UuidFormat::Braced, "{67e55044-10b1-426f-9247-bb680e5fe0c8}"),
+            (
+                UuidFormat::Urn,
+                "urn:uuid:67e55044-10b1-426f-9247-bb680e5fe0c8",
+            ),
+        ];
+        for (format, expected) in cases {
+            let rendered = id.display_as(format).to_string();
+            assert_eq!(rendered, format!("Order::{expected}"));
+            assert_eq!(format!("{:#}", id.display_as(format)), expected);
+
+            let actual = assert_ok!(Id::<Order, Uuid>::parse_as(&rendered, format));
+            assert_eq!(actual.label, "Order");
+            assert_eq!(actual, id);
+        }
+
+        assert_eq!(
+            id.display_as(UuidFormat::default()).to_string(),
+            id.to_string()
+        );
+        let upper = assert_ok!(Id::<Order, Uuid>::parse_as(
+            "Order::67E5504410B1426F9247BB680E5FE0C8",
+            UuidFormat::Simple
+        ));
+        assert_eq!(upper, id);
+        assert_err!(Id::<Order, Uuid>::parse_as(
+            "Order::67e55044-10b1-426f-9247-bb680e5fe0c8",
+            UuidFormat::Simple
+        ));
+        assert_eq!(
+            assert_err!(Id::<Order, Uuid>::parse_as(
+                "Invoice::67e5504410b1426f9247bb680e5fe0c8",
+                UuidFormat::Simple
+            )),
+            IdParseError::LabelMismatch {
+                expected: "Order".to_string(),
+                actual: "Invoice".to_string()
+            }
+        );
+    }
+}
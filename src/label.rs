@@ -1,9 +1,69 @@
 use crate::{CustomLabeling, Labeling, MakeLabeling, NoLabeling};
-use std::collections::HashMap;
+use once_cell::sync::Lazy;
+use smol_str::SmolStr;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::rc::Rc;
+use std::sync::{Arc, RwLock};
 
+/// Types whose ids carry a label, usually via `#[derive(Label)]`.
+///
+/// Containers such as `Option<T>`, `Vec<T>`, `[T; N]` and `Arc<T>` are labeled as their element
+/// type `T`. Tuples (up to arity 4) are labeled as their first element type, so an `(Order, Audit)`
+/// pair is labeled `Order` and its ids do not name the remaining elements.
 pub trait Label {
     type Labeler: Labeling;
+
+    /// Separates the label from the id in the labeled `Label::id` form of the type's ids, e.g.,
+    /// `"/"` for ids that must render as `user/abc123`.
+    const DELIMITER: &'static str = crate::DELIMITER;
+
+    /// The label, if fixed at compile time, for const contexts such as match arms. Set by
+    /// `#[derive(Label)]` for types whose label does not depend on generic parameters.
+    ///
+    /// It is an `Option` because not every label is a constant: hand-written impls and generic
+    /// types may only know their label at runtime, via [`Label::labeler()`]. A default of `None`
+    /// lets them implement `Label` without claiming a constant that could disagree with it.
+    const LABEL: Option<&'static str> = None;
+
     fn labeler() -> Self::Labeler;
+
+    /// Previous labels of the type, still accepted when parsing or decoding tagged ids (e.g., after
+    /// an entity is renamed).
+    fn aliases() -> &'static [&'static str] {
+        &[]
+    }
+}
+
+static LABEL_ALIASES: Lazy<RwLock<HashMap<SmolStr, Vec<SmolStr>>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// Registers an additional alias for `T`'s label at runtime, supplementing those declared by
+/// [`Label::aliases()`].
+pub fn register_label_alias<T: ?Sized + Label>(alias: impl AsRef<str>) {
+    let label = <T as Label>::labeler().label_smol();
+    let alias = SmolStr::new(alias);
+    let mut registry = LABEL_ALIASES
+        .write()
+        .expect("label alias registry lock poisoned");
+    let aliases = registry.entry(label).or_default();
+    if !aliases.contains(&alias) {
+        aliases.push(alias);
+    }
+    drop(registry);
+}
+
+/// Whether `label` identifies `T`, as either its current label or one of its aliases.
+pub fn accepts_label<T: ?Sized + Label>(label: &str) -> bool {
+    let labeler = <T as Label>::labeler();
+    if label == labeler.label() || T::aliases().contains(&label) {
+        return true;
+    }
+
+    LABEL_ALIASES
+        .read()
+        .expect("label alias registry lock poisoned")
+        .get(labeler.label())
+        .is_some_and(|aliases| aliases.iter().any(|alias| alias == label))
 }
 
 impl Label for () {
@@ -14,30 +74,65 @@ impl Label for () {
     }
 }
 
-impl<T: Label> Label for Option<T> {
-    type Labeler = <T as Label>::Labeler;
+/// Labels containers and tuples as their (first) element type.
+macro_rules! delegate_label {
+    (<$t:ident, const $n:ident: usize> $container:ty) => {
+        delegate_label!(@impl [$t: Label, const $n: usize] $t, $container);
+    };
+    (<$t:ident $(: ?$sized:ident)? $(, $rest:ident)*> $container:ty) => {
+        delegate_label!(@impl [$t: $(?$sized +)? Label $(, $rest)*] $t, $container);
+    };
+    (@impl [$($params:tt)*] $t:ident, $container:ty) => {
+        impl<$($params)*> Label for $container {
+            type Labeler = <$t as Label>::Labeler;
+            const DELIMITER: &'static str = <$t as Label>::DELIMITER;
+            const LABEL: Option<&'static str> = <$t as Label>::LABEL;
 
-    fn labeler() -> Self::Labeler {
-        <T as Label>::labeler()
-    }
+            fn labeler() -> Self::Labeler {
+                <$t as Label>::labeler()
+            }
+
+            fn aliases() -> &'static [&'static str] {
+                <$t as Label>::aliases()
+            }
+        }
+    };
 }
 
-impl<T: Label, E> Label for Result<T, E> {
-    type Labeler = <T as Label>::Labeler;
+delegate_label!(<T> Option<T>);
+delegate_label!(<T, E> Result<T, E>);
+delegate_label!(<T> Vec<T>);
+delegate_label!(<T, const N: usize> [T; N]);
+delegate_label!(<T: ?Sized> Box<T>);
+delegate_label!(<T: ?Sized> Arc<T>);
+delegate_label!(<T: ?Sized> Rc<T>);
+delegate_label!(<T> HashSet<T>);
+delegate_label!(<A, B> (A, B));
+delegate_label!(<A, B, C> (A, B, C));
+delegate_label!(<A, B, C, D> (A, B, C, D));
+
+impl<K: Label, V: Label> Label for HashMap<K, V> {
+    type Labeler = CustomLabeling;
 
     fn labeler() -> Self::Labeler {
-        <T as Label>::labeler()
+        let k_labeler = <K as Label>::labeler();
+        let v_labeler = <V as Label>::labeler();
+        CustomLabeling::from(format!(
+            "HashMap<{},{}>",
+            k_labeler.label(),
+            v_labeler.label()
+        ))
     }
 }
 
-impl<K: Label, V: Label> Label for HashMap<K, V> {
+impl<K: Label, V: Label> Label for BTreeMap<K, V> {
     type Labeler = CustomLabeling;
 
     fn labeler() -> Self::Labeler {
         let k_labeler = <K as Label>::labeler();
         let v_labeler = <V as Label>::labeler();
         CustomLabeling::from(format!(
-            "HashMap<{},{}",
+            "BTreeMap<{},{}>",
             k_labeler.label(),
             v_labeler.label()
         ))
@@ -73,10 +168,67 @@ primitive_label!(u128);
 primitive_label!(usize);
 primitive_label!(String);
 
-impl<'a> Label for &'a str {
+impl Label for &str {
     type Labeler = MakeLabeling<Self>;
 
     fn labeler() -> Self::Labeler {
         MakeLabeling::<Self>::default()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Customer;
+
+    impl Label for Customer {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("Customer")
+        }
+
+        fn aliases() -> &'static [&'static str] {
+            &["Client"]
+        }
+    }
+
+    #[test]
+    fn test_accepts_label_aliases() {
+        assert!(accepts_label::<Customer>("Customer"));
+        assert!(accepts_label::<Customer>("Client"));
+        assert!(accepts_label::<Option<Customer>>("Client"));
+        assert!(!accepts_label::<Customer>("Patron"));
+        assert!(!accepts_label::<u64>("Customer"));
+
+        register_label_alias::<Customer>("Patron");
+        register_label_alias::<Customer>("Patron");
+        assert!(accepts_label::<Customer>("Patron"));
+        assert!(!accepts_label::<u64>("Patron"));
+    }
+
+    #[test]
+    fn test_container_labels() {
+        fn label_of<T: ?Sized + Label>() -> String {
+            T::labeler().label().to_string()
+        }
+
+        assert_eq!(label_of::<Box<Customer>>(), "Customer");
+        assert_eq!(label_of::<Arc<Customer>>(), "Customer");
+        assert_eq!(label_of::<Rc<Customer>>(), "Customer");
+        assert_eq!(label_of::<[Customer; 3]>(), "Customer");
+        assert_eq!(label_of::<HashSet<Customer>>(), "Customer");
+        assert_eq!(label_of::<(Customer, u8, bool)>(), "Customer");
+        assert_eq!(label_of::<(Customer, u8, bool, char)>(), "Customer");
+        assert_eq!(
+            label_of::<HashMap<Customer, u64>>(),
+            "HashMap<Customer,u64>"
+        );
+        assert_eq!(
+            label_of::<BTreeMap<Customer, u64>>(),
+            "BTreeMap<Customer,u64>"
+        );
+        assert!(accepts_label::<Arc<Customer>>("Client"));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::Label;
+use crate::{Label, DELIMITER};
 use once_cell::sync::OnceCell;
 use pretty_type_name::pretty_type_name;
 use smol_str::SmolStr;
@@ -6,9 +6,56 @@ use std::convert::Infallible;
 use std::fmt;
 use std::marker::PhantomData;
 use std::str::FromStr;
+use thiserror::Error;
 
 pub trait Labeling {
     fn label(&self) -> &str;
+
+    /// The label, if it is fixed at compile time, so ids can share rather than copy it.
+    fn static_label(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// The label as held by ids. Labelers that already own a `SmolStr` hand out a clone, which
+    /// shares rather than copies the label.
+    fn label_smol(&self) -> SmolStr {
+        self.static_label()
+            .map_or_else(|| SmolStr::new(self.label()), SmolStr::new_static)
+    }
+}
+
+/// Reasons a label would render ids whose `Display` form cannot be parsed back.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum LabelError {
+    #[error("label `{label}` contains the id delimiter `{delimiter}`")]
+    ContainsDelimiter { label: String, delimiter: String },
+
+    #[error("label {0:?} contains a control character")]
+    ControlCharacter(String),
+
+    #[error("label {0:?} has leading or trailing whitespace")]
+    SurroundingWhitespace(String),
+}
+
+/// Checks that `label` can be rendered ahead of `delimiter` and parsed back from an id's `Display`
+/// form.
+pub fn validate_label(label: &str, delimiter: &str) -> Result<(), LabelError> {
+    if !delimiter.is_empty() && label.contains(delimiter) {
+        return Err(LabelError::ContainsDelimiter {
+            label: label.to_string(),
+            delimiter: delimiter.to_string(),
+        });
+    }
+
+    if label.chars().any(char::is_control) {
+        return Err(LabelError::ControlCharacter(label.to_string()));
+    }
+
+    if label.trim() != label {
+        return Err(LabelError::SurroundingWhitespace(label.to_string()));
+    }
+
+    Ok(())
 }
 
 impl dyn Labeling {
@@ -39,11 +86,20 @@ impl<T: ?Sized> Default for MakeLabeling<T> {
     }
 }
 
-impl<T: ?Sized> Labeling for MakeLabeling<T> {
-    fn label(&self) -> &str {
+impl<T: ?Sized> MakeLabeling<T> {
+    fn type_label(&self) -> &SmolStr {
         self.label
             .get_or_init(|| SmolStr::new(pretty_type_name::<T>()))
-            .as_str()
+    }
+}
+
+impl<T: ?Sized> Labeling for MakeLabeling<T> {
+    fn label(&self) -> &str {
+        self.type_label().as_str()
+    }
+
+    fn label_smol(&self) -> SmolStr {
+        self.type_label().clone()
     }
 }
 
@@ -59,6 +115,60 @@ impl<T: ?Sized> fmt::Display for MakeLabeling<T> {
     }
 }
 
+/// Labels with a string fixed at compile time.
+///
+/// `#[derive(Label)]` generates this for types whose label does not depend on generic parameters,
+/// along with [`Label::LABEL`], usable in const contexts such as match arms.
+///
+#[cfg_attr(feature = "derive", doc = "```rust")]
+#[cfg_attr(not(feature = "derive"), doc = "```ignore")]
+/// use tagid::{Label, Labeling};
+///
+/// #[derive(Label)]
+/// #[label("ord")]
+/// struct Order;
+///
+/// const ORDER: Option<&str> = Order::LABEL;
+/// assert_eq!(<Order as Label>::labeler().static_label(), ORDER);
+/// assert!(matches!(Some("ord"), Order::LABEL));
+/// ```
+#[derive(Copy, Clone, PartialEq, Eq, Hash)]
+pub struct StaticLabeling {
+    label: &'static str,
+}
+
+impl StaticLabeling {
+    pub const fn new(label: &'static str) -> Self {
+        Self { label }
+    }
+
+    pub const fn as_str(&self) -> &'static str {
+        self.label
+    }
+}
+
+impl Labeling for StaticLabeling {
+    fn label(&self) -> &str {
+        self.label
+    }
+
+    fn static_label(&self) -> Option<&'static str> {
+        Some(self.label)
+    }
+}
+
+impl fmt::Debug for StaticLabeling {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "StaticLabeling({})", self.label)
+    }
+}
+
+impl fmt::Display for StaticLabeling {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.label)
+    }
+}
+
 #[derive(Clone)]
 pub struct CustomLabeling {
     label: SmolStr,
@@ -70,12 +180,88 @@ impl CustomLabeling {
             label: SmolStr::new(label),
         }
     }
+
+    /// Creates a labeling after checking the label against the default [`DELIMITER`] via
+    /// [`validate_label()`].
+    pub fn try_new(label: impl AsRef<str>) -> Result<Self, LabelError> {
+        let label = label.as_ref();
+        validate_label(label, DELIMITER)?;
+        Ok(Self::new(label))
+    }
+
+    /// Starts building a label qualified by namespace segments; see [`LabelNamespace`].
+    pub fn namespace(segment: impl AsRef<str>) -> LabelNamespace {
+        LabelNamespace::new(segment)
+    }
+}
+
+/// Builds a [`CustomLabeling`] from namespace segments followed by an entity name.
+///
+/// Segments are joined with [`LabelNamespace::DEFAULT_JOINER`] unless another joiner is set, which
+/// should differ from the label's [`Label::DELIMITER`] so ids remain parseable.
+///
+/// ```rust
+/// use tagid::{CustomLabeling, Labeling};
+///
+/// let labeler = CustomLabeling::namespace("billing").entity("Invoice");
+/// assert_eq!(labeler.label(), "billing.Invoice");
+///
+/// let labeler = CustomLabeling::namespace("acme")
+///     .namespace("billing")
+///     .joiner("/")
+///     .entity("Invoice");
+/// assert_eq!(labeler.label(), "acme/billing/Invoice");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LabelNamespace {
+    segments: Vec<SmolStr>,
+    joiner: SmolStr,
+}
+
+impl LabelNamespace {
+    pub const DEFAULT_JOINER: &'static str = ".";
+
+    pub fn new(segment: impl AsRef<str>) -> Self {
+        Self {
+            segments: vec![SmolStr::new(segment)],
+            joiner: SmolStr::new_static(Self::DEFAULT_JOINER),
+        }
+    }
+
+    /// Appends a nested namespace segment.
+    #[must_use]
+    pub fn namespace(mut self, segment: impl AsRef<str>) -> Self {
+        self.segments.push(SmolStr::new(segment));
+        self
+    }
+
+    /// Sets the separator placed between segments.
+    #[must_use]
+    pub fn joiner(mut self, joiner: impl AsRef<str>) -> Self {
+        self.joiner = SmolStr::new(joiner);
+        self
+    }
+
+    /// Completes the label with the entity name.
+    pub fn entity(self, name: impl AsRef<str>) -> CustomLabeling {
+        let mut label = String::new();
+        for segment in &self.segments {
+            label.push_str(segment);
+            label.push_str(&self.joiner);
+        }
+        label.push_str(name.as_ref());
+        CustomLabeling::from(label)
+    }
 }
 
 impl Labeling for CustomLabeling {
     fn label(&self) -> &str {
         self.label.as_str()
     }
+
+    fn label_smol(&self) -> SmolStr {
+        self.label.clone()
+    }
 }
 
 impl fmt::Debug for CustomLabeling {
@@ -128,3 +314,47 @@ impl fmt::Display for NoLabeling {
         write!(f, "")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use claim::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_custom_labeling_try_new() {
+        assert_eq!(
+            assert_ok!(CustomLabeling::try_new("Order")).label(),
+            "Order"
+        );
+        assert_ok!(CustomLabeling::try_new("Batch<Order, Item>"));
+        assert_ok!(CustomLabeling::try_new(""));
+
+        assert_eq!(
+            assert_err!(CustomLabeling::try_new("billing::Invoice")),
+            LabelError::ContainsDelimiter {
+                label: "billing::Invoice".to_string(),
+                delimiter: DELIMITER.to_string(),
+            }
+        );
+        assert_matches!(
+            CustomLabeling::try_new("Order\n"),
+            Err(LabelError::ControlCharacter(_))
+        );
+        assert_matches!(
+            CustomLabeling::try_new(" Order"),
+            Err(LabelError::SurroundingWhitespace(_))
+        );
+        assert_ok!(validate_label("billing::Invoice", "/"));
+    }
+
+    #[test]
+    fn test_label_smol_shares_owned_label() {
+        let labeler = CustomLabeling::new("billing.accounts_receivable.Invoice");
+        assert_eq!(labeler.label_smol().as_ptr(), labeler.label_smol().as_ptr());
+
+        let labeler = MakeLabeling::<CustomLabeling>::new();
+        assert_eq!(labeler.label_smol(), "CustomLabeling");
+        assert_eq!(StaticLabeling::new("Order").label_smol(), "Order");
+    }
+}
@@ -9,24 +9,93 @@ extern crate tagid_derive;
 #[doc(hidden)]
 pub use tagid_derive::*;
 
+pub mod checksum;
+pub mod clock;
+pub mod config;
 mod label;
 mod labeling;
+pub mod manifest;
+pub mod registry;
+pub mod serde;
 
 #[cfg(feature = "envelope")]
 pub mod envelope;
+#[cfg(feature = "graphql")]
+pub mod graphql;
 mod id;
+#[cfg(feature = "testing")]
+pub mod testing;
 
-pub use id::{Entity, Id, IdGenerator};
-pub use label::Label;
-pub use labeling::{CustomLabeling, Labeling, MakeLabeling, NoLabeling};
+pub use id::labeled;
+pub use id::{
+    AsRawId, AsyncEntity, AsyncIdGenerator, DefaultIdGenerator, Entity, FallbackGenerator,
+    FallbackId, Id, IdBytes, IdBytesError, IdGenError, IdGenerator, IdParseError, IdRef, Immediate,
+    NilId, RawId, RawIdError, ResourceName, ResourceNameBuilder, ResourceNameError, ScopedId,
+    TimeOrderedIdGenerator, TimestampedId, TryIdGenerator, UrnError, RESOURCE_NAME_SCHEME,
+    SCOPE_SEPARATOR, URN_SCHEME,
+};
+pub use label::{accepts_label, register_label_alias, Label};
+pub use labeling::{
+    validate_label, CustomLabeling, LabelError, LabelNamespace, Labeling, MakeLabeling, NoLabeling,
+    StaticLabeling,
+};
+
+/// A snapshot of the entities registered via [`manifest::register()`].
+pub fn manifest() -> manifest::Manifest {
+    manifest::Manifest::snapshot()
+}
 
 #[cfg(feature = "cuid")]
-pub use id::{CuidGenerator, CuidId};
+pub use id::{CuidGenerator, CuidId, SharedCuidGenerator, SharedCuidId};
 
 #[cfg(feature = "uuid")]
-pub use id::UuidGenerator;
+pub use id::{UuidFormat, UuidGenerator, UuidV7Generator};
+
+#[cfg(feature = "nanoid")]
+pub use id::{NanoIdAlphabet, NanoIdGenerator, UrlSafeAlphabet};
+
+#[cfg(feature = "ksuid")]
+pub use id::{Ksuid, KsuidError, KsuidGenerator};
+
+#[cfg(feature = "objectid")]
+pub use id::{objectid, ObjectIdGenerator};
+
+#[cfg(feature = "typeid")]
+pub use id::{typeid, TypeIdError, TypeIdGenerator};
+
+#[cfg(feature = "ulid")]
+pub use id::{Ulid, UlidError, UlidGenerator};
+
+#[cfg(all(feature = "ulid", feature = "sqlx"))]
+pub use id::UlidStorage;
+
+#[cfg(feature = "prost")]
+pub use id::{IdProtoError, ProtoBytes};
+
+#[cfg(feature = "zeroize")]
+pub use id::SensitiveId;
+
+#[cfg(feature = "sqids")]
+pub use id::{ObfuscatedIdGenerator, Obfuscation, ObfuscationError, Obfuscator};
+
+#[cfg(feature = "actix-web")]
+pub use id::TypedIdError;
+
+#[cfg(feature = "axum")]
+pub use id::TypedIdRejection;
 
 #[cfg(feature = "snowflake")]
-pub use id::snowflake::{self, MachineNode, SnowflakeGenerator};
+pub use id::{snowflake, MachineNode, SnowflakeGenerator};
+
+#[doc(hidden)]
+pub mod __private {
+    #[cfg(feature = "registry")]
+    pub use inventory;
+    pub use once_cell::sync::Lazy;
+    pub use serde;
+    #[cfg(feature = "sqlx")]
+    pub use sqlx;
+}
 
+/// The default [`Label::DELIMITER`] between the label and id of labeled ids.
 pub const DELIMITER: &str = "::";
@@ -0,0 +1,284 @@
+//! An opt-in registry of the entities in a binary, exportable as a serializable [`Manifest`].
+//!
+//! Entities are registered explicitly at startup via [`register()`]; the resulting manifest lists
+//! each entity's label, id representation and generator, which can be published to generate
+//! documentation or compared via [`Manifest::diff()`] to detect drift between services sharing
+//! an event bus. Registered entities are also checked for shared labels by the
+//! [`LabelRegistry`](crate::registry::LabelRegistry), which keeps its labels alongside them.
+//!
+#![cfg_attr(all(feature = "derive", feature = "cuid"), doc = "```rust")]
+#![cfg_attr(not(all(feature = "derive", feature = "cuid")), doc = "```ignore")]
+//! use tagid::{CuidGenerator, Entity, Label};
+//!
+//! #[derive(Label)]
+//! struct Order;
+//! impl Entity for Order { type IdGen = CuidGenerator; }
+//!
+//! tagid::manifest::register::<Order>();
+//! let manifest = tagid::manifest();
+//! let order = manifest.get("Order").unwrap();
+//! assert_eq!(order.id_kind, "String");
+//! assert_eq!(order.generator, "CuidGenerator");
+//! ```
+
+use crate::{Entity, IdGenerator, Label, Labeling};
+use once_cell::sync::Lazy;
+use pretty_type_name::pretty_type_name;
+use serde::{Deserialize, Serialize};
+use smol_str::SmolStr;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::RwLock;
+
+/// The registered types by type name: entities registered via [`register()`] along with their
+/// manifest entry, and types whose label alone was registered in the label registry.
+static REGISTRY: Lazy<RwLock<BTreeMap<&'static str, Registration>>> =
+    Lazy::new(|| RwLock::new(BTreeMap::new()));
+
+struct Registration {
+    label: SmolStr,
+    entry: Option<ManifestEntry>,
+}
+
+/// Describes how a registered entity is identified.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub label: String,
+    pub entity: String,
+    pub id_kind: String,
+    pub generator: String,
+}
+
+impl ManifestEntry {
+    pub fn of<E: ?Sized + Entity>() -> Self {
+        let labeler = <E as Label>::labeler();
+        Self {
+            label: labeler.label().to_string(),
+            entity: pretty_type_name::<E>(),
+            id_kind: pretty_type_name::<<E::IdGen as IdGenerator>::IdType>(),
+            generator: pretty_type_name::<E::IdGen>(),
+        }
+    }
+}
+
+/// Registers the entity in the global manifest. Returns `false` if the entity was already
+/// registered.
+pub fn register<E: ?Sized + Entity>() -> bool {
+    let entry = ManifestEntry::of::<E>();
+    let registration = Registration {
+        label: SmolStr::new(&entry.label),
+        entry: Some(entry),
+    };
+    let mut registry = REGISTRY.write().expect("manifest registry lock poisoned");
+    let previous = registry.insert(std::any::type_name::<E>(), registration);
+    drop(registry);
+    previous
+        .and_then(|registration| registration.entry)
+        .is_none()
+}
+
+/// Registers the label a type renders, unless the type is already registered.
+pub(crate) fn register_label(type_name: &'static str, label: SmolStr) {
+    REGISTRY
+        .write()
+        .expect("manifest registry lock poisoned")
+        .entry(type_name)
+        .or_insert(Registration { label, entry: None });
+}
+
+/// The registered types rendering each non-empty label.
+pub(crate) fn types_by_label() -> BTreeMap<SmolStr, BTreeSet<&'static str>> {
+    let mut by_label: BTreeMap<_, BTreeSet<_>> = BTreeMap::new();
+    for (type_name, registration) in REGISTRY
+        .read()
+        .expect("manifest registry lock poisoned")
+        .iter()
+        .filter(|(_, registration)| !registration.label.is_empty())
+    {
+        by_label
+            .entry(registration.label.clone())
+            .or_default()
+            .insert(*type_name);
+    }
+    by_label
+}
+
+/// A snapshot of the registered entities, ordered by label.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Manifest {
+    entries: Vec<ManifestEntry>,
+}
+
+impl Manifest {
+    /// The current contents of the global registry.
+    pub fn snapshot() -> Self {
+        let mut entries: Vec<_> = REGISTRY
+            .read()
+            .expect("manifest registry lock poisoned")
+            .values()
+            .filter_map(|registration| registration.entry.clone())
+            .collect();
+        entries.sort();
+        Self { entries }
+    }
+
+    pub fn entries(&self) -> &[ManifestEntry] {
+        &self.entries
+    }
+
+    pub fn get(&self, label: &str) -> Option<&ManifestEntry> {
+        self.entries.iter().find(|e| e.label == label)
+    }
+
+    /// Compares the manifest against another (e.g., one published by a peer service), keyed by
+    /// label.
+    pub fn diff(&self, other: &Self) -> ManifestDiff {
+        let mut diff = ManifestDiff::default();
+
+        for entry in &self.entries {
+            match other.get(&entry.label) {
+                None => diff.removed.push(entry.clone()),
+                Some(theirs) if theirs != entry => {
+                    diff.changed.push((entry.clone(), theirs.clone()))
+                }
+                Some(_) => {}
+            }
+        }
+
+        for theirs in &other.entries {
+            if self.get(&theirs.label).is_none() {
+                diff.added.push(theirs.clone());
+            }
+        }
+
+        diff
+    }
+}
+
+impl IntoIterator for Manifest {
+    type Item = ManifestEntry;
+    type IntoIter = std::vec::IntoIter<ManifestEntry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+/// Differences between two manifests, from the perspective of the manifest `diff()` was called
+/// on.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestDiff {
+    /// Entries only present in the other manifest.
+    pub added: Vec<ManifestEntry>,
+
+    /// Entries only present in this manifest.
+    pub removed: Vec<ManifestEntry>,
+
+    /// Entries sharing a label but differing otherwise, as `(ours, theirs)`.
+    pub changed: Vec<(ManifestEntry, ManifestEntry)>,
+}
+
+impl ManifestDiff {
+    pub const fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CustomLabeling, MakeLabeling};
+    use pretty_assertions::assert_eq;
+
+    struct SeqGenerator;
+
+    impl IdGenerator for SeqGenerator {
+        type IdType = u64;
+
+        fn next_id_rep() -> Self::IdType {
+            0
+        }
+    }
+
+    struct Invoice;
+
+    impl Label for Invoice {
+        type Labeler = MakeLabeling<Self>;
+
+        fn labeler() -> Self::Labeler {
+            MakeLabeling::default()
+        }
+    }
+
+    impl Entity for Invoice {
+        type IdGen = SeqGenerator;
+    }
+
+    struct Payment;
+
+    impl Label for Payment {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("pmt")
+        }
+    }
+
+    impl Entity for Payment {
+        type IdGen = SeqGenerator;
+    }
+
+    #[test]
+    fn test_register_and_snapshot() {
+        assert!(register::<Invoice>());
+        assert!(!register::<Invoice>());
+        register::<Payment>();
+
+        let manifest = Manifest::snapshot();
+        let invoice = manifest.get("Invoice").unwrap();
+        assert_eq!(
+            invoice,
+            &ManifestEntry {
+                label: "Invoice".to_string(),
+                entity: "Invoice".to_string(),
+                id_kind: "u64".to_string(),
+                generator: "SeqGenerator".to_string(),
+            }
+        );
+        assert_eq!(manifest.get("pmt").unwrap().entity, "Payment");
+
+        let json = serde_json::to_value(&manifest).unwrap();
+        assert!(json.as_array().is_some());
+    }
+
+    #[test]
+    fn test_manifest_diff() {
+        let ours = Manifest {
+            entries: vec![
+                ManifestEntry::of::<Invoice>(),
+                ManifestEntry::of::<Payment>(),
+            ],
+        };
+        assert!(ours.diff(&ours).is_empty());
+
+        let mut changed_payment = ManifestEntry::of::<Payment>();
+        changed_payment.id_kind = "Uuid".to_string();
+        let extra = ManifestEntry {
+            label: "Refund".to_string(),
+            entity: "Refund".to_string(),
+            id_kind: "String".to_string(),
+            generator: "CuidGenerator".to_string(),
+        };
+        let theirs = Manifest {
+            entries: vec![changed_payment.clone(), extra.clone()],
+        };
+
+        let diff = ours.diff(&theirs);
+        assert_eq!(diff.added, vec![extra]);
+        assert_eq!(diff.removed, vec![ManifestEntry::of::<Invoice>()]);
+        assert_eq!(
+            diff.changed,
+            vec![(ManifestEntry::of::<Payment>(), changed_payment)]
+        );
+    }
+}
@@ -0,0 +1,275 @@
+//! An opt-in global registry of entity labels, to catch distinct types that render their ids
+//! under the same label, e.g., two `Account` entities from different crates.
+//!
+//! Labels register explicitly via [`LabelRegistry::register()`], or, with the `registry` feature,
+//! every non-generic `#[derive(Label)]` type registers itself when [`LabelRegistry::init()`] runs
+//! at startup. The registry shares its store with the [`manifest`](mod@crate::manifest), so entities
+//! registered there are checked as well.
+//!
+#![cfg_attr(feature = "derive", doc = "```rust")]
+#![cfg_attr(not(feature = "derive"), doc = "```ignore")]
+//! use tagid::registry::LabelRegistry;
+//! use tagid::Label;
+//!
+//! mod billing {
+//!     #[derive(tagid::Label)]
+//!     pub struct Account;
+//! }
+//!
+//! mod identity {
+//!     #[derive(tagid::Label)]
+//!     pub struct Account;
+//! }
+//!
+//! LabelRegistry::register::<billing::Account>().unwrap();
+//! let collision = LabelRegistry::register::<identity::Account>().unwrap_err();
+//! assert_eq!(collision.label, "Account");
+//! assert_eq!(collision.type_names.len(), 2);
+//! ```
+
+use crate::{manifest, Label, Labeling};
+use smol_str::SmolStr;
+use std::fmt;
+use thiserror::Error;
+
+/// A label rendered by more than one type.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("label `{label}` is shared by {}", .type_names.join(", "))]
+pub struct LabelCollision {
+    pub label: String,
+    pub type_names: Vec<&'static str>,
+}
+
+/// The label collisions found by [`LabelRegistry::init()`].
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub struct LabelCollisions(pub Vec<LabelCollision>);
+
+impl fmt::Display for LabelCollisions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "duplicate entity labels: ")?;
+        for (i, collision) in self.0.iter().enumerate() {
+            if 0 < i {
+                write!(f, "; ")?;
+            }
+            write!(f, "{collision}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A label registration submitted by `#[derive(Label)]` with the `registry` feature.
+#[cfg(feature = "registry")]
+#[doc(hidden)]
+pub struct LabelRegistration {
+    label: fn() -> SmolStr,
+    type_name: fn() -> &'static str,
+}
+
+#[cfg(feature = "registry")]
+impl LabelRegistration {
+    pub const fn of<T: ?Sized + Label>() -> Self {
+        Self {
+            label: label_of::<T>,
+            type_name: std::any::type_name::<T>,
+        }
+    }
+}
+
+fn label_of<T: ?Sized + Label>() -> SmolStr {
+    <T as Label>::labeler().label_smol()
+}
+
+#[cfg(feature = "registry")]
+inventory::collect!(LabelRegistration);
+
+/// Registers the type of a `#[derive(Label)]` when the `registry` feature is enabled.
+#[cfg(feature = "registry")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __register_label {
+    ($t:ty) => {
+        $crate::__private::inventory::submit! {
+            $crate::registry::LabelRegistration::of::<$t>()
+        }
+    };
+}
+
+#[cfg(not(feature = "registry"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __register_label {
+    ($t:ty) => {};
+}
+
+/// The global registry of labels and the types rendering them.
+pub struct LabelRegistry;
+
+impl LabelRegistry {
+    /// Registers the labels of all `#[derive(Label)]` types linked into the binary, failing with
+    /// every label shared by more than one type. Without the `registry` feature this only checks
+    /// the explicitly registered labels.
+    pub fn init() -> Result<(), LabelCollisions> {
+        #[cfg(feature = "registry")]
+        for registration in inventory::iter::<LabelRegistration> {
+            Self::insert((registration.label)(), (registration.type_name)());
+        }
+
+        let collisions = Self::collisions();
+        if collisions.is_empty() {
+            Ok(())
+        } else {
+            Err(LabelCollisions(collisions))
+        }
+    }
+
+    /// Registers `T`'s label, failing if another type already registered it. Unlabeled types are
+    /// ignored.
+    pub fn register<T: ?Sized + Label>() -> Result<(), LabelCollision> {
+        let label = label_of::<T>();
+        if label.is_empty() {
+            return Ok(());
+        }
+
+        let type_names = Self::insert(label.clone(), std::any::type_name::<T>());
+        if type_names.len() == 1 {
+            Ok(())
+        } else {
+            Err(LabelCollision {
+                label: label.to_string(),
+                type_names,
+            })
+        }
+    }
+
+    fn insert(label: SmolStr, type_name: &'static str) -> Vec<&'static str> {
+        manifest::register_label(type_name, label.clone());
+        Self::type_names(&label)
+    }
+
+    fn type_names(label: &str) -> Vec<&'static str> {
+        manifest::types_by_label()
+            .remove(label)
+            .map(|type_names| type_names.into_iter().collect())
+            .unwrap_or_default()
+    }
+
+    /// The type registered under `label`, unless there is none or several.
+    pub fn type_name(label: &str) -> Option<&'static str> {
+        match Self::type_names(label).as_slice() {
+            [type_name] => Some(*type_name),
+            _ => None,
+        }
+    }
+
+    /// Every registered label shared by more than one type, ordered by label.
+    pub fn collisions() -> Vec<LabelCollision> {
+        manifest::types_by_label()
+            .into_iter()
+            .filter(|(_, type_names)| 1 < type_names.len())
+            .map(|(label, type_names)| LabelCollision {
+                label: label.to_string(),
+                type_names: type_names.into_iter().collect(),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CustomLabeling, Entity, IdGenerator};
+    use claim::*;
+    use pretty_assertions::assert_eq;
+
+    struct Ledger;
+
+    impl Label for Ledger {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("RegistryLedger")
+        }
+    }
+
+    struct OtherLedger;
+
+    impl Label for OtherLedger {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("RegistryLedger")
+        }
+    }
+
+    struct Journal;
+
+    impl Label for Journal {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("RegistryJournal")
+        }
+    }
+
+    impl Entity for Journal {
+        type IdGen = Self;
+    }
+
+    impl IdGenerator for Journal {
+        type IdType = u64;
+
+        fn next_id_rep() -> Self::IdType {
+            0
+        }
+    }
+
+    struct OtherJournal;
+
+    impl Label for OtherJournal {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("RegistryJournal")
+        }
+    }
+
+    #[test]
+    fn test_label_registry_includes_manifest_entities() {
+        assert!(manifest::register::<Journal>());
+        assert_eq!(
+            LabelRegistry::type_name("RegistryJournal"),
+            Some(std::any::type_name::<Journal>())
+        );
+
+        let collision = assert_err!(LabelRegistry::register::<OtherJournal>());
+        assert_eq!(collision.type_names.len(), 2);
+        assert!(crate::manifest().get("RegistryJournal").is_some());
+    }
+
+    #[test]
+    fn test_label_registry() {
+        assert_ok!(LabelRegistry::register::<Ledger>());
+        assert_ok!(LabelRegistry::register::<Ledger>());
+        assert_ok!(LabelRegistry::register::<()>());
+        assert_eq!(
+            LabelRegistry::type_name("RegistryLedger"),
+            Some(std::any::type_name::<Ledger>())
+        );
+
+        let collision = assert_err!(LabelRegistry::register::<OtherLedger>());
+        assert_eq!(collision.label, "RegistryLedger");
+        assert_eq!(
+            collision.to_string(),
+            "label `RegistryLedger` is shared by tagid::registry::tests::Ledger, \
+             tagid::registry::tests::OtherLedger"
+        );
+        assert_eq!(LabelRegistry::type_name("RegistryLedger"), None);
+        assert!(LabelRegistry::collisions().contains(&collision));
+
+        let collisions = assert_err!(LabelRegistry::init());
+        assert!(collisions.0.contains(&collision));
+        assert!(collisions
+            .to_string()
+            .starts_with("duplicate entity labels: "));
+    }
+}
@@ -0,0 +1,309 @@
+//! Modules for picking the wire format of individual `Id` fields via `#[serde(with = ...)]`.
+//!
+//! - [`id_string`]: the bare id as a string, e.g., `"42"` for numeric ids that must survive
+//!   JavaScript clients.
+//! - [`id_labeled`]: the labeled `Label::id` form, rejecting other entities' labels.
+//! - [`id_lenient`]: the bare id, also accepting the labeled form when deserializing, e.g., while
+//!   older clients still send labeled ids.
+//! - [`option_id`]: an optional id in the labeled form, with `None` as `null`.
+//! - [`structured`]: a self-describing `{ "label": ..., "id": ... }` struct.
+//!
+#![cfg_attr(feature = "derive", doc = "```rust")]
+#![cfg_attr(not(feature = "derive"), doc = "```ignore")]
+//! use serde::{Deserialize, Serialize};
+//! use tagid::{Id, Label};
+//!
+//! #[derive(Label)]
+//! struct User;
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Comment {
+//!     #[serde(with = "tagid::serde::id_string")]
+//!     id: Id<Comment, u64>,
+//!     #[serde(with = "tagid::serde::id_labeled")]
+//!     author: Id<User, u64>,
+//!     #[serde(with = "tagid::serde::option_id", default)]
+//!     editor: Option<Id<User, u64>>,
+//! }
+//!
+//! impl Label for Comment {
+//!     type Labeler = tagid::StaticLabeling;
+//!
+//!     fn labeler() -> Self::Labeler {
+//!         tagid::StaticLabeling::new("Comment")
+//!     }
+//! }
+//!
+//! let comment: Comment =
+//!     serde_json::from_str(r#"{ "id": "7", "author": "User::42", "editor": null }"#).unwrap();
+//! assert_eq!(comment.id.id, 7);
+//! assert_eq!(comment.author.id, 42);
+//! assert_eq!(comment.editor, None);
+//! ```
+
+/// (De)serializes an id as its bare id rendered as a string.
+pub mod id_string {
+    use crate::{Id, Label};
+    use serde::{de, Deserialize, Deserializer, Serializer};
+    use std::borrow::Cow;
+    use std::fmt;
+    use std::str::FromStr;
+
+    pub fn serialize<T, ID, S>(id: &Id<T, ID>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: ?Sized,
+        ID: fmt::Display,
+        S: Serializer,
+    {
+        serializer.collect_str(&id.id)
+    }
+
+    pub fn deserialize<'de, T, ID, D>(deserializer: D) -> Result<Id<T, ID>, D::Error>
+    where
+        T: ?Sized + Label,
+        ID: FromStr,
+        <ID as FromStr>::Err: fmt::Display,
+        D: Deserializer<'de>,
+    {
+        let rep = Cow::<'de, str>::deserialize(deserializer)?;
+        ID::from_str(&rep)
+            .map(Id::for_labeled)
+            .map_err(de::Error::custom)
+    }
+}
+
+/// (De)serializes an id in its labeled `Label::id` form; the same as [`crate::labeled`].
+pub mod id_labeled {
+    pub use crate::labeled::{deserialize, deserialize_lenient, serialize};
+}
+
+/// Serializes an id as its bare id, deserializing from either the bare or labeled `Label::id` form.
+///
+/// A label, when present, must match `T` (or one of its aliases). Only string reps are inspected
+/// for a label, so numeric ids deserialize as usual.
+///
+#[cfg_attr(feature = "derive", doc = "```rust")]
+#[cfg_attr(not(feature = "derive"), doc = "```ignore")]
+/// use serde::Deserialize;
+/// use tagid::{Id, Label};
+///
+/// #[derive(Label)]
+/// struct User;
+///
+/// #[derive(Deserialize)]
+/// struct Request {
+///     #[serde(with = "tagid::serde::id_lenient")]
+///     user_id: Id<User, String>,
+/// }
+///
+/// let labeled: Request = serde_json::from_str(r#"{ "user_id": "User::abc123" }"#).unwrap();
+/// let bare: Request = serde_json::from_str(r#"{ "user_id": "abc123" }"#).unwrap();
+/// assert_eq!(labeled.user_id, bare.user_id);
+/// assert!(serde_json::from_str::<Request>(r#"{ "user_id": "Order::abc123" }"#).is_err());
+/// ```
+pub mod id_lenient {
+    pub use crate::id::lenient::{deserialize, serialize};
+}
+
+/// (De)serializes an optional id in its labeled `Label::id` form, with `None` as `null`.
+///
+/// Pair with `#[serde(default)]` to also accept a missing field.
+pub mod option_id {
+    use crate::{Id, Label};
+    use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+    use std::borrow::Cow;
+    use std::fmt;
+    use std::str::FromStr;
+
+    struct Labeled<'a, T: ?Sized, ID>(&'a Id<T, ID>);
+
+    impl<T: ?Sized, ID: fmt::Display> Serialize for Labeled<'_, T, ID> {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            self.0.serialize_labeled(serializer)
+        }
+    }
+
+    pub fn serialize<T, ID, S>(id: &Option<Id<T, ID>>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: ?Sized,
+        ID: fmt::Display,
+        S: Serializer,
+    {
+        match id {
+            Some(id) => serializer.serialize_some(&Labeled(id)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, T, ID, D>(deserializer: D) -> Result<Option<Id<T, ID>>, D::Error>
+    where
+        T: ?Sized + Label,
+        ID: FromStr,
+        <ID as FromStr>::Err: fmt::Display,
+        D: Deserializer<'de>,
+    {
+        Option::<Cow<'de, str>>::deserialize(deserializer)?
+            .map(|rep| Id::from_str(&rep))
+            .transpose()
+            .map_err(de::Error::custom)
+    }
+}
+
+/// (De)serializes an id as a struct with explicit `label` and `id` fields, for stores that need
+/// self-describing payloads readable without knowledge of the Rust type.
+///
+#[cfg_attr(feature = "derive", doc = "```rust")]
+#[cfg_attr(not(feature = "derive"), doc = "```ignore")]
+/// use serde::{Deserialize, Serialize};
+/// use tagid::{Id, Label};
+///
+/// #[derive(Label)]
+/// struct User;
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct AuditEntry {
+///     #[serde(with = "tagid::serde::structured")]
+///     actor: Id<User, u64>,
+/// }
+///
+/// let entry = AuditEntry { actor: Id::for_labeled(42) };
+/// let json = serde_json::to_string(&entry).unwrap();
+/// assert_eq!(json, r#"{"actor":{"label":"User","id":42}}"#);
+/// assert!(serde_json::from_str::<AuditEntry>(r#"{"actor":{"label":"Order","id":42}}"#).is_err());
+/// ```
+pub mod structured {
+    use crate::{accepts_label, Id, IdParseError, Label, Labeling};
+    use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+    use std::borrow::Cow;
+
+    #[derive(Serialize)]
+    struct LabeledRef<'a, ID> {
+        label: &'a str,
+        id: &'a ID,
+    }
+
+    #[derive(Deserialize)]
+    struct Labeled<'a, ID> {
+        #[serde(borrow)]
+        label: Cow<'a, str>,
+        id: ID,
+    }
+
+    pub fn serialize<T, ID, S>(id: &Id<T, ID>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: ?Sized,
+        ID: Serialize,
+        S: Serializer,
+    {
+        LabeledRef {
+            label: &id.label,
+            id: &id.id,
+        }
+        .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, T, ID, D>(deserializer: D) -> Result<Id<T, ID>, D::Error>
+    where
+        T: ?Sized + Label,
+        ID: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        let Labeled { label, id } = Labeled::<ID>::deserialize(deserializer)?;
+        if !accepts_label::<T>(&label) {
+            return Err(de::Error::custom(IdParseError::LabelMismatch {
+                expected: <T as Label>::labeler().label().to_string(),
+                actual: label.into_owned(),
+            }));
+        }
+        Ok(Id::for_labeled(id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CustomLabeling, Id, Label};
+    use claim::*;
+    use pretty_assertions::assert_eq;
+    use serde::{Deserialize, Serialize};
+
+    struct User;
+
+    impl Label for User {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("User")
+        }
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Review {
+        #[serde(with = "crate::serde::id_string")]
+        id: Id<User, u64>,
+        #[serde(with = "crate::serde::id_labeled")]
+        author: Id<User, u64>,
+        #[serde(with = "crate::serde::option_id", default)]
+        approver: Option<Id<User, u64>>,
+    }
+
+    #[test]
+    fn test_serde_with_modules() {
+        let review = Review {
+            id: Id::for_labeled(1),
+            author: Id::for_labeled(2),
+            approver: Some(Id::for_labeled(3)),
+        };
+        let json = assert_ok!(serde_json::to_string(&review));
+        assert_eq!(
+            json,
+            r#"{"id":"1","author":"User::2","approver":"User::3"}"#
+        );
+        assert_eq!(assert_ok!(serde_json::from_str::<Review>(&json)), review);
+
+        let review = Review {
+            approver: None,
+            ..review
+        };
+        let json = assert_ok!(serde_json::to_string(&review));
+        assert_eq!(json, r#"{"id":"1","author":"User::2","approver":null}"#);
+        assert_eq!(assert_ok!(serde_json::from_str::<Review>(&json)), review);
+
+        let actual: Review = assert_ok!(serde_json::from_str(r#"{"id":"1","author":"User::2"}"#));
+        assert_eq!(actual, review);
+        assert_err!(serde_json::from_str::<Review>(
+            r#"{"id":"1","author":"User::2","approver":"Order::3"}"#
+        ));
+        assert_err!(serde_json::from_str::<Review>(
+            r#"{"id":"User::1","author":"User::2"}"#
+        ));
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct AuditEntry {
+        #[serde(with = "crate::serde::structured")]
+        actor: Id<User, String>,
+    }
+
+    #[test]
+    fn test_structured_serde() {
+        let entry = AuditEntry {
+            actor: Id::for_labeled("u-17".to_string()),
+        };
+        let json = assert_ok!(serde_json::to_string(&entry));
+        assert_eq!(json, r#"{"actor":{"label":"User","id":"u-17"}}"#);
+        assert_eq!(assert_ok!(serde_json::from_str::<AuditEntry>(&json)), entry);
+
+        let err = assert_err!(serde_json::from_str::<AuditEntry>(
+            r#"{"actor":{"label":"Order","id":"u-17"}}"#
+        ));
+        assert!(err
+            .to_string()
+            .contains("id label `Order` does not match expected label `User`"));
+        assert_err!(serde_json::from_str::<AuditEntry>(
+            r#"{"actor":"User::u-17"}"#
+        ));
+    }
+}
@@ -0,0 +1,328 @@
+//! Deterministic id generation for tests.
+//!
+//! Entities whose `IdGen` is a [`MockIdGenerator`] (typically under `#[cfg(test)]`) draw ids from
+//! the [`MockSequence`] installed for the current thread, so snapshots and assertions over
+//! generated ids are stable:
+//!
+//! ```rust
+//! use tagid::testing::{MockIdGenerator, MockSequence};
+//! use tagid::{Entity, Label};
+//!
+//! #[derive(Label)]
+//! struct Order;
+//!
+//! impl Entity for Order {
+//!     type IdGen = MockIdGenerator<String>;
+//! }
+//!
+//! let _guard = MockIdGenerator::install(MockSequence::counter(1, |n| format!("order-{n}")));
+//! assert_eq!(Order::next_id().to_string(), "Order::order-1");
+//! assert_eq!(Order::next_id().to_string(), "Order::order-2");
+//! ```
+//!
+//! Sequences are installed per thread, keyed by id type, so tests running in parallel do not
+//! interfere. Tasks spawned onto other threads (e.g., a multi-threaded async runtime) do not see
+//! the sequence.
+//!
+//! Likewise, [`FixedClock`] and [`StepClock`] make the time deterministic once installed via
+//! [`clock::install()`].
+
+use crate::clock::{self, Clock};
+use crate::id::{IdGenError, IdGenerator, TryIdGenerator};
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+thread_local! {
+    static SEQUENCES: RefCell<HashMap<TypeId, Box<dyn Any>>> = RefCell::new(HashMap::new());
+}
+
+/// A deterministic sequence of ids.
+pub struct MockSequence<ID> {
+    ids: Box<dyn Iterator<Item = ID>>,
+}
+
+impl<ID> std::fmt::Debug for MockSequence<ID> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MockSequence").finish_non_exhaustive()
+    }
+}
+
+impl<ID: 'static> MockSequence<ID> {
+    /// Yields the given ids in order; generation fails once they are exhausted.
+    pub fn fixed(ids: impl IntoIterator<Item = ID> + 'static) -> Self {
+        Self {
+            ids: Box::new(ids.into_iter()),
+        }
+    }
+
+    /// Yields ids made from an incrementing counter, beginning at `start`.
+    pub fn counter(start: u64, make_id: impl FnMut(u64) -> ID + 'static) -> Self {
+        Self {
+            ids: Box::new((start..).map(make_id)),
+        }
+    }
+
+    /// Yields ids made from a pseudo-random sequence that is reproducible for a given `seed`.
+    pub fn seeded(seed: u64, make_id: impl FnMut(u64) -> ID + 'static) -> Self {
+        let randoms = std::iter::successors(Some(seed), |state| {
+            Some(state.wrapping_add(0x9e37_79b9_7f4a_7c15))
+        })
+        .skip(1)
+        .map(split_mix);
+
+        Self {
+            ids: Box::new(randoms.map(make_id)),
+        }
+    }
+}
+
+/// The SplitMix64 output function, which scrambles successive states into well-distributed values.
+const fn split_mix(state: u64) -> u64 {
+    let mut z = state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Generates ids from the [`MockSequence`] installed for the current thread.
+pub struct MockIdGenerator<ID> {
+    marker: PhantomData<fn() -> ID>,
+}
+
+impl<ID: 'static> MockIdGenerator<ID> {
+    /// Installs the sequence for the current thread until the returned guard is dropped, at which
+    /// point any previously installed sequence is restored.
+    ///
+    /// Sequences are matched by their exact id type, so annotate integer literals (e.g., `7_u64`)
+    /// where inference would otherwise pick `i32`.
+    #[must_use = "the sequence is uninstalled when the guard is dropped"]
+    pub fn install(sequence: MockSequence<ID>) -> MockGuard<ID> {
+        let previous = SEQUENCES.with(|sequences| {
+            sequences
+                .borrow_mut()
+                .insert(TypeId::of::<ID>(), Box::new(sequence))
+        });
+
+        MockGuard {
+            previous,
+            marker: PhantomData,
+        }
+    }
+
+    fn is_installed() -> bool {
+        SEQUENCES.with(|sequences| sequences.borrow().contains_key(&TypeId::of::<ID>()))
+    }
+}
+
+impl<ID: Send + 'static> TryIdGenerator for MockIdGenerator<ID> {
+    fn try_next_id_rep() -> Result<Self::IdType, IdGenError> {
+        SEQUENCES.with(|sequences| {
+            let mut sequences = sequences.borrow_mut();
+            let sequence = sequences
+                .get_mut(&TypeId::of::<ID>())
+                .and_then(|sequence| sequence.downcast_mut::<MockSequence<ID>>())
+                .ok_or(IdGenError::Unavailable("MockIdGenerator"))?;
+
+            sequence.ids.next().ok_or_else(|| {
+                IdGenError::Exhausted(format!(
+                    "mock sequence of {}",
+                    pretty_type_name::pretty_type_name::<ID>()
+                ))
+            })
+        })
+    }
+}
+
+impl<ID: Send + 'static> IdGenerator for MockIdGenerator<ID> {
+    type IdType = ID;
+
+    /// # Panics
+    /// Panics if no sequence is installed for the current thread or the sequence is exhausted.
+    fn next_id_rep() -> Self::IdType {
+        Self::try_next_id_rep().unwrap_or_else(|err| panic!("failed to generate mock id: {err}"))
+    }
+
+    fn is_available() -> bool {
+        Self::is_installed()
+    }
+}
+
+/// Uninstalls a [`MockSequence`] when dropped, restoring the sequence it replaced.
+pub struct MockGuard<ID: 'static> {
+    previous: Option<Box<dyn Any>>,
+    marker: PhantomData<fn() -> ID>,
+}
+
+impl<ID: 'static> Drop for MockGuard<ID> {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        // ignore drops during thread teardown, when the thread-local may already be destroyed
+        let _ = SEQUENCES.try_with(|sequences| {
+            let mut sequences = sequences.borrow_mut();
+            match previous {
+                Some(previous) => sequences.insert(TypeId::of::<ID>(), previous),
+                None => sequences.remove(&TypeId::of::<ID>()),
+            }
+        });
+    }
+}
+
+/// A clock standing still at the time it is set to, until moved explicitly.
+///
+/// Generators that wait for the clock to advance, e.g., a snowflake generator after exhausting a
+/// millisecond's sequence, never finish under a fixed clock; use a [`StepClock`] instead.
+#[derive(Debug)]
+pub struct FixedClock {
+    now: Mutex<SystemTime>,
+}
+
+impl FixedClock {
+    pub const fn new(now: SystemTime) -> Self {
+        Self {
+            now: Mutex::new(now),
+        }
+    }
+
+    /// Installs a shared fixed clock for the current thread, returning the clock along with the
+    /// guard, so the test can move it.
+    pub fn install(now: SystemTime) -> (Arc<Self>, clock::ClockGuard) {
+        let clock = Arc::new(Self::new(now));
+        let guard = clock::install(Arc::clone(&clock));
+        (clock, guard)
+    }
+
+    pub fn set(&self, now: SystemTime) {
+        *self.lock() = now;
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        *self.lock() += duration;
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, SystemTime> {
+        self.now
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> SystemTime {
+        *self.lock()
+    }
+}
+
+/// A clock advancing by a fixed step each time it is read, beginning at `start`.
+#[derive(Debug)]
+pub struct StepClock {
+    next: Mutex<SystemTime>,
+    step: Duration,
+}
+
+impl StepClock {
+    pub const fn new(start: SystemTime, step: Duration) -> Self {
+        Self {
+            next: Mutex::new(start),
+            step,
+        }
+    }
+}
+
+impl Clock for StepClock {
+    fn now(&self) -> SystemTime {
+        let mut next = self
+            .next
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let now = *next;
+        *next += self.step;
+        now
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CustomLabeling, Entity, Label};
+    use claim::*;
+    use pretty_assertions::assert_eq;
+
+    struct Order;
+
+    impl Label for Order {
+        type Labeler = CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            CustomLabeling::new("Order")
+        }
+    }
+
+    impl Entity for Order {
+        type IdGen = MockIdGenerator<u64>;
+    }
+
+    #[test]
+    fn test_fixed_sequence() {
+        let _guard = MockIdGenerator::install(MockSequence::fixed([7_u64, 3]));
+        assert_eq!(Order::next_id().id, 7);
+        assert_eq!(Order::next_id().id, 3);
+        assert_matches!(Order::try_next_id(), Err(IdGenError::Exhausted(_)));
+    }
+
+    #[test]
+    fn test_seeded_sequence_is_reproducible() {
+        let draw = |seed| {
+            let _guard = MockIdGenerator::install(MockSequence::seeded(seed, |n| n));
+            (0..5).map(|_| Order::next_id().id).collect::<Vec<_>>()
+        };
+
+        let ids = draw(42);
+        assert_eq!(ids, draw(42));
+        assert_ne!(ids, draw(43));
+        assert_eq!(
+            ids.iter().collect::<std::collections::HashSet<_>>().len(),
+            5
+        );
+    }
+
+    #[test]
+    fn test_guard_restores_previous_sequence() {
+        assert!(!MockIdGenerator::<u64>::is_available());
+        assert_matches!(Order::try_next_id(), Err(IdGenError::Unavailable(_)));
+
+        let outer = MockIdGenerator::install(MockSequence::counter(1, |n| n));
+        assert_eq!(Order::next_id().id, 1);
+        {
+            let _inner = MockIdGenerator::install(MockSequence::fixed([100_u64]));
+            assert_eq!(Order::next_id().id, 100);
+        }
+        assert_eq!(Order::next_id().id, 2);
+
+        drop(outer);
+        assert!(!MockIdGenerator::<u64>::is_available());
+    }
+
+    #[test]
+    fn test_fixed_and_step_clocks() {
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_669_779_798);
+
+        let (fixed, guard) = FixedClock::install(start);
+        assert_eq!(clock::now(), start);
+        assert_eq!(clock::now(), start);
+        fixed.advance(Duration::from_secs(60));
+        assert_eq!(clock::now(), start + Duration::from_secs(60));
+        fixed.set(start);
+        assert_eq!(clock::now(), start);
+        drop(guard);
+        assert_ne!(clock::now(), start);
+
+        let _guard = clock::install(StepClock::new(start, Duration::from_millis(5)));
+        assert_eq!(clock::now(), start);
+        assert_eq!(clock::now(), start + Duration::from_millis(5));
+        assert_eq!(clock::now(), start + Duration::from_millis(10));
+    }
+}
@@ -1,15 +1,630 @@
+use heck::{ToKebabCase, ToLowerCamelCase, ToShoutySnakeCase, ToSnakeCase, ToUpperCamelCase};
 use proc_macro::{self, TokenStream};
+use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
-use syn::DeriveInput;
+use syn::ext::IdentExt;
+use syn::{
+    Attribute, Data, DeriveInput, Field, Fields, GenericArgument, Index, Lit, Member, Meta,
+    NestedMeta, PathArguments, Type,
+};
 
-#[proc_macro_derive(Label)]
+#[proc_macro_derive(Label, attributes(label))]
 pub fn label_derive(input: TokenStream) -> TokenStream {
-    let DeriveInput { ident, .. } = syn::parse_macro_input!(input);
-    let output = quote! {
-        impl ::tagid::Label for #ident {
+    let input: DeriveInput = syn::parse_macro_input!(input);
+    label_impl(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derives both `Label` and `Entity`, with the generator given by `#[entity(id_gen = "...")]`.
+#[proc_macro_derive(Entity, attributes(entity, label))]
+pub fn entity_derive(input: TokenStream) -> TokenStream {
+    let input: DeriveInput = syn::parse_macro_input!(input);
+    let output = label_impl(&input).and_then(|label| {
+        let entity = entity_impl(&input)?;
+        Ok(quote! { #label #entity })
+    });
+    output.unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+/// Derives `envelope::Correlation` from the field marked `#[correlation]`, or else the
+/// `correlation_id` field, which must be an `Id<T, ID>`.
+#[proc_macro_derive(Correlation, attributes(correlation))]
+pub fn correlation_derive(input: TokenStream) -> TokenStream {
+    let input: DeriveInput = syn::parse_macro_input!(input);
+    correlation_impl(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derives `envelope::IntoEnvelope` for a message carrying its metadata and content in the fields
+/// marked `#[envelope(metadata)]` and `#[envelope(content)]`, or else named `metadata` and
+/// `content`. The generator defaults to the content entity's, unless declared via
+/// `#[envelope(id_gen = "...")]`. Other fields are dropped by `into_envelope()`.
+#[proc_macro_derive(IntoEnvelope, attributes(envelope))]
+pub fn into_envelope_derive(input: TokenStream) -> TokenStream {
+    let input: DeriveInput = syn::parse_macro_input!(input);
+    into_envelope_impl(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derives the conversions of a newtype wrapping an `Id<T, ID>`, e.g., `struct UserId(Id<User,
+/// Uuid>)`: `Deref` to and `From` the id (and back), `Display`, serde and (with tagid's `sqlx`
+/// feature) sqlx passthrough to the id, and `next()` generating the id of the `Entity` `T`.
+#[proc_macro_derive(TypedId)]
+pub fn typed_id_derive(input: TokenStream) -> TokenStream {
+    let input: DeriveInput = syn::parse_macro_input!(input);
+    typed_id_impl(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn label_impl(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let LabelAttrs {
+        label,
+        rename_all,
+        aliases,
+        delimiter,
+        generics,
+    } = LabelAttrs::parse(&input.attrs)?;
+    let name = ident.unraw().to_string();
+    let label = label.or_else(|| rename_all.map(|case| case.apply(&name)));
+    let type_params: Vec<_> = input.generics.type_params().map(|p| &p.ident).collect();
+    let mut label_where_clause = where_clause.cloned();
+
+    let labeling = match (generics.unwrap_or(GenericsRule::Include), label) {
+        (GenericsRule::Delegate, label) if !type_params.is_empty() => {
+            let base = label.unwrap_or_else(|| name.clone());
+            let predicates = &mut label_where_clause
+                .get_or_insert_with(|| syn::parse_quote! { where })
+                .predicates;
+            for param in &type_params {
+                predicates.push(syn::parse_quote! { #param: ::tagid::Label });
+            }
+
+            quote! {
+                type Labeler = ::tagid::CustomLabeling;
+                fn labeler() -> Self::Labeler {
+                    let params: &[::std::string::String] = &[
+                        #(::tagid::Labeling::label(&<#type_params as ::tagid::Label>::labeler()).to_string()),*
+                    ];
+                    ::tagid::CustomLabeling::new(::std::format!("{}<{}>", #base, params.join(",")))
+                }
+            }
+        }
+        // the included type arguments of generic types are only named at runtime
+        (GenericsRule::Include, None) if !input.generics.params.is_empty() => quote! {
             type Labeler = ::tagid::MakeLabeling<Self>;
             fn labeler() -> Self::Labeler { ::tagid::MakeLabeling::default() }
+        },
+        (_, label) => {
+            let label = label.unwrap_or(name);
+            quote! {
+                type Labeler = ::tagid::StaticLabeling;
+                const LABEL: ::core::option::Option<&'static str> = ::core::option::Option::Some(#label);
+                fn labeler() -> Self::Labeler { ::tagid::StaticLabeling::new(#label) }
+            }
+        }
+    };
+
+    let aliases_fn = if aliases.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            fn aliases() -> &'static [&'static str] { &[#(#aliases),*] }
         }
     };
-    output.into()
-}
\ No newline at end of file
+
+    let delimiter_const = delimiter.map_or_else(
+        || quote! {},
+        |delimiter| quote! { const DELIMITER: &'static str = #delimiter; },
+    );
+
+    // generic types have no single label to register
+    let registration = if input.generics.params.is_empty() {
+        quote! { ::tagid::__register_label!(#ident); }
+    } else {
+        quote! {}
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::tagid::Label for #ident #ty_generics #label_where_clause {
+            #labeling
+            #delimiter_const
+            #aliases_fn
+        }
+
+        #registration
+    })
+}
+
+fn entity_impl(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let id_gen = entity_id_gen(input)?;
+
+    Ok(quote! {
+        impl #impl_generics ::tagid::Entity for #ident #ty_generics #where_clause {
+            type IdGen = #id_gen;
+        }
+    })
+}
+
+fn typed_id_impl(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let ident = &input.ident;
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "TypedId can only be derived for non-generic structs",
+        ));
+    }
+
+    let (member, field) = match struct_fields(input, "TypedId")?.as_slice() {
+        [(member, field)] => (member.clone(), *field),
+        _ => {
+            return Err(syn::Error::new_spanned(
+                ident,
+                "deriving TypedId requires a struct with a single `Id<T, ID>` field",
+            ))
+        }
+    };
+    let id_ty = &field.ty;
+    let (entity, _) = id_type_args(id_ty, "TypedId")?;
+
+    Ok(quote! {
+        impl #ident {
+            /// Generates the next id of the entity.
+            pub fn next() -> Self {
+                Self::from(<#entity as ::tagid::Entity>::next_id())
+            }
+        }
+
+        impl ::core::ops::Deref for #ident {
+            type Target = #id_ty;
+
+            fn deref(&self) -> &Self::Target {
+                &self.#member
+            }
+        }
+
+        impl ::core::convert::From<#id_ty> for #ident {
+            fn from(id: #id_ty) -> Self {
+                Self { #member: id }
+            }
+        }
+
+        impl ::core::convert::From<#ident> for #id_ty {
+            fn from(id: #ident) -> Self {
+                id.#member
+            }
+        }
+
+        impl ::core::fmt::Display for #ident {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                ::core::fmt::Display::fmt(&self.#member, f)
+            }
+        }
+
+        impl ::tagid::__private::serde::Serialize for #ident {
+            fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+            where
+                S: ::tagid::__private::serde::Serializer,
+            {
+                ::tagid::__private::serde::Serialize::serialize(&self.#member, serializer)
+            }
+        }
+
+        impl<'de> ::tagid::__private::serde::Deserialize<'de> for #ident {
+            fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
+            where
+                D: ::tagid::__private::serde::Deserializer<'de>,
+            {
+                <#id_ty as ::tagid::__private::serde::Deserialize<'de>>::deserialize(deserializer)
+                    .map(Self::from)
+            }
+        }
+
+        ::tagid::__typed_id_sqlx!(#ident, #id_ty);
+    })
+}
+
+fn correlation_impl(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let fields = struct_fields(input, "Correlation")?;
+    let (member, field) = fields
+        .iter()
+        .find(|(_, field)| {
+            field
+                .attrs
+                .iter()
+                .any(|attr| attr.path.is_ident("correlation"))
+        })
+        .or_else(|| {
+            fields
+                .iter()
+                .find(|(member, _)| is_named(member, "correlation_id"))
+        })
+        .ok_or_else(|| {
+            syn::Error::new_spanned(
+                ident,
+                "deriving Correlation requires a `#[correlation]` or `correlation_id` field",
+            )
+        })?;
+    let (correlated, id_type) = id_type_args(&field.ty, "correlation")?;
+
+    Ok(quote! {
+        impl #impl_generics ::tagid::envelope::Correlation for #ident #ty_generics #where_clause {
+            type Correlated = #correlated;
+            type IdType = #id_type;
+
+            fn correlation(&self) -> &::tagid::Id<Self::Correlated, Self::IdType> {
+                &self.#member
+            }
+        }
+    })
+}
+
+fn into_envelope_impl(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let fields = struct_fields(input, "IntoEnvelope")?;
+    let required = |role: &str| {
+        envelope_field(&fields, role)?.ok_or_else(|| {
+            syn::Error::new_spanned(
+                ident,
+                format!(
+                    "deriving IntoEnvelope requires an `#[envelope({role})]` or `{role}` field"
+                ),
+            )
+        })
+    };
+    let (metadata, _) = required("metadata")?;
+    let (content, content_field) = required("content")?;
+    let content_ty = &content_field.ty;
+    let id_gen = match id_gen_attr(&input.attrs, "envelope")? {
+        Some(id_gen) => quote! { #id_gen },
+        None => quote! { <#content_ty as ::tagid::Entity>::IdGen },
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::tagid::envelope::IntoEnvelope for #ident #ty_generics #where_clause {
+            type Content = #content_ty;
+            type IdGen = #id_gen;
+
+            fn into_envelope(
+                self,
+            ) -> ::tagid::envelope::Envelope<
+                Self::Content,
+                <Self::IdGen as ::tagid::IdGenerator>::IdType,
+            > {
+                ::tagid::envelope::Envelope::from_parts(self.#metadata, self.#content)
+            }
+
+            fn metadata(
+                &self,
+            ) -> &::tagid::envelope::MetaData<
+                Self::Content,
+                <Self::IdGen as ::tagid::IdGenerator>::IdType,
+            > {
+                &self.#metadata
+            }
+        }
+    })
+}
+
+/// The `#[label("...", rename_all = "...", alias = "...", delimiter = "...", generics = "...")]`
+/// declarations on the type. An explicit label takes precedence over `rename_all`.
+#[derive(Default)]
+struct LabelAttrs {
+    label: Option<String>,
+    rename_all: Option<RenameRule>,
+    aliases: Vec<String>,
+    delimiter: Option<String>,
+    generics: Option<GenericsRule>,
+}
+
+impl LabelAttrs {
+    const EXPECTED: &'static str = "#[label(\"...\", rename_all = \"...\", alias = \"...\", \
+                                    delimiter = \"...\", generics = \"...\")]";
+
+    fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut result = Self::default();
+
+        for nested in nested_meta(attrs, "label", Self::EXPECTED)? {
+            match nested {
+                NestedMeta::Lit(lit) => {
+                    if result.label.is_some() {
+                        return Err(syn::Error::new_spanned(lit, "duplicate label"));
+                    }
+                    result.label = Some(string_value(&lit, "label")?);
+                }
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("alias") => {
+                    result.aliases.push(string_value(&nv.lit, "label alias")?);
+                }
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("delimiter") => {
+                    if result.delimiter.is_some() {
+                        return Err(syn::Error::new_spanned(nv, "duplicate label delimiter"));
+                    }
+                    let delimiter = string_value(&nv.lit, "label delimiter")?;
+                    if delimiter.is_empty() {
+                        return Err(syn::Error::new_spanned(
+                            &nv.lit,
+                            "label delimiter must not be empty",
+                        ));
+                    }
+                    result.delimiter = Some(delimiter);
+                }
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("generics") => {
+                    if result.generics.is_some() {
+                        return Err(syn::Error::new_spanned(nv, "duplicate label generics"));
+                    }
+                    let rule = string_value(&nv.lit, "label generics")?;
+                    let rule = GenericsRule::from_str(&rule).ok_or_else(|| {
+                        syn::Error::new_spanned(
+                            &nv.lit,
+                            format!(
+                                "unknown generics rule `{rule}`; expected one of: {}",
+                                GenericsRule::NAMES.join(", ")
+                            ),
+                        )
+                    })?;
+                    result.generics = Some(rule);
+                }
+                NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("rename_all") => {
+                    let rule = string_value(&nv.lit, "label rename_all")?;
+                    let rule = RenameRule::from_str(&rule).ok_or_else(|| {
+                        syn::Error::new_spanned(
+                            &nv.lit,
+                            format!(
+                                "unknown rename_all rule `{rule}`; expected one of: {}",
+                                RenameRule::NAMES.join(", ")
+                            ),
+                        )
+                    })?;
+                    result.rename_all = Some(rule);
+                }
+                other => {
+                    return Err(syn::Error::new_spanned(
+                        other,
+                        format!("unsupported label attribute; expected `{}`", Self::EXPECTED),
+                    ))
+                }
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+/// How `#[label(generics = "...")]` labels generic types: `include` the type names of their type
+/// arguments, as in `Page<UserRecord>`, the default unless the label is explicit; `delegate` to
+/// the labels of their type arguments, as in `Page<account>` for `UserRecord` labeled `account`; or
+/// `strip` them, as in `Page`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GenericsRule {
+    Include,
+    Delegate,
+    Strip,
+}
+
+impl GenericsRule {
+    const NAMES: [&'static str; 3] = ["include", "delegate", "strip"];
+
+    fn from_str(rule: &str) -> Option<Self> {
+        match rule {
+            "include" => Some(Self::Include),
+            "delegate" => Some(Self::Delegate),
+            "strip" => Some(Self::Strip),
+            _ => None,
+        }
+    }
+}
+
+/// Case conversions applied to the type name by `#[label(rename_all = "...")]`, following serde's
+/// naming.
+#[derive(Debug, Clone, Copy)]
+enum RenameRule {
+    Lower,
+    Upper,
+    Pascal,
+    Camel,
+    Snake,
+    ScreamingSnake,
+    Kebab,
+}
+
+impl RenameRule {
+    const NAMES: [&'static str; 7] = [
+        "lowercase",
+        "UPPERCASE",
+        "PascalCase",
+        "camelCase",
+        "snake_case",
+        "SCREAMING_SNAKE_CASE",
+        "kebab-case",
+    ];
+
+    fn from_str(rule: &str) -> Option<Self> {
+        match rule {
+            "lowercase" => Some(Self::Lower),
+            "UPPERCASE" => Some(Self::Upper),
+            "PascalCase" => Some(Self::Pascal),
+            "camelCase" => Some(Self::Camel),
+            "snake_case" => Some(Self::Snake),
+            "SCREAMING_SNAKE_CASE" => Some(Self::ScreamingSnake),
+            "kebab-case" => Some(Self::Kebab),
+            _ => None,
+        }
+    }
+
+    fn apply(self, name: &str) -> String {
+        match self {
+            Self::Lower => name.to_lowercase(),
+            Self::Upper => name.to_uppercase(),
+            Self::Pascal => name.to_upper_camel_case(),
+            Self::Camel => name.to_lower_camel_case(),
+            Self::Snake => name.to_snake_case(),
+            Self::ScreamingSnake => name.to_shouty_snake_case(),
+            Self::Kebab => name.to_kebab_case(),
+        }
+    }
+}
+
+/// Finds the generator declared via `#[entity(id_gen = "...")]`.
+fn entity_id_gen(input: &DeriveInput) -> syn::Result<Type> {
+    id_gen_attr(&input.attrs, "entity")?.ok_or_else(|| {
+        syn::Error::new_spanned(
+            &input.ident,
+            "deriving Entity requires an `#[entity(id_gen = \"...\")]` attribute",
+        )
+    })
+}
+
+/// The generator declared via `#[name(id_gen = "...")]`, if any.
+fn id_gen_attr(attrs: &[Attribute], name: &str) -> syn::Result<Option<Type>> {
+    let mut id_gen = None;
+
+    let expected = format!("#[{name}(id_gen = \"...\")]");
+    for nested in nested_meta(attrs, name, &expected)? {
+        match nested {
+            NestedMeta::Meta(Meta::NameValue(nv)) if nv.path.is_ident("id_gen") => {
+                let rep = string_value(&nv.lit, &format!("{name} id_gen"))?;
+                let ty = syn::parse_str::<Type>(&rep)
+                    .map_err(|err| syn::Error::new_spanned(&nv.lit, err))?;
+                id_gen = Some(ty);
+            }
+            other => {
+                return Err(syn::Error::new_spanned(
+                    other,
+                    format!("unsupported {name} attribute; expected `id_gen = \"...\"`"),
+                ))
+            }
+        }
+    }
+
+    Ok(id_gen)
+}
+
+/// Finds the field flagged `#[envelope(role)]`, or else the field named `role`.
+fn envelope_field<'a, 'f>(
+    fields: &'a [(Member, &'f Field)],
+    role: &str,
+) -> syn::Result<Option<&'a (Member, &'f Field)>> {
+    const EXPECTED: &str = "#[envelope(metadata)]` or `#[envelope(content)]";
+
+    for entry in fields {
+        for nested in nested_meta(&entry.1.attrs, "envelope", EXPECTED)? {
+            match nested {
+                NestedMeta::Meta(Meta::Path(path))
+                    if path.is_ident("metadata") || path.is_ident("content") =>
+                {
+                    if path.is_ident(role) {
+                        return Ok(Some(entry));
+                    }
+                }
+                other => {
+                    return Err(syn::Error::new_spanned(
+                        other,
+                        format!("unsupported envelope field attribute; expected `{EXPECTED}`"),
+                    ))
+                }
+            }
+        }
+    }
+
+    Ok(fields.iter().find(|(member, _)| is_named(member, role)))
+}
+
+/// The fields of the struct deriving `derive`, along with how each is accessed.
+fn struct_fields<'a>(
+    input: &'a DeriveInput,
+    derive: &str,
+) -> syn::Result<Vec<(Member, &'a Field)>> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                format!("{derive} can only be derived for structs"),
+            ))
+        }
+    };
+
+    Ok(match fields {
+        Fields::Named(named) => named
+            .named
+            .iter()
+            .map(|field| (Member::Named(field.ident.clone().unwrap()), field))
+            .collect(),
+        Fields::Unnamed(unnamed) => unnamed
+            .unnamed
+            .iter()
+            .enumerate()
+            .map(|(i, field)| (Member::Unnamed(Index::from(i)), field))
+            .collect(),
+        Fields::Unit => Vec::new(),
+    })
+}
+
+fn is_named(member: &Member, name: &str) -> bool {
+    matches!(member, Member::Named(ident) if ident == name)
+}
+
+/// The label and id type arguments of an `Id<T, ID>` field type.
+fn id_type_args<'t>(ty: &'t Type, what: &str) -> syn::Result<(&'t Type, &'t Type)> {
+    if let Type::Path(path) = ty {
+        if let Some(segment) = path.path.segments.last() {
+            if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                let types: Vec<&Type> = args
+                    .args
+                    .iter()
+                    .filter_map(|arg| match arg {
+                        GenericArgument::Type(ty) => Some(ty),
+                        _ => None,
+                    })
+                    .collect();
+                if let (true, [correlated, id_type]) = (segment.ident == "Id", types.as_slice()) {
+                    return Ok((correlated, id_type));
+                }
+            }
+        }
+    }
+
+    Err(syn::Error::new_spanned(
+        ty,
+        format!("the {what} field must be an `Id<T, ID>`"),
+    ))
+}
+
+/// The nested items of every `#[name(...)]` attribute.
+fn nested_meta(attrs: &[Attribute], name: &str, expected: &str) -> syn::Result<Vec<NestedMeta>> {
+    let mut nested = Vec::new();
+
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident(name)) {
+        match attr.parse_meta()? {
+            Meta::List(list) => nested.extend(list.nested),
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    format!("expected `{expected}`"),
+                ))
+            }
+        }
+    }
+
+    Ok(nested)
+}
+
+fn string_value(lit: &Lit, what: &str) -> syn::Result<String> {
+    match lit {
+        Lit::Str(value) => Ok(value.value()),
+        lit => Err(syn::Error::new_spanned(
+            lit,
+            format!("{what} must be a string literal"),
+        )),
+    }
+}
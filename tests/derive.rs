@@ -0,0 +1,314 @@
+#![cfg(feature = "derive")]
+
+use tagid::{accepts_label, Entity, Id, IdGenerator, Label, Labeling, TypedId};
+
+#[derive(Label)]
+#[label(alias = "Client", alias = "Patron")]
+struct Customer;
+
+#[derive(Label)]
+struct Order;
+
+#[derive(Label)]
+#[label("ord")]
+struct LabeledOrder;
+
+#[derive(Label)]
+#[label(rename_all = "snake_case")]
+struct PurchaseOrder;
+
+#[derive(Label)]
+#[label(rename_all = "kebab-case")]
+struct LineItem;
+
+#[derive(Label)]
+#[label(rename_all = "SCREAMING_SNAKE_CASE")]
+struct ShippingLabel;
+
+#[derive(Label)]
+#[label(rename_all = "lowercase", alias = "Refund")]
+struct CreditNote;
+
+#[derive(Label)]
+#[label("user", delimiter = "/")]
+struct LegacyUser;
+
+#[test]
+fn test_derive_label() {
+    assert_eq!(<Order as Label>::labeler().label(), "Order");
+    assert!(<Order as Label>::aliases().is_empty());
+}
+
+#[test]
+fn test_derive_custom_label() {
+    assert_eq!(<LabeledOrder as Label>::labeler().label(), "ord");
+    let id: Id<LabeledOrder, u64> = Id::for_labeled(3);
+    assert_eq!(id.to_string(), "ord::3");
+    assert_eq!("ord::3".parse::<Id<LabeledOrder, u64>>().unwrap(), id);
+}
+
+#[test]
+fn test_derive_label_rename_all() {
+    assert_eq!(
+        <PurchaseOrder as Label>::labeler().label(),
+        "purchase_order"
+    );
+    assert_eq!(<LineItem as Label>::labeler().label(), "line-item");
+    assert_eq!(
+        <ShippingLabel as Label>::labeler().label(),
+        "SHIPPING_LABEL"
+    );
+    assert_eq!(<CreditNote as Label>::labeler().label(), "creditnote");
+    assert!(accepts_label::<CreditNote>("Refund"));
+}
+
+#[test]
+fn test_derive_label_delimiter() {
+    assert_eq!(<Order as Label>::DELIMITER, "::");
+    assert_eq!(<LegacyUser as Label>::DELIMITER, "/");
+
+    let id: Id<LegacyUser, String> = Id::for_labeled("abc123".to_string());
+    assert_eq!(id.to_string(), "user/abc123");
+    assert_eq!(format!("{id:?}"), r#"user/"abc123""#);
+    assert_eq!("user/abc123".parse::<Id<LegacyUser, String>>().unwrap(), id);
+    assert!("user::abc123".parse::<Id<LegacyUser, String>>().is_err());
+
+    let parsed: Id<LegacyUser, String> = "user/abc123".parse().unwrap();
+    assert_eq!(parsed.to_string(), "user/abc123");
+    assert_eq!(parsed.delimiter(), "/");
+    assert_eq!(id.relabel::<Order>().to_string(), "Order::abc123");
+}
+
+#[test]
+fn test_derive_const_label() {
+    const LABELS: [Option<&str>; 3] = [Order::LABEL, LabeledOrder::LABEL, LineItem::LABEL];
+    assert_eq!(LABELS, [Some("Order"), Some("ord"), Some("line-item")]);
+
+    let kind = match Some("ord") {
+        Order::LABEL => "order",
+        LabeledOrder::LABEL => "labeled order",
+        _ => "unknown",
+    };
+    assert_eq!(kind, "labeled order");
+    assert_eq!(<Order as Label>::labeler().static_label(), Some("Order"));
+    assert_eq!(<Shipment<u8> as Label>::labeler().static_label(), None);
+    assert_eq!(<Shipment<u8> as Label>::LABEL, None);
+
+    assert_eq!(Inventory::LABEL, 7);
+    assert_eq!(<Inventory as Label>::LABEL, Some("Inventory"));
+    assert_eq!(<r#type as Label>::LABEL, Some("type"));
+    assert_eq!(<r#type as Label>::labeler().label(), "type");
+}
+
+#[derive(Label)]
+struct Inventory;
+
+impl Inventory {
+    const LABEL: u8 = 7;
+}
+
+#[allow(non_camel_case_types)]
+#[derive(Label)]
+struct r#type;
+
+#[derive(Label)]
+#[label("account")]
+struct UserRecord;
+
+#[derive(Label)]
+struct Page<T>(std::marker::PhantomData<T>);
+
+#[derive(Label)]
+#[label(generics = "delegate")]
+struct Batch<A, B>(std::marker::PhantomData<(A, B)>);
+
+#[derive(Label)]
+#[label(rename_all = "snake_case", generics = "strip")]
+struct SearchResults<T>(std::marker::PhantomData<T>);
+
+#[test]
+fn test_derive_generic_labels() {
+    assert_eq!(
+        <Page<UserRecord> as Label>::labeler().label(),
+        "Page<UserRecord>"
+    );
+    assert_eq!(
+        <Batch<UserRecord, Order> as Label>::labeler().label(),
+        "Batch<account,Order>"
+    );
+    assert_eq!(
+        Id::<Batch<UserRecord, LabeledOrder>, u64>::for_labeled(3).to_string(),
+        "Batch<account,ord>::3"
+    );
+    assert_eq!(
+        <SearchResults<UserRecord> as Label>::labeler().label(),
+        "search_results"
+    );
+    assert_eq!(SearchResults::<u8>::LABEL, Some("search_results"));
+}
+
+#[test]
+fn test_derive_label_aliases() {
+    assert_eq!(<Customer as Label>::labeler().label(), "Customer");
+    assert_eq!(<Customer as Label>::aliases(), &["Client", "Patron"]);
+    assert!(accepts_label::<Customer>("Client"));
+    assert!(accepts_label::<Customer>("Patron"));
+    assert!(!accepts_label::<Order>("Client"));
+
+    let id: Id<Customer, u64> = Id::from_urn("urn:acme:Client:42").unwrap();
+    assert_eq!(id.to_string(), "Customer::42");
+}
+
+#[derive(Entity)]
+#[entity(id_gen = "tagid::CuidGenerator")]
+#[label("invoice", alias = "Receipt")]
+struct Invoice;
+
+#[derive(Entity)]
+#[entity(id_gen = "SequenceGenerator")]
+struct Shipment<T> {
+    _contents: std::marker::PhantomData<T>,
+}
+
+struct SequenceGenerator;
+
+impl IdGenerator for SequenceGenerator {
+    type IdType = u64;
+
+    fn next_id_rep() -> Self::IdType {
+        7
+    }
+}
+
+#[test]
+fn test_derive_entity() {
+    let id = Invoice::next_id();
+    assert_eq!(id.label, "invoice");
+    assert!(accepts_label::<Invoice>("Receipt"));
+
+    let id: Id<Shipment<String>, u64> = Shipment::<String>::next_id();
+    assert_eq!(id.id, 7);
+    assert_eq!(id.label, "Shipment<String>");
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, TypedId)]
+struct InvoiceId(Id<Invoice, String>);
+
+#[derive(Debug, TypedId)]
+struct ShipmentId {
+    inner: Id<Shipment<u8>, u64>,
+}
+
+#[test]
+fn test_derive_typed_id() {
+    let id = InvoiceId::next();
+    assert_eq!(id.label, "invoice");
+    assert_eq!(id.to_string(), format!("invoice::{}", id.id));
+
+    let json = serde_json::to_string(&id).unwrap();
+    assert_eq!(json, format!("\"{}\"", id.id));
+    assert_eq!(serde_json::from_str::<InvoiceId>(&json).unwrap(), id);
+
+    let raw: Id<Invoice, String> = id.clone().into();
+    assert_eq!(InvoiceId::from(raw), id);
+
+    let shipment_id = ShipmentId::next();
+    assert_eq!(shipment_id.id, 7);
+    assert_eq!(shipment_id.to_string(), "Shipment<u8>::7");
+}
+
+#[cfg(feature = "registry")]
+#[test]
+fn test_derive_label_registration() {
+    use tagid::registry::LabelRegistry;
+
+    mod other {
+        #[derive(tagid::Label)]
+        #[label("ord")]
+        pub struct Ordinal;
+    }
+
+    let collisions = LabelRegistry::init().unwrap_err();
+    assert_eq!(collisions.0.len(), 1);
+    assert_eq!(collisions.0[0].label, "ord");
+    assert_eq!(
+        LabelRegistry::type_name("line-item"),
+        Some(std::any::type_name::<LineItem>())
+    );
+    assert_eq!(LabelRegistry::type_name("Shipment<String>"), None);
+}
+
+#[cfg(feature = "envelope")]
+mod envelope {
+    use super::*;
+    use iso8601_timestamp::Timestamp;
+    use tagid::envelope::{Correlation, IntoEnvelope, MetaData};
+
+    #[derive(Correlation)]
+    struct OrderPlaced {
+        correlation_id: Id<Order, u64>,
+        _total: u32,
+    }
+
+    #[derive(Correlation)]
+    struct OrderShipped(u32, #[correlation] Id<Order, String>);
+
+    #[test]
+    fn test_derive_correlation() {
+        let placed = OrderPlaced {
+            correlation_id: Id::for_labeled(17),
+            _total: 42,
+        };
+        assert_eq!(placed.correlation().id, 17);
+        assert_eq!(placed.correlation().label, "Order");
+
+        let shipped = OrderShipped(3, Id::for_labeled("ord-17".to_string()));
+        assert_eq!(shipped.correlation().id, "ord-17");
+        assert_eq!(shipped.0, 3);
+    }
+
+    #[derive(IntoEnvelope)]
+    struct InvoiceMessage {
+        metadata: MetaData<Invoice, String>,
+        content: Invoice,
+        _partition: u32,
+    }
+
+    #[derive(IntoEnvelope)]
+    #[envelope(id_gen = "SequenceGenerator")]
+    struct OrderMessage(
+        #[envelope(content)] OrderPlaced,
+        #[envelope(metadata)] MetaData<OrderPlaced, u64>,
+    );
+
+    impl Label for OrderPlaced {
+        type Labeler = tagid::CustomLabeling;
+
+        fn labeler() -> Self::Labeler {
+            tagid::CustomLabeling::new("OrderPlaced")
+        }
+    }
+
+    #[test]
+    fn test_derive_into_envelope() {
+        let metadata = MetaData::default();
+        let message = InvoiceMessage {
+            metadata: metadata.clone(),
+            content: Invoice,
+            _partition: 3,
+        };
+        assert_eq!(message.metadata(), &metadata);
+        let envelope = message.into_envelope();
+        assert_eq!(envelope.metadata(), &metadata);
+        assert_eq!(envelope.metadata().correlation().label, "invoice");
+
+        let placed = OrderPlaced {
+            correlation_id: Id::for_labeled(17),
+            _total: 42,
+        };
+        let metadata = MetaData::from_parts(Id::for_labeled(17), Timestamp::now_utc(), None);
+        let envelope = OrderMessage(placed, metadata).into_envelope();
+        assert_eq!(envelope.metadata().correlation().id, 17);
+        assert_eq!(envelope.correlation().id, 17);
+    }
+}